@@ -401,6 +401,9 @@ impl From<EpochError> for Error {
         match error {
             EpochError::EpochOutOfBounds(epoch_id) => Error::EpochOutOfBounds(epoch_id),
             EpochError::MissingBlock(h) => Error::DBNotFoundErr(format!("epoch block: {h}")),
+            EpochError::DBCorruption(msg) => {
+                Error::IOErr(io::Error::new(io::ErrorKind::InvalidData, msg))
+            }
             EpochError::NotAValidator(account_id, epoch_id) => {
                 Error::NotAValidator(format!("account_id: {account_id}, epoch_id: {epoch_id:?}"))
             }
@@ -444,6 +447,7 @@ impl From<ChunkAccessError> for Error {
     fn from(error: ChunkAccessError) -> Self {
         match error {
             ChunkAccessError::ChunkMissing(chunk_hash) => Error::ChunkMissing(chunk_hash),
+            ChunkAccessError::IOError(err) => Error::IOErr(err),
         }
     }
 }
@@ -8,10 +8,12 @@ use near_epoch_manager::EpochManagerAdapter;
 use near_primitives::block::Block;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::get_block_shard_uid;
+use near_primitives::sharding::ChunkHash;
 use near_primitives::state_sync::{StateHeaderKey, StatePartKey};
 use near_primitives::types::{BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId};
 use near_primitives::utils::{get_block_shard_id, get_outcome_id_block_hash, index_to_bytes};
 use near_store::adapter::{StoreAdapter, StoreUpdateAdapter};
+use near_store::keys::{BlockInfoKey, ColumnKey};
 use near_store::{DBCol, KeyForStateChanges, ShardTries, ShardUId};
 
 use crate::types::RuntimeAdapter;
@@ -632,7 +634,7 @@ impl<'a> ChainStoreUpdate<'a> {
         self.gc_outcomes(&block)?;
         match gc_mode {
             GCMode::StateSync { clear_block_info: false } => {}
-            _ => self.gc_col(DBCol::BlockInfo, block_hash.as_bytes()),
+            _ => self.gc_col_by_key(&BlockInfoKey(&block_hash)),
         }
         self.gc_col(DBCol::StateDlInfos, block_hash.as_bytes());
 
@@ -731,7 +733,7 @@ impl<'a> ChainStoreUpdate<'a> {
         }
         self.gc_col(DBCol::BlockRefCount, block_hash.as_bytes());
         self.gc_outcomes(&block)?;
-        self.gc_col(DBCol::BlockInfo, block_hash.as_bytes());
+        self.gc_col_by_key(&BlockInfoKey(&block_hash));
         self.gc_col(DBCol::StateDlInfos, block_hash.as_bytes());
 
         // 3. update columns related to prev block (block refcount and NextBlockHashes)
@@ -848,6 +850,10 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    fn gc_col_by_key(&mut self, key: &impl ColumnKey) {
+        self.gc_col(key.column(), &key.key_bytes());
+    }
+
     fn gc_col(&mut self, col: DBCol, key: &[u8]) {
         let mut store_update = self.store().store_update();
         match col {
@@ -912,7 +918,8 @@ impl<'a> ChainStoreUpdate<'a> {
                 self.chain_store().chunk_extras.pop(key);
             }
             DBCol::PartialChunks => {
-                store_update.delete(col, key);
+                let chunk_hash = ChunkHash(CryptoHash::try_from(key).unwrap());
+                store_update.chunk_store_update().delete_partial_chunk(&chunk_hash);
                 self.chain_store().partial_chunks.pop(key);
             }
             DBCol::InvalidChunks => {
@@ -968,6 +975,8 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::BlockHeight  // block sync needs it + genesis should be accessible
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::BannedPeers
+            | DBCol::KnownPeers
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
@@ -993,6 +1002,8 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::EpochSyncProof
             | DBCol::Misc
             | DBCol::_ReceiptIdToShardId
+            // Deleted together with DBCol::PartialChunks.
+            | DBCol::PartialChunkParts
             => unreachable!(),
         }
         self.merge(store_update);
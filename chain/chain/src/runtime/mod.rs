@@ -42,13 +42,13 @@ use near_store::config::StateSnapshotType;
 use near_store::flat::FlatStorageManager;
 use near_store::metadata::DbKind;
 use near_store::{
-    ApplyStatePartResult, DBCol, ShardTries, StateSnapshotConfig, Store, Trie, TrieConfig,
-    TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
+    ApplyStatePartResult, DBCol, ShardTries, StateSnapshotConfig, Store, StoreUpdate, Trie,
+    TrieConfig, TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
 };
 use near_vm_runner::ContractCode;
 use near_vm_runner::{precompile_contract, ContractRuntimeCache, FilesystemContractRuntimeCache};
 use node_runtime::adapter::ViewRuntimeAdapter;
-use node_runtime::state_viewer::{TrieViewer, ViewApplyState};
+use node_runtime::state_viewer::{TrieViewer, ViewApplyState, ViewStatePage};
 use node_runtime::{
     validate_transaction, verify_and_charge_transaction, ApplyState, Runtime,
     ValidatorAccountsUpdate,
@@ -1115,13 +1115,18 @@ impl RuntimeAdapter for NightshadeRuntime {
                     block_hash: *block_hash,
                 })
             }
-            QueryRequest::ViewState { account_id, prefix, include_proof } => {
+            QueryRequest::ViewState { account_id, prefix, include_proof, after_key, limit } => {
+                let page = ViewStatePage {
+                    after_key: after_key.as_ref().map(|key| key.as_slice()),
+                    limit: limit.map(|limit| limit as usize),
+                };
                 let view_state_result = self
                     .view_state(
                         &shard_uid,
                         *state_root,
                         account_id,
                         prefix.as_ref(),
+                        page,
                         *include_proof,
                     )
                     .map_err(|err| {
@@ -1251,7 +1256,11 @@ impl RuntimeAdapter for NightshadeRuntime {
         //       Optimize taking into account that flat state values always correspond to a consecutive range of keys.
         flat_state_delta.apply_to_flat_state(&mut store_update.flat_store_update(), shard_uid);
         self.precompile_contracts(epoch_id, contract_codes)?;
-        Ok(store_update.commit()?)
+        // Parts can be large.  Committing them in batches is fine since state
+        // which is only partially applied is never used: a node restarting
+        // state sync clears the state before applying the parts again.
+        let store_update: StoreUpdate = store_update.into();
+        Ok(store_update.commit_in_batches()?)
     }
 
     /// `block_hash` is a block whose `prev_state_root` is `state_root`
@@ -1542,9 +1551,10 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         state_root: MerkleHash,
         account_id: &AccountId,
         prefix: &[u8],
+        page: ViewStatePage<'_>,
         include_proof: bool,
     ) -> Result<ViewStateResult, node_runtime::state_viewer::errors::ViewStateError> {
         let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
-        self.trie_viewer.view_state(&state_update, account_id, prefix, include_proof)
+        self.trie_viewer.view_state(&state_update, account_id, prefix, page, include_proof)
     }
 }
@@ -1133,11 +1133,11 @@ impl ChainStoreAccess for ChainStore {
 
     /// Get partial chunk.
     fn get_partial_chunk(&self, chunk_hash: &ChunkHash) -> Result<Arc<PartialEncodedChunk>, Error> {
-        match self.read_with_cache(DBCol::PartialChunks, &self.partial_chunks, chunk_hash.as_ref())
-        {
-            Ok(Some(shard_chunk)) => Ok(shard_chunk),
-            _ => Err(Error::ChunkMissing(chunk_hash.clone())),
-        }
+        let partial_chunk =
+            self.partial_chunks.get_or_try_put(chunk_hash.as_ref().to_vec(), |_| {
+                self.store.chunk_store().get_partial_chunk(chunk_hash)
+            })?;
+        Ok(partial_chunk)
     }
 
     /// Does this full block exist?
@@ -2366,12 +2366,8 @@ impl<'a> ChainStoreUpdate<'a> {
                     &hash_set,
                 )?;
             }
-            for (chunk_hash, partial_chunk) in self.chain_store_cache_update.partial_chunks.iter() {
-                store_update.insert_ser(
-                    DBCol::PartialChunks,
-                    chunk_hash.as_ref(),
-                    partial_chunk,
-                )?;
+            for partial_chunk in self.chain_store_cache_update.partial_chunks.values() {
+                store_update.chunk_store_update().insert_partial_chunk(partial_chunk)?;
             }
         }
 
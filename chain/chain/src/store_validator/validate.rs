@@ -11,6 +11,7 @@ use near_primitives::transaction::{ExecutionOutcomeWithProof, SignedTransaction}
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{BlockHeight, EpochId};
 use near_primitives::utils::{get_block_shard_id, get_outcome_id_block_hash, index_to_bytes};
+use near_store::keys::BlockInfoKey;
 use near_store::{
     DBCol, TrieChanges, CHUNK_TAIL_KEY, FORK_TAIL_KEY, HEADER_HEAD_KEY, HEAD_KEY, TAIL_KEY,
 };
@@ -428,7 +429,7 @@ pub(crate) fn block_info_exists(
     _block: &Block,
 ) -> Result<(), StoreValidatorError> {
     unwrap_or_err_db!(
-        sv.store.get_ser::<BlockInfo>(DBCol::BlockInfo, block_hash.as_ref()),
+        sv.store.get_ser_by_key::<BlockInfo>(&BlockInfoKey(block_hash)),
         "Can't get BlockInfo from storage"
     );
     Ok(())
@@ -442,7 +443,7 @@ pub(crate) fn block_epoch_exists(
     // TODO #2893: why?
     /*
     unwrap_or_err_db!(
-        sv.store.get_ser::<EpochInfo>(DBCol::EpochInfo, block.header().epoch_id().as_ref()),
+        sv.store.get_ser_by_key::<EpochInfo>(&EpochInfoKey::epoch(block.header().epoch_id())),
         "Can't get EpochInfo from storage"
     );
     */
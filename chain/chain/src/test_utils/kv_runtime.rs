@@ -1355,6 +1355,7 @@ impl RuntimeAdapter for KeyValueRuntime {
                 kind: QueryResponseKind::ViewState(ViewStateResult {
                     values: Default::default(),
                     proof: vec![],
+                    last_key: None,
                 }),
                 block_height,
                 block_hash: *block_hash,
@@ -20,8 +20,9 @@ use near_primitives::shard_layout::ShardUId;
 use near_primitives::test_utils::{create_test_signer, TestBlockBuilder};
 use near_primitives::types::{BlockHeight, NumBlocks, StateRoot};
 use near_primitives::validator_signer::ValidatorSigner;
+use near_store::keys::BlockInfoKey;
 use near_store::test_utils::gen_changes;
-use near_store::{DBCol, ShardTries, Trie, WrappedTrieChanges};
+use near_store::{ShardTries, Trie, WrappedTrieChanges};
 
 // Build a chain of num_blocks on top of prev_block
 fn do_fork(
@@ -861,7 +862,7 @@ fn test_clear_old_data_too_many_heights_common(gc_blocks_limit: NumBlocks) {
     {
         let mut store_update = chain.chain_store().store().store_update();
         let block_info = BlockInfo::default();
-        store_update.insert_ser(DBCol::BlockInfo, prev_block.hash().as_ref(), &block_info).unwrap();
+        store_update.insert_ser_by_key(&BlockInfoKey(prev_block.hash()), &block_info).unwrap();
         store_update.commit().unwrap();
     }
     for i in 1..1000 {
@@ -877,7 +878,7 @@ fn test_clear_old_data_too_many_heights_common(gc_blocks_limit: NumBlocks) {
         {
             let mut store_update = store_update.store().store_update();
             let block_info = BlockInfo::default();
-            store_update.insert_ser(DBCol::BlockInfo, block.hash().as_ref(), &block_info).unwrap();
+            store_update.insert_ser_by_key(&BlockInfoKey(block.hash()), &block_info).unwrap();
             store_update.commit().unwrap();
         }
         store_update
@@ -23,6 +23,9 @@ use tracing::warn;
 //    This functionality is meant for block producers. When producing a block, the block producer
 //    will only include chunks in the block for which it has received the part it owns.
 //    Users of the data structure are responsible for adding chunk to this map at the right time.
+// 4) It stores the set of chunk parts forwarded to or by this node, so that the same part is not
+//    reprocessed or forwarded again when it arrives from multiple peers. Unlike the chunk entries,
+//    the parts are kept until they go out of horizon, even if the chunk is complete.
 
 /// A chunk is out of rear horizon if its height + HEIGHT_HORIZON < largest_seen_height
 const HEIGHT_HORIZON: BlockHeightDelta = 1024;
@@ -65,6 +68,9 @@ pub struct EncodedChunksCache {
     /// A map from a block hash to a set of incomplete chunks (does not have all parts and receipts yet)
     /// whose previous block is the block hash.
     incomplete_chunks: HashMap<CryptoHash, HashSet<ChunkHash>>,
+    /// A map from a block height to the (chunk hash, part ord) of the parts forwarded to or by
+    /// this node for chunks at this height.
+    seen_parts: HashMap<BlockHeight, HashSet<(ChunkHash, u64)>>,
 }
 
 impl EncodedChunksCacheEntry {
@@ -110,6 +116,7 @@ impl EncodedChunksCache {
             height_map: HashMap::new(),
             height_to_shard_to_chunk: HashMap::new(),
             incomplete_chunks: HashMap::new(),
+            seen_parts: HashMap::new(),
         }
     }
 
@@ -251,9 +258,32 @@ impl EncodedChunksCache {
                 }
             }
             self.height_to_shard_to_chunk.remove(&height);
+            self.seen_parts.remove(&height);
         }
     }
 
+    /// Returns true if the part of the chunk at the given height has been forwarded
+    /// to or by this node already.
+    pub fn is_part_seen(&self, height: BlockHeight, chunk_hash: &ChunkHash, part_ord: u64) -> bool {
+        self.seen_parts
+            .get(&height)
+            .is_some_and(|parts| parts.contains(&(chunk_hash.clone(), part_ord)))
+    }
+
+    /// Marks the part as forwarded to or by this node; returns false if it has been
+    /// marked already. Parts of chunks outside of horizon are not recorded.
+    pub fn mark_part_seen(
+        &mut self,
+        height: BlockHeight,
+        chunk_hash: &ChunkHash,
+        part_ord: u64,
+    ) -> bool {
+        if !self.height_within_horizon(height) {
+            return true;
+        }
+        self.seen_parts.entry(height).or_default().insert((chunk_hash.clone(), part_ord))
+    }
+
     /// Marks the chunk for inclusion in a block; returns true if we haven't already
     /// called for this chunk. Requires that the chunk is already in the cache.
     pub fn mark_chunk_for_inclusion(&mut self, chunk_hash: &ChunkHash) -> bool {
@@ -337,4 +367,21 @@ mod tests {
         assert!(cache.encoded_chunks.is_empty());
         assert!(cache.height_map.is_empty());
     }
+
+    #[test]
+    fn test_seen_parts() {
+        let mut cache = EncodedChunksCache::new();
+        cache.update_largest_seen_height::<ChunkRequestInfo>(1, &HashMap::default());
+        let chunk_hash = create_chunk_header(1, 0).chunk_hash();
+        assert!(!cache.is_part_seen(1, &chunk_hash, 0));
+        assert!(cache.mark_part_seen(1, &chunk_hash, 0));
+        assert!(!cache.mark_part_seen(1, &chunk_hash, 0));
+        assert!(cache.is_part_seen(1, &chunk_hash, 0));
+        assert!(!cache.is_part_seen(1, &chunk_hash, 1));
+
+        // Parts out of horizon are forgotten.
+        cache.update_largest_seen_height::<ChunkRequestInfo>(2000, &HashMap::default());
+        assert!(!cache.is_part_seen(1, &chunk_hash, 0));
+        assert!(cache.seen_parts.is_empty());
+    }
 }
@@ -55,6 +55,14 @@ pub static PARTIAL_ENCODED_CHUNK_FORWARD_CACHED_WITHOUT_HEADER: LazyLock<Counter
     },
 );
 
+pub static PARTIAL_ENCODED_CHUNK_FORWARD_DUPLICATE_PARTS: LazyLock<Counter> = LazyLock::new(|| {
+    near_o11y::metrics::try_create_counter(
+        "near_partial_encoded_chunk_forward_duplicate_parts",
+        "Number of forwarded chunk parts dropped because they have been seen already",
+    )
+    .unwrap()
+});
+
 pub static PARTIAL_ENCODED_CHUNK_FORWARD_CACHED_WITHOUT_PREV_BLOCK: LazyLock<Counter> =
     LazyLock::new(|| {
         near_o11y::metrics::try_create_counter(
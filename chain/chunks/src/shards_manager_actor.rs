@@ -466,13 +466,19 @@ impl ShardsManagerActor {
             && !request_from_archival
             && Some(&chunk_producer_account_id) != me
         {
-            Some(chunk_producer_account_id)
+            Some(chunk_producer_account_id.clone())
         } else {
             self.get_random_target_tracking_shard(ancestor_hash, shard_id, me)?
         };
 
         let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(ancestor_hash)?;
 
+        // If there is no route to the target account, the request is sent to the chunk
+        // producer and then to the block producer of the height, which both should have
+        // all the parts, rather than being dropped until the next retry.
+        let block_producer_account_id = self.epoch_manager.get_block_producer(&epoch_id, height)?;
+        let fallback_targets = [chunk_producer_account_id, block_producer_account_id];
+
         for part_ord in 0..self.epoch_manager.num_total_parts() {
             let part_ord = part_ord as u64;
             if cache_entry.is_some_and(|cache_entry| cache_entry.parts.contains_key(&part_ord)) {
@@ -539,8 +545,18 @@ impl ShardsManagerActor {
                         HashSet::new()
                     },
                 };
+                let mut fallback_account_ids = vec![];
+                for account_id in &fallback_targets {
+                    if Some(account_id) != target_account.as_ref()
+                        && Some(account_id) != me
+                        && !fallback_account_ids.contains(account_id)
+                    {
+                        fallback_account_ids.push(account_id.clone());
+                    }
+                }
                 let target = AccountIdOrPeerTrackingShard {
                     account_id: target_account,
+                    fallback_account_ids,
                     prefer_peer,
                     shard_id,
                     only_archival: request_from_archival,
@@ -943,9 +959,10 @@ impl ShardsManagerActor {
             return (PartialEncodedChunkResponseSource::InMemoryCache, response);
         }
 
-        // Try fetching partial encoded chunk from storage.
-        if let Ok(partial_chunk) = self.store.get_partial_chunk(&chunk_hash) {
-            Self::lookup_partial_encoded_chunk_from_partial_chunk_storage(
+        // Try fetching partial encoded chunk from storage.  Its parts are
+        // stored separately so only the requested ones need to be read.
+        if let Ok(partial_chunk) = self.store.get_partial_chunk_without_parts(&chunk_hash) {
+            self.lookup_partial_encoded_chunk_from_partial_chunk_storage(
                 part_ords,
                 tracking_shards,
                 &mut response,
@@ -1003,17 +1020,22 @@ impl ShardsManagerActor {
     }
 
     /// Looks up the given part_ords and tracking_shards from the partial chunks
-    /// storage, appending any we have found into the response, and deleting those we
-    /// have found from part_ords and tracking_shards.
+    /// storage, appending any we have found into the response.  `partial_chunk`
+    /// is the stored partial chunk without parts; the requested parts are read
+    /// from the storage one by one.
     fn lookup_partial_encoded_chunk_from_partial_chunk_storage(
+        &self,
         part_ords: HashSet<u64>,
         tracking_shards: HashSet<ShardId>,
         response: &mut PartialEncodedChunkResponseMsg,
         partial_chunk: &PartialEncodedChunk,
     ) {
-        for part in partial_chunk.parts() {
-            if part_ords.contains(&part.part_ord) {
-                response.parts.push(part.clone());
+        let chunk_hash = partial_chunk.chunk_hash();
+        let mut part_ords = part_ords.into_iter().collect::<Vec<_>>();
+        part_ords.sort_unstable();
+        for part_ord in part_ords {
+            if let Some(part) = self.store.get_partial_chunk_part(&chunk_hash, part_ord) {
+                response.parts.push(part);
             }
         }
         for receipt in partial_chunk.prev_outgoing_receipts() {
@@ -1276,12 +1298,37 @@ impl ShardsManagerActor {
 
     fn process_partial_encoded_chunk_forward(
         &mut self,
-        forward: PartialEncodedChunkForwardMsg,
+        mut forward: PartialEncodedChunkForwardMsg,
         me: Option<&AccountId>,
     ) -> Result<(), Error> {
-        let maybe_header = self
-            .validate_partial_encoded_chunk_forward(&forward)
-            .and_then(|_| self.get_partial_encoded_chunk_header(&forward.chunk_hash));
+        // The same part may be forwarded to us by multiple peers; skip the parts we have
+        // seen already, so that they are not validated and processed again.
+        let num_parts = forward.parts.len();
+        forward.parts.retain(|part| {
+            !self.encoded_chunks.is_part_seen(
+                forward.height_created,
+                &forward.chunk_hash,
+                part.part_ord,
+            )
+        });
+        metrics::PARTIAL_ENCODED_CHUNK_FORWARD_DUPLICATE_PARTS
+            .inc_by((num_parts - forward.parts.len()) as f64);
+        if forward.parts.is_empty() {
+            return Ok(());
+        }
+
+        let validated = self.validate_partial_encoded_chunk_forward(&forward);
+        if validated.is_ok() {
+            for part in &forward.parts {
+                self.encoded_chunks.mark_part_seen(
+                    forward.height_created,
+                    &forward.chunk_hash,
+                    part.part_ord,
+                );
+            }
+        }
+        let maybe_header =
+            validated.and_then(|_| self.get_partial_encoded_chunk_header(&forward.chunk_hash));
 
         let header = match maybe_header {
             Ok(header) => Ok(header),
@@ -1827,6 +1874,10 @@ impl ShardsManagerActor {
             Some(me) => me,
             None => return Ok(()),
         };
+        let height_created = partial_encoded_chunk.header.height_created();
+        let chunk_hash = partial_encoded_chunk.header.chunk_hash();
+        // Parts seen already are not forwarded again, e.g. if the chunk has been evicted
+        // from the cache and we received them once more.
         let owned_parts: Vec<_> = partial_encoded_chunk
             .parts
             .iter()
@@ -1836,6 +1887,11 @@ impl ShardsManagerActor {
                         .epoch_manager
                         .get_part_owner(epoch_id, part.part_ord)
                         .is_ok_and(|owner| &owner == me)
+                    && self.encoded_chunks.mark_part_seen(
+                        height_created,
+                        &chunk_hash,
+                        part.part_ord,
+                    )
             })
             .cloned()
             .collect();
@@ -2424,6 +2480,62 @@ mod test {
         assert_eq!(requested_parts, HashSet::new());
     }
 
+    #[test]
+    fn test_chunk_request_fallback_targets() {
+        // Requests should fall back to the chunk producer and then to the block producer,
+        // if there is no route to the target account.
+        let mut fixture = ChunkTestFixture::new(true, 3, 6, 1, true);
+        let clock = FakeClock::default();
+        let mut shards_manager = ShardsManagerActor::new(
+            clock.clock(),
+            mutable_validator_signer(&fixture.mock_shard_tracker),
+            Arc::new(fixture.epoch_manager.clone()),
+            Arc::new(fixture.epoch_manager.clone()),
+            fixture.shard_tracker.clone(),
+            fixture.mock_network.as_sender(),
+            fixture.mock_client_adapter.as_sender(),
+            fixture.store.clone(),
+            fixture.mock_chain_head.clone(),
+            fixture.mock_chain_head.clone(),
+            Duration::hours(1),
+        );
+        shards_manager.request_chunk_single(
+            &fixture.mock_chunk_header,
+            CryptoHash::default(),
+            false,
+            Some(&fixture.mock_shard_tracker),
+        );
+
+        let epoch_manager = &fixture.epoch_manager;
+        let epoch_id = epoch_manager.get_epoch_id_from_prev_block(&CryptoHash::default()).unwrap();
+        let height = fixture.mock_chunk_header.height_created();
+        let chunk_producer = epoch_manager
+            .get_chunk_producer(&epoch_id, height, fixture.mock_chunk_header.shard_id())
+            .unwrap();
+        let block_producer = epoch_manager.get_block_producer(&epoch_id, height).unwrap();
+        let me = &fixture.mock_shard_tracker;
+        let mut num_requests = 0;
+        while let Some(r) = fixture.mock_network.pop() {
+            let NetworkRequests::PartialEncodedChunkRequest { target, .. } =
+                r.as_network_requests_ref()
+            else {
+                continue;
+            };
+            num_requests += 1;
+            let mut want = vec![];
+            for account_id in [&chunk_producer, &block_producer] {
+                if account_id != me
+                    && Some(account_id) != target.account_id.as_ref()
+                    && !want.contains(&account_id)
+                {
+                    want.push(account_id);
+                }
+            }
+            assert_eq!(target.fallback_account_ids.iter().collect::<Vec<_>>(), want);
+        }
+        assert!(num_requests > 0);
+    }
+
     #[test]
     fn test_invalid_chunk() {
         // Test that process_partial_encoded_chunk will reject invalid chunk
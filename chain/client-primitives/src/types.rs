@@ -10,10 +10,11 @@ use near_primitives::types::{
 };
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, DownloadStatusView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
-    GasPriceView, LightClientBlockLiteView, LightClientBlockView, MaintenanceWindowsView,
-    QueryRequest, QueryResponse, ReceiptView, ShardSyncDownloadView, SplitStorageInfoView,
-    StateChangesKindsView, StateChangesRequestView, StateChangesView, SyncStatusView, TxStatusView,
+    BlockView, ChunkView, ContractLogsView, DownloadStatusView, EpochValidatorInfo,
+    ExecutionOutcomeWithIdView, GasPriceView, LightClientBlockLiteView, LightClientBlockView,
+    MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView, ShardSyncDownloadView,
+    SplitStorageInfoView, StateChangesKindsView, StateChangesRequestView, StateChangesView,
+    SyncStatusView, TxStatusView,
 };
 pub use near_primitives::views::{StatusResponse, StatusSyncInfo};
 use std::collections::HashMap;
@@ -594,6 +595,7 @@ pub enum QueryError {
 
 #[derive(Debug)]
 pub struct Status {
+    // If true - fail if the node is syncing or doesn't receive new blocks.
     pub is_health_check: bool,
     // If true - return more detailed information about the current status (recent blocks etc).
     pub detailed: bool,
@@ -730,6 +732,15 @@ pub struct PeerInfo {
     pub id: PeerId,
     pub addr: Option<std::net::SocketAddr>,
     pub account_id: Option<AccountId>,
+    /// Height of the latest block the peer told us about.
+    pub height: Option<BlockHeight>,
+    /// Milliseconds since we received the last message from the peer.
+    pub last_time_received_message_millis: u64,
+    pub received_bytes_per_sec: u64,
+    pub sent_bytes_per_sec: u64,
+    pub is_outbound_peer: bool,
+    /// Whether the peer has proven to be a current validator.
+    pub is_validator: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -1098,6 +1109,45 @@ impl From<near_chain_primitives::Error> for GetMaintenanceWindowsError {
     }
 }
 
+/// Logs emitted by executions on `account_id` in the blocks of the given
+/// (inclusive) range of heights. The range must lie between the chain tail
+/// and head. Logs are filtered out of the execution outcomes stored for each
+/// block, there is no per-account index, hence the limit on the range size.
+#[derive(Debug)]
+pub struct GetContractLogs {
+    pub account_id: AccountId,
+    pub from_height: BlockHeight,
+    pub to_height: BlockHeight,
+}
+
+impl Message for GetContractLogs {
+    type Result = Result<Vec<ContractLogsView>, GetContractLogsError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetContractLogsError {
+    #[error("Invalid block range {from_height}..={to_height}, at most {max_blocks} blocks can be requested")]
+    InvalidRange { from_height: BlockHeight, to_height: BlockHeight, max_blocks: u64 },
+    #[error("Block either has never been observed on the node or has been garbage collected: {0}")]
+    UnknownBlock(String),
+    #[error("Node doesn't track the shard {shard_id} of account {account_id}")]
+    UnavailableShard { account_id: AccountId, shard_id: ShardId },
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetContractLogsError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::DBNotFoundErr(s) => Self::UnknownBlock(s),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct GetClientConfig {}
 
@@ -55,7 +55,7 @@ use near_epoch_manager::EpochManagerAdapter;
 use near_network::client::ProcessTxResponse;
 use near_network::types::{AccountKeys, ChainInfo, PeerManagerMessageRequest, SetChainInfo};
 use near_network::types::{
-    HighestHeightPeerInfo, NetworkRequests, PeerManagerAdapter, ReasonForBan,
+    HighestHeightPeerInfo, NetworkRequests, PeerManagerAdapter, PeerOffense, ReasonForBan,
 };
 
 use near_pool::InsertTransactionResult;
@@ -1110,7 +1110,7 @@ impl Client {
 
         let res = self.receive_block_impl(
             block,
-            peer_id,
+            peer_id.clone(),
             was_requested,
             apply_chunks_done_sender,
             signer,
@@ -1120,6 +1120,7 @@ impl Client {
         if let Err(err) = res {
             if err.is_bad_data() {
                 warn!(target: "client", ?err, "Receive bad block");
+                self.report_peer(peer_id, PeerOffense::InvalidBlock);
             } else if err.is_error() {
                 if let near_chain::Error::DBNotFoundErr(msg) = &err {
                     debug_assert!(!msg.starts_with("BLOCK HEIGHT"), "{:?}", err);
@@ -2686,6 +2687,14 @@ impl Client {
             NetworkRequests::BanPeer { peer_id, ban_reason },
         ));
     }
+
+    /// Lowers reputation of the peer, which gets banned once it misbehaves
+    /// often enough.
+    pub fn report_peer(&self, peer_id: PeerId, offense: PeerOffense) {
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::ReportPeer { peer_id, offense },
+        ));
+    }
 }
 
 impl Client {
@@ -57,7 +57,7 @@ use near_network::client::{
 };
 use near_network::types::ReasonForBan;
 use near_network::types::{
-    NetworkInfo, NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest,
+    NetworkInfo, NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest, PeerType,
 };
 use near_performance_metrics;
 use near_performance_metrics_macros::perf;
@@ -241,6 +241,8 @@ pub struct ClientActorInner {
     node_id: PeerId,
     /// Last time we announced our accounts as validators.
     last_validator_announce_time: Option<Instant>,
+    /// Epoch for which we announced our accounts last time.
+    last_validator_announce_epoch: Option<EpochId>,
     /// Info helper.
     info_helper: InfoHelper,
 
@@ -376,6 +378,7 @@ impl ClientActorInner {
                 tier1_accounts_data: vec![],
             },
             last_validator_announce_time: None,
+            last_validator_announce_epoch: None,
             info_helper,
             block_production_next_attempt: now,
             log_summary_timer_next_attempt: now,
@@ -810,6 +813,8 @@ impl Handler<Status> for ClientActorInner {
                 earliest_block_time,
                 epoch_id: Some(head.epoch_id),
                 epoch_start_height,
+                latest_header_height: Some(self.client.chain.header_head()?.height),
+                num_connected_peers: Some(self.network_info.num_connected_peers),
             },
             validator_account_id,
             validator_public_key,
@@ -823,11 +828,23 @@ impl Handler<Status> for ClientActorInner {
 }
 
 /// Private to public API conversion.
-fn make_peer_info(from: near_network::types::PeerInfo) -> near_client_primitives::types::PeerInfo {
+fn make_peer_info(
+    now: Instant,
+    from: &near_network::types::ConnectedPeerInfo,
+) -> near_client_primitives::types::PeerInfo {
+    let full_peer_info = &from.full_peer_info;
     near_client_primitives::types::PeerInfo {
-        id: from.id,
-        addr: from.addr,
-        account_id: from.account_id,
+        id: full_peer_info.peer_info.id.clone(),
+        addr: full_peer_info.peer_info.addr,
+        account_id: full_peer_info.peer_info.account_id.clone(),
+        height: full_peer_info.chain_info.last_block.map(|x| x.height),
+        last_time_received_message_millis: (now - from.last_time_received_message)
+            .whole_milliseconds()
+            .max(0) as u64,
+        received_bytes_per_sec: from.received_bytes_per_sec,
+        sent_bytes_per_sec: from.sent_bytes_per_sec,
+        is_outbound_peer: from.peer_type == PeerType::Outbound,
+        is_validator: from.is_validator,
     }
 }
 
@@ -845,9 +862,10 @@ fn make_known_producer(
 
 impl Handler<GetNetworkInfo> for ClientActorInner {
     fn handle(&mut self, _msg: GetNetworkInfo) -> Result<NetworkInfoResponse, String> {
+        let now = self.clock.now();
         Ok(NetworkInfoResponse {
             connected_peers: (self.network_info.connected_peers.iter())
-                .map(|fpi| make_peer_info(fpi.full_peer_info.peer_info.clone()))
+                .map(|cpi| make_peer_info(now, cpi))
                 .collect(),
             num_connected_peers: self.network_info.num_connected_peers,
             peer_max_count: self.network_info.peer_max_count,
@@ -958,28 +976,33 @@ impl ClientActorInner {
             Some(signer) => signer,
         };
 
+        // Announce AccountId if client is becoming a validator soon.
+        let next_epoch_id = unwrap_or_return!(self
+            .client
+            .epoch_manager
+            .get_next_epoch_id_from_prev_block(&prev_block_hash));
+
         let now = self.clock.now();
-        // Check that we haven't announced it too recently
+        // Check that we haven't announced it too recently, unless the epoch has changed since.
         if let Some(last_validator_announce_time) = self.last_validator_announce_time {
-            // Don't make announcement if have passed less than half of the time in which other peers
-            // should remove our Account Id from their Routing Tables.
-            if 2 * (now - last_validator_announce_time) < self.client.config.ttl_account_id_router {
+            // Don't make announcement if have passed less than a third of the time in which other
+            // peers should remove our Account Id from their Routing Tables. Relays forward a refresh
+            // only once per half of that time, so announcing more often than that leaves them room
+            // for propagation delays.
+            if 3 * (now - last_validator_announce_time) < self.client.config.ttl_account_id_router
+                && self.last_validator_announce_epoch.as_ref() == Some(&next_epoch_id)
+            {
                 return;
             }
         }
 
         debug!(target: "client", "Check announce account for {}, last announce time {:?}", signer.validator_id(), self.last_validator_announce_time);
 
-        // Announce AccountId if client is becoming a validator soon.
-        let next_epoch_id = unwrap_or_return!(self
-            .client
-            .epoch_manager
-            .get_next_epoch_id_from_prev_block(&prev_block_hash));
-
         // Check client is part of the futures validators
         if self.client.is_validator(&next_epoch_id, &prev_block_hash, validator_signer) {
             debug!(target: "client", "Sending announce account for {}", signer.validator_id());
             self.last_validator_announce_time = Some(now);
+            self.last_validator_announce_epoch = Some(next_epoch_id);
 
             let signature =
                 signer.sign_account_announce(signer.validator_id(), &self.node_id, &next_epoch_id);
@@ -776,6 +776,11 @@ fn new_peer_info_view(chain: &Chain, connected_peer_info: &ConnectedPeerInfo) ->
             .whole_milliseconds() as u64,
         is_outbound_peer: connected_peer_info.peer_type == PeerType::Outbound,
         nonce: connected_peer_info.nonce,
+        invalid_messages: connected_peer_info.invalid_messages,
+        requests_served: connected_peer_info.requests_served,
+        avg_response_latency_millis: connected_peer_info
+            .avg_response_latency
+            .map(|latency| latency.whole_milliseconds() as u64),
     }
 }
 
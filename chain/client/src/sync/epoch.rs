@@ -24,6 +24,7 @@ use near_primitives::merkle::PartialMerkleTree;
 use near_primitives::network::PeerId;
 use near_primitives::types::{BlockHeight, EpochId};
 use near_primitives::utils::compression::CompressedData;
+use near_store::keys::{BlockInfoKey, EpochInfoKey};
 use near_store::{DBCol, Store, FINAL_HEAD_KEY};
 use rand::seq::SliceRandom;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
@@ -104,15 +105,15 @@ impl EpochSync {
 
         let current_epoch = *final_block_header_in_current_epoch.epoch_id();
         let current_epoch_info = store
-            .get_ser::<EpochInfo>(DBCol::EpochInfo, current_epoch.0.as_bytes())?
+            .get_ser_by_key::<EpochInfo>(&EpochInfoKey::epoch(&current_epoch))?
             .ok_or_else(|| Error::EpochOutOfBounds(current_epoch))?;
         let next_epoch = *final_block_header_in_current_epoch.next_epoch_id();
         let next_epoch_info = store
-            .get_ser::<EpochInfo>(DBCol::EpochInfo, next_epoch.0.as_bytes())?
+            .get_ser_by_key::<EpochInfo>(&EpochInfoKey::epoch(&next_epoch))?
             .ok_or_else(|| Error::EpochOutOfBounds(next_epoch))?;
 
         let genesis_epoch_info = store
-            .get_ser::<EpochInfo>(DBCol::EpochInfo, EpochId::default().0.as_bytes())?
+            .get_ser_by_key::<EpochInfo>(&EpochInfoKey::epoch(&EpochId::default()))?
             .ok_or_else(|| Error::EpochOutOfBounds(EpochId::default()))?;
 
         // If we have an existing (possibly and likely outdated) EpochSyncProof stored on disk,
@@ -157,7 +158,7 @@ impl EpochSync {
         let prev_epoch =
             *all_past_epochs_since_last_proof.last().unwrap().last_final_block_header.epoch_id();
         let prev_epoch_info = store
-            .get_ser::<EpochInfo>(DBCol::EpochInfo, prev_epoch.0.as_bytes())?
+            .get_ser_by_key::<EpochInfo>(&EpochInfoKey::epoch(&prev_epoch))?
             .ok_or_else(|| Error::EpochOutOfBounds(prev_epoch))?;
 
         let last_block_of_prev_epoch = store
@@ -165,7 +166,7 @@ impl EpochSync {
             .ok_or_else(|| Error::Other("Could not find last block of target epoch".to_string()))?;
 
         let last_block_info_of_prev_epoch = store
-            .get_ser::<BlockInfo>(DBCol::BlockInfo, last_block_of_prev_epoch.hash().as_bytes())?
+            .get_ser_by_key::<BlockInfo>(&BlockInfoKey(last_block_of_prev_epoch.hash()))?
             .ok_or_else(|| Error::Other("Could not find last block info".to_string()))?;
 
         let second_last_block_of_prev_epoch = store
@@ -178,24 +179,17 @@ impl EpochSync {
             })?;
 
         let second_last_block_info_of_prev_epoch = store
-            .get_ser::<BlockInfo>(
-                DBCol::BlockInfo,
-                last_block_of_prev_epoch.prev_hash().as_bytes(),
-            )?
+            .get_ser_by_key::<BlockInfo>(&BlockInfoKey(last_block_of_prev_epoch.prev_hash()))?
             .ok_or_else(|| Error::Other("Could not find second last block info".to_string()))?;
 
         let first_block_info_of_prev_epoch = store
-            .get_ser::<BlockInfo>(
-                DBCol::BlockInfo,
-                last_block_info_of_prev_epoch.epoch_first_block().as_bytes(),
-            )?
+            .get_ser_by_key::<BlockInfo>(&BlockInfoKey(
+                last_block_info_of_prev_epoch.epoch_first_block(),
+            ))?
             .ok_or_else(|| Error::Other("Could not find first block info".to_string()))?;
 
         let block_info_for_final_block_of_current_epoch = store
-            .get_ser::<BlockInfo>(
-                DBCol::BlockInfo,
-                final_block_header_in_current_epoch.hash().as_bytes(),
-            )?
+            .get_ser_by_key::<BlockInfo>(&BlockInfoKey(final_block_header_in_current_epoch.hash()))?
             .ok_or_else(|| {
                 Error::Other("Could not find block info for latest final block".to_string())
             })?;
@@ -208,10 +202,9 @@ impl EpochSync {
             .ok_or_else(|| Error::Other("Could not find first block of next epoch".to_string()))?;
 
         let first_block_info_of_current_epoch = store
-            .get_ser::<BlockInfo>(
-                DBCol::BlockInfo,
-                block_info_for_final_block_of_current_epoch.epoch_first_block().as_bytes(),
-            )?
+            .get_ser_by_key::<BlockInfo>(&BlockInfoKey(
+                block_info_for_final_block_of_current_epoch.epoch_first_block(),
+            ))?
             .ok_or_else(|| {
                 Error::Other("Could not find first block info of next epoch".to_string())
             })?;
@@ -483,9 +476,8 @@ impl EpochSync {
             proof.last_epoch.next_next_epoch_info,
         )?;
 
-        store_update.insert_ser(
-            DBCol::BlockInfo,
-            &borsh::to_vec(proof.current_epoch.first_block_info_in_epoch.hash()).unwrap(),
+        store_update.insert_ser_by_key(
+            &BlockInfoKey(proof.current_epoch.first_block_info_in_epoch.hash()),
             &proof.current_epoch.first_block_info_in_epoch,
         )?;
 
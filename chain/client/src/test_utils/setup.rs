@@ -459,6 +459,10 @@ fn process_peer_manager_message_default(
                 connection_established_time: near_async::time::Instant::now(),
                 peer_type: PeerType::Outbound,
                 nonce: 3,
+                is_validator: false,
+                invalid_messages: 0,
+                requests_served: 0,
+                avg_response_latency: None,
             })
             .collect();
         let peers2 = peers.iter().filter_map(|it| it.full_peer_info.clone().into()).collect();
@@ -761,6 +765,7 @@ fn process_peer_manager_message_default(
         }
         NetworkRequests::ForwardTx(_, _)
         | NetworkRequests::BanPeer { .. }
+        | NetworkRequests::ReportPeer { .. }
         | NetworkRequests::TxStatus(_, _, _)
         | NetworkRequests::SnapshotHostInfo { .. }
         | NetworkRequests::Challenge(_)
@@ -597,6 +597,8 @@ impl TestEnv {
                     account_id,
                     prefix: vec![].into(),
                     include_proof: false,
+                    after_key: None,
+                    limit: None,
                 },
             )
             .unwrap();
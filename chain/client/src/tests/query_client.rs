@@ -1,8 +1,10 @@
 use crate::test_utils::{setup_no_network, setup_only_view};
 use crate::{
-    GetBlock, GetBlockWithMerkleTree, GetExecutionOutcomesForBlock, Query, Status, TxStatus,
+    GetBlock, GetBlockWithMerkleTree, GetContractLogs, GetExecutionOutcomesForBlock, Query, Status,
+    TxStatus,
 };
 use actix::System;
+use assert_matches::assert_matches;
 use futures::{future, FutureExt};
 use near_actix_test_utils::run_actix;
 use near_async::messaging::IntoMultiSender;
@@ -56,6 +58,64 @@ fn query_client() {
     });
 }
 
+/// Query contract logs from view client
+#[test]
+fn query_contract_logs() {
+    init_test_logger();
+    run_actix(async {
+        let actor_handles = setup_no_network(
+            Clock::real(),
+            vec!["test".parse().unwrap()],
+            "other".parse().unwrap(),
+            true,
+            true,
+        );
+        let view_client = actor_handles.view_client_actor;
+        let res = view_client
+            .send(
+                GetContractLogs {
+                    account_id: "test".parse().unwrap(),
+                    from_height: 10,
+                    to_height: 1000,
+                }
+                .with_span_context(),
+            )
+            .await;
+        assert_matches!(
+            res.unwrap(),
+            Err(near_client_primitives::types::GetContractLogsError::InvalidRange { .. })
+        );
+        // Genesis doesn't have any execution outcomes.
+        let res = view_client
+            .send(
+                GetContractLogs {
+                    account_id: "test".parse().unwrap(),
+                    from_height: 0,
+                    to_height: 0,
+                }
+                .with_span_context(),
+            )
+            .await;
+        assert_eq!(res.unwrap().unwrap(), vec![]);
+        // Blocks above the head may still be produced.
+        let res = view_client
+            .send(
+                GetContractLogs {
+                    account_id: "test".parse().unwrap(),
+                    from_height: 0,
+                    to_height: 50,
+                }
+                .with_span_context(),
+            )
+            .await;
+        assert_matches!(
+            res.unwrap(),
+            Err(near_client_primitives::types::GetContractLogsError::UnknownBlock(_))
+        );
+        System::current().stop();
+    });
+}
+
 /// When we receive health check and the latest block's timestamp is in the future, the client
 /// should not crash.
 #[test]
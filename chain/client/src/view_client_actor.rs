@@ -17,13 +17,13 @@ use near_chain_configs::{ClientConfig, MutableValidatorSigner, ProtocolConfigVie
 use near_chain_primitives::error::EpochErrorResultToChainError;
 use near_client_primitives::types::{
     Error, GetBlock, GetBlockError, GetBlockProof, GetBlockProofError, GetBlockProofResponse,
-    GetBlockWithMerkleTree, GetChunkError, GetExecutionOutcome, GetExecutionOutcomeError,
-    GetExecutionOutcomesForBlock, GetGasPrice, GetGasPriceError, GetMaintenanceWindows,
-    GetMaintenanceWindowsError, GetNextLightClientBlockError, GetProtocolConfig,
-    GetProtocolConfigError, GetReceipt, GetReceiptError, GetSplitStorageInfo,
-    GetSplitStorageInfoError, GetStateChangesError, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfoError, Query, QueryError,
-    TxStatus, TxStatusError,
+    GetBlockWithMerkleTree, GetChunkError, GetContractLogs, GetContractLogsError,
+    GetExecutionOutcome, GetExecutionOutcomeError, GetExecutionOutcomesForBlock, GetGasPrice,
+    GetGasPriceError, GetMaintenanceWindows, GetMaintenanceWindowsError,
+    GetNextLightClientBlockError, GetProtocolConfig, GetProtocolConfigError, GetReceipt,
+    GetReceiptError, GetSplitStorageInfo, GetSplitStorageInfoError, GetStateChangesError,
+    GetStateChangesWithCauseInBlock, GetStateChangesWithCauseInBlockForTrackedShards,
+    GetValidatorInfoError, Query, QueryError, TxStatus, TxStatusError,
 };
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManagerAdapter;
@@ -54,11 +54,11 @@ use near_primitives::types::{
 use near_primitives::validator_signer::ValidatorSigner;
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView, ExecutionStatusView,
-    FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum, FinalExecutionStatus, GasPriceView,
-    LightClientBlockView, MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView,
-    SignedTransactionView, SplitStorageInfoView, StateChangesKindsView, StateChangesView,
-    TxExecutionStatus, TxStatusView,
+    BlockView, ChunkView, ContractLogsView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
+    ExecutionStatusView, FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum,
+    FinalExecutionStatus, GasPriceView, LightClientBlockView, MaintenanceWindowsView, QueryRequest,
+    QueryResponse, ReceiptView, SignedTransactionView, SplitStorageInfoView, StateChangesKindsView,
+    StateChangesView, TxExecutionStatus, TxStatusView,
 };
 use near_store::flat::{FlatStorageReadyStatus, FlatStorageStatus};
 use near_store::{DBCol, COLD_HEAD_KEY, FINAL_HEAD_KEY, HEAD_KEY};
@@ -75,6 +75,8 @@ const QUERY_REQUEST_LIMIT: usize = 500;
 const REQUEST_WAIT_TIME: i64 = 1000;
 
 const POISONED_LOCK_ERR: &str = "The lock was poisoned.";
+/// Max number of blocks which contract logs can be requested for at once.
+const CONTRACT_LOGS_MAX_BLOCKS: u64 = 100;
 
 /// Request and response manager across all instances of ViewClientActor.
 pub struct ViewClientRequestManager {
@@ -1160,6 +1162,89 @@ impl Handler<GetExecutionOutcomesForBlock> for ViewClientActorInner {
     }
 }
 
+/// Collects the logs emitted on an account from the execution outcomes stored
+/// for the blocks in the range, so that they don't need to be re-executed.
+impl Handler<GetContractLogs> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetContractLogs,
+    ) -> Result<Vec<ContractLogsView>, GetContractLogsError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer =
+            metrics::VIEW_CLIENT_MESSAGE_TIME.with_label_values(&["GetContractLogs"]).start_timer();
+        let GetContractLogs { account_id, from_height, to_height } = msg;
+        if from_height > to_height || to_height - from_height >= CONTRACT_LOGS_MAX_BLOCKS {
+            return Err(GetContractLogsError::InvalidRange {
+                from_height,
+                to_height,
+                max_blocks: CONTRACT_LOGS_MAX_BLOCKS,
+            });
+        }
+        let chain_store = self.chain.chain_store();
+        // Below the tail, a missing block means it was garbage collected rather
+        // than skipped.
+        let tail = chain_store.tail()?;
+        if from_height < tail {
+            return Err(GetContractLogsError::UnknownBlock(format!(
+                "#{from_height} is garbage collected, the earliest available block is #{tail}"
+            )));
+        }
+        // Above the head, a missing block may still be produced, so the range
+        // can't be answered yet.
+        let head = chain_store.head()?;
+        if to_height > head.height {
+            return Err(GetContractLogsError::UnknownBlock(format!(
+                "#{to_height} is above the head #{}",
+                head.height
+            )));
+        }
+        let genesis_height = self.chain.genesis().height();
+        let me = self.validator.get().map(|v| v.validator_id().clone());
+        let mut result = vec![];
+        for height in from_height..=to_height {
+            let block_hash = match chain_store.get_block_hash_by_height(height) {
+                Ok(block_hash) => block_hash,
+                // Skipped height.
+                Err(near_chain::Error::DBNotFoundErr(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            // The genesis block has no execution outcomes.
+            if height != genesis_height {
+                let header = chain_store.get_block_header(&block_hash)?;
+                let shard_id = self
+                    .epoch_manager
+                    .account_id_to_shard_id(&account_id, header.epoch_id())
+                    .into_chain_error()?;
+                if !self.shard_tracker.care_about_shard(
+                    me.as_ref(),
+                    header.prev_hash(),
+                    shard_id,
+                    true,
+                ) {
+                    return Err(GetContractLogsError::UnavailableShard { account_id, shard_id });
+                }
+            }
+            let mut outcomes: Vec<_> =
+                chain_store.get_block_execution_outcomes(&block_hash)?.into_iter().collect();
+            outcomes.sort_by_key(|(shard_id, _)| *shard_id);
+            for outcome in outcomes.into_iter().flat_map(|(_, outcomes)| outcomes) {
+                let outcome = outcome.outcome_with_id;
+                if outcome.outcome.executor_id != account_id || outcome.outcome.logs.is_empty() {
+                    continue;
+                }
+                result.push(ContractLogsView {
+                    block_hash,
+                    block_height: height,
+                    id: outcome.id,
+                    logs: outcome.outcome.logs,
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
 impl Handler<GetReceipt> for ViewClientActorInner {
     #[perf]
     fn handle(&mut self, msg: GetReceipt) -> Result<Option<ReceiptView>, GetReceiptError> {
@@ -1487,11 +1572,12 @@ impl Handler<AnnounceAccountRequest> for ViewClientActorInner {
         let mut filtered_announce_accounts = Vec::new();
 
         for (announce_account, last_epoch) in announce_accounts {
-            // Keep the announcement if it is newer than the last announcement from
-            // the same account.
+            // Keep the announcement if it is not older than the last announcement from
+            // the same account. Announcements for the same epoch are refreshes, which
+            // keep the routing tables from expiring them.
             if let Some(last_epoch) = last_epoch {
                 match self.epoch_manager.compare_epoch_id(&announce_account.epoch_id, &last_epoch) {
-                    Ok(Ordering::Greater) => {}
+                    Ok(Ordering::Greater | Ordering::Equal) => {}
                     _ => continue,
                 }
             }
@@ -7,7 +7,7 @@ use near_primitives::block::{BlockHeader, Tip};
 use near_primitives::epoch_block_info::{BlockInfo, SlashState};
 use near_primitives::epoch_info::EpochInfo;
 use near_primitives::epoch_manager::{
-    AllEpochConfig, EpochConfig, EpochConfigStore, EpochSummary, ShardConfig, AGGREGATOR_KEY,
+    AllEpochConfig, EpochConfig, EpochConfigStore, EpochSummary, ShardConfig,
 };
 use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
@@ -25,7 +25,8 @@ use near_primitives::version::{
 use near_primitives::views::{
     CurrentEpochValidatorInfo, EpochValidatorInfo, NextEpochValidatorInfo, ValidatorKickoutView,
 };
-use near_store::{DBCol, Store, StoreUpdate, HEADER_HEAD_KEY};
+use near_store::keys::{BlockInfoKey, EpochInfoKey, EpochStartKey, EpochValidatorInfoKey};
+use near_store::{DBCol, Store, StoreError, StoreUpdate, HEADER_HEAD_KEY};
 use num_rational::BigRational;
 use primitive_types::U256;
 use reward_calculator::ValidatorOnlineThresholds;
@@ -60,6 +61,15 @@ const EPOCH_CACHE_SIZE: usize = if cfg!(feature = "no_cache") { 1 } else { 50 };
 const BLOCK_CACHE_SIZE: usize = if cfg!(feature = "no_cache") { 5 } else { 1000 }; // TODO(#5080): fix this
 const AGGREGATOR_SAVE_PERIOD: u64 = 1000;
 
+/// Converts an error reading from the store, telling corrupted values apart
+/// from failing I/O.
+fn store_read_error(err: std::io::Error) -> EpochError {
+    match StoreError::from_io(&err) {
+        Some(err) => EpochError::DBCorruption(err.to_string()),
+        None => EpochError::from(err),
+    }
+}
+
 /// In the current architecture, various components have access to the same
 /// shared mutable instance of [`EpochManager`]. This handle manages locking
 /// required for such access.
@@ -293,8 +303,8 @@ impl EpochManager {
         let validator_reward =
             HashMap::from([(reward_calculator.protocol_treasury_account.clone(), 0u128)]);
         let epoch_info_aggregator = store
-            .get_ser(DBCol::EpochInfo, AGGREGATOR_KEY)
-            .map_err(EpochError::from)?
+            .get_ser_by_key(&EpochInfoKey::Aggregator)
+            .map_err(store_read_error)?
             .unwrap_or_default();
         let genesis_num_block_producer_seats =
             config.for_protocol_version(genesis_protocol_version).num_block_producer_seats;
@@ -431,7 +441,7 @@ impl EpochManager {
         // blocks to compute the aggregator data. See issue for details. Consider a cleaner way.
         self.epoch_info_aggregator =
             EpochInfoAggregator::new(*prev_epoch_id, *prev_epoch_prev_last_block_info.prev_hash());
-        store_update.set_ser(DBCol::EpochInfo, AGGREGATOR_KEY, &self.epoch_info_aggregator)?;
+        store_update.set_ser_by_key(&EpochInfoKey::Aggregator, &self.epoch_info_aggregator)?;
 
         self.save_block_info(store_update, Arc::new(prev_epoch_first_block_info))?;
         self.save_block_info(store_update, Arc::new(prev_epoch_prev_last_block_info))?;
@@ -1298,7 +1308,7 @@ impl EpochManager {
     fn is_last_block_in_finished_epoch(&self, hash: &CryptoHash) -> Result<bool, EpochError> {
         match self.get_epoch_info(&EpochId(*hash)) {
             Ok(_) => Ok(true),
-            Err(EpochError::IOErr(msg)) => Err(EpochError::IOErr(msg)),
+            Err(err @ (EpochError::IOErr(_) | EpochError::DBCorruption(_))) => Err(err),
             Err(EpochError::EpochOutOfBounds(_)) => Ok(false),
             Err(EpochError::MissingBlock(_)) => Ok(false),
             Err(err) => {
@@ -1727,6 +1737,33 @@ impl EpochManager {
         let stake_divisor = { config.minimum_stake_divisor as Balance };
         Ok(seat_price / stake_divisor)
     }
+
+    /// Returns the minimal stake with which a validator was selected for the epoch.
+    pub fn get_seat_price(&self, epoch_id: &EpochId) -> Result<Balance, EpochError> {
+        Ok(self.get_epoch_info(epoch_id)?.seat_price())
+    }
+
+    /// Returns the seat price which the epoch after next would have if the
+    /// current epoch ended at the given block: the validators of the next
+    /// epoch with the stake proposals made so far, not accounting for kickouts
+    /// and rewards.
+    pub fn get_projected_seat_price(
+        &self,
+        last_block_hash: &CryptoHash,
+    ) -> Result<Balance, EpochError> {
+        let next_epoch_id = self.get_next_epoch_id(last_block_hash)?;
+        let next_epoch_info = self.get_epoch_info(&next_epoch_id)?;
+        let protocol_version = next_epoch_info.protocol_version();
+        let epoch_config = self.config.for_protocol_version(protocol_version);
+        let aggregator = self.get_epoch_info_aggregator_upto_last(last_block_hash)?;
+        let proposals = aggregator.all_proposals.into_values().collect();
+        Ok(validator_selection::proposals_to_seat_price(
+            &epoch_config,
+            &next_epoch_info,
+            proposals,
+            protocol_version,
+        ))
+    }
 }
 
 /// Private utilities for EpochManager.
@@ -1833,7 +1870,8 @@ impl EpochManager {
     pub fn get_epoch_info(&self, epoch_id: &EpochId) -> Result<Arc<EpochInfo>, EpochError> {
         self.epochs_info.get_or_try_put(*epoch_id, |epoch_id| {
             self.store
-                .get_ser(DBCol::EpochInfo, epoch_id.as_ref())?
+                .get_ser_by_key(&EpochInfoKey::epoch(epoch_id))
+                .map_err(store_read_error)?
                 .ok_or_else(|| EpochError::EpochOutOfBounds(*epoch_id))
         })
     }
@@ -1852,7 +1890,7 @@ impl EpochManager {
         epoch_id: &EpochId,
         epoch_info: Arc<EpochInfo>,
     ) -> Result<(), EpochError> {
-        store_update.set_ser(DBCol::EpochInfo, epoch_id.as_ref(), &epoch_info)?;
+        store_update.set_ser_by_key(&EpochInfoKey::epoch(epoch_id), &epoch_info)?;
         self.epochs_info.put(*epoch_id, epoch_info);
         Ok(())
     }
@@ -1860,7 +1898,8 @@ impl EpochManager {
     pub fn get_epoch_validator_info(&self, epoch_id: &EpochId) -> Result<EpochSummary, EpochError> {
         // We don't use cache here since this query happens rarely and only for rpc.
         self.store
-            .get_ser(DBCol::EpochValidatorInfo, epoch_id.as_ref())?
+            .get_ser_by_key(&EpochValidatorInfoKey(epoch_id))
+            .map_err(store_read_error)?
             .ok_or_else(|| EpochError::EpochOutOfBounds(*epoch_id))
     }
 
@@ -1873,7 +1912,7 @@ impl EpochManager {
         epoch_summary: &EpochSummary,
     ) -> Result<(), EpochError> {
         store_update
-            .set_ser(DBCol::EpochValidatorInfo, epoch_id.as_ref(), epoch_summary)
+            .set_ser_by_key(&EpochValidatorInfoKey(epoch_id), epoch_summary)
             .map_err(EpochError::from)
     }
 
@@ -1888,11 +1927,13 @@ impl EpochManager {
     /// Get BlockInfo for a block
     /// # Errors
     /// EpochError::IOErr if storage returned an error
+    /// EpochError::DBCorruption if the stored block info is corrupted
     /// EpochError::MissingBlock if block is not in storage
     pub fn get_block_info(&self, hash: &CryptoHash) -> Result<Arc<BlockInfo>, EpochError> {
         self.blocks_info.get_or_try_put(*hash, |hash| {
             self.store
-                .get_ser(DBCol::BlockInfo, hash.as_ref())?
+                .get_ser_by_key(&BlockInfoKey(hash))
+                .map_err(store_read_error)?
                 .ok_or_else(|| EpochError::MissingBlock(*hash))
                 .map(Arc::new)
         })
@@ -1905,7 +1946,7 @@ impl EpochManager {
     ) -> Result<(), EpochError> {
         let block_hash = *block_info.hash();
         store_update
-            .insert_ser(DBCol::BlockInfo, block_hash.as_ref(), &block_info)
+            .insert_ser_by_key(&BlockInfoKey(&block_hash), &block_info)
             .map_err(EpochError::from)?;
         self.blocks_info.put(block_hash, block_info);
         Ok(())
@@ -1918,7 +1959,7 @@ impl EpochManager {
         epoch_start: BlockHeight,
     ) -> Result<(), EpochError> {
         store_update
-            .set_ser(DBCol::EpochStart, epoch_id.as_ref(), &epoch_start)
+            .set_ser_by_key(&EpochStartKey(epoch_id), &epoch_start)
             .map_err(EpochError::from)?;
         self.epoch_id_to_start.put(*epoch_id, epoch_start);
         Ok(())
@@ -1927,7 +1968,8 @@ impl EpochManager {
     fn get_epoch_start_from_epoch_id(&self, epoch_id: &EpochId) -> Result<BlockHeight, EpochError> {
         self.epoch_id_to_start.get_or_try_put(*epoch_id, |epoch_id| {
             self.store
-                .get_ser(DBCol::EpochStart, epoch_id.as_ref())?
+                .get_ser_by_key(&EpochStartKey(epoch_id))
+                .map_err(store_read_error)?
                 .ok_or_else(|| EpochError::EpochOutOfBounds(*epoch_id))
         })
     }
@@ -1962,11 +2004,8 @@ impl EpochManager {
                 block_info.height() % AGGREGATOR_SAVE_PERIOD == 0
             };
             if save {
-                store_update.set_ser(
-                    DBCol::EpochInfo,
-                    AGGREGATOR_KEY,
-                    &self.epoch_info_aggregator,
-                )?;
+                store_update
+                    .set_ser_by_key(&EpochInfoKey::Aggregator, &self.epoch_info_aggregator)?;
             }
         }
         Ok(())
@@ -2069,11 +2108,13 @@ impl EpochManager {
                     // TODO(11931): think of a better way to do this.
                     let tip = self
                         .store
-                        .get_ser::<Tip>(DBCol::BlockMisc, HEADER_HEAD_KEY)?
+                        .get_ser::<Tip>(DBCol::BlockMisc, HEADER_HEAD_KEY)
+                        .map_err(store_read_error)?
                         .ok_or_else(|| EpochError::IOErr("Tip not found in store".to_string()))?;
                     let block_header = self
                         .store
-                        .get_ser::<BlockHeader>(DBCol::BlockHeader, tip.prev_block_hash.as_bytes())?
+                        .get_ser::<BlockHeader>(DBCol::BlockHeader, tip.prev_block_hash.as_bytes())
+                        .map_err(store_read_error)?
                         .ok_or_else(|| {
                             EpochError::IOErr(
                                 "BlockHeader for prev block of tip not found in store".to_string(),
@@ -173,6 +173,35 @@ fn test_validator_change_of_stake() {
     );
 }
 
+#[test]
+fn test_projected_seat_price() {
+    let amount_staked = 1_000_000;
+    let validators =
+        vec![("test1".parse().unwrap(), amount_staked), ("test2".parse().unwrap(), amount_staked)];
+    let mut epoch_manager =
+        setup_epoch_manager(validators, 2, 1, 2, 0, 0, 0, default_reward_calculator());
+
+    let h = hash_range(4);
+    record_block(&mut epoch_manager, CryptoHash::default(), h[0], 0, vec![]);
+    record_block(
+        &mut epoch_manager,
+        h[0],
+        h[1],
+        1,
+        vec![stake("test3".parse().unwrap(), 3 * amount_staked)],
+    );
+    let epoch_id = epoch_manager.get_epoch_id(&h[1]).unwrap();
+    let seat_price = epoch_manager.get_seat_price(&epoch_id).unwrap();
+    let projected_seat_price = epoch_manager.get_projected_seat_price(&h[1]).unwrap();
+    assert!(projected_seat_price > seat_price);
+
+    // The epoch after next is selected with the same proposals.
+    record_block(&mut epoch_manager, h[1], h[2], 2, vec![]);
+    record_block(&mut epoch_manager, h[2], h[3], 3, vec![]);
+    let next_epoch_id = epoch_manager.get_next_epoch_id(&h[3]).unwrap();
+    assert_eq!(epoch_manager.get_seat_price(&next_epoch_id).unwrap(), projected_seat_price);
+}
+
 /// Test handling forks across the epoch finalization.
 /// Fork with where one BP produces blocks in one chain and 2 BPs are in another chain.
 ///     |   | /--1---4------|--7---10------|---13---
@@ -3754,3 +3783,16 @@ fn test_possible_epochs_of_height_around_tip() {
         }
     }
 }
+
+/// Block info which can't be deserialized is reported as corruption rather
+/// than as a generic IO error.
+#[test]
+fn test_corrupted_block_info() {
+    let epoch_manager =
+        setup_default_epoch_manager(vec![("test1".parse().unwrap(), 1_000_000)], 1, 1, 1, 90, 60);
+    let h = hash(&[1]);
+    let mut store_update = epoch_manager.store.store_update();
+    store_update.insert(DBCol::BlockInfo, h.as_ref().to_vec(), vec![1, 2, 3]);
+    store_update.commit().unwrap();
+    assert!(matches!(epoch_manager.get_block_info(&h), Err(EpochError::DBCorruption(_))));
+}
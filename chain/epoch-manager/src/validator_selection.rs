@@ -204,15 +204,7 @@ pub fn proposals_to_epoch_info(
     // Select validators for the next epoch.
     // Returns unselected proposals, validator lists for all roles and stake
     // threshold to become a validator.
-    let validator_roles = if ProtocolFeature::StatelessValidation.enabled(protocol_version) {
-        select_validators_from_proposals(epoch_config, proposals, protocol_version)
-    } else {
-        old_validator_selection::select_validators_from_proposals(
-            epoch_config,
-            proposals,
-            protocol_version,
-        )
-    };
+    let validator_roles = select_validator_roles(epoch_config, proposals, protocol_version);
 
     // Add kickouts for validators which fell out of validator set.
     // Used for querying epoch info by RPC.
@@ -307,6 +299,41 @@ pub fn proposals_to_epoch_info(
     ))
 }
 
+/// Computes the seat price of the epoch following `prev_epoch_info` given the
+/// stake proposals, as `proposals_to_epoch_info` would without any kickouts
+/// and rewards.
+pub fn proposals_to_seat_price(
+    epoch_config: &EpochConfig,
+    prev_epoch_info: &EpochInfo,
+    proposals: Vec<ValidatorStake>,
+    protocol_version: ProtocolVersion,
+) -> Balance {
+    let proposals = apply_epoch_update_to_proposals(
+        proposals,
+        prev_epoch_info,
+        &HashMap::new(),
+        &HashMap::new(),
+        &mut BTreeMap::new(),
+    );
+    select_validator_roles(epoch_config, proposals, protocol_version).threshold
+}
+
+fn select_validator_roles(
+    epoch_config: &EpochConfig,
+    proposals: HashMap<AccountId, ValidatorStake>,
+    protocol_version: ProtocolVersion,
+) -> ValidatorRoles {
+    if ProtocolFeature::StatelessValidation.enabled(protocol_version) {
+        select_validators_from_proposals(epoch_config, proposals, protocol_version)
+    } else {
+        old_validator_selection::select_validators_from_proposals(
+            epoch_config,
+            proposals,
+            protocol_version,
+        )
+    }
+}
+
 /// Generates proposals based on proposals generated throughout last epoch,
 /// last epoch validators and validator kickouts.
 /// For each account that was validator in last epoch or made stake action last epoch
@@ -0,0 +1,65 @@
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
+use near_primitives::views::ContractLogsView;
+use serde_json::Value;
+
+/// Request of the `EXPERIMENTAL_contract_logs` method: logs emitted by
+/// executions on `account_id` in the blocks at heights `from_height..=to_height`.
+///
+/// At most 100 blocks can be requested at once, and the range must lie between
+/// the earliest block kept by the node and its head, otherwise `UNKNOWN_BLOCK`
+/// is returned. The node doesn't keep a per-account index of logs: they are
+/// read from the execution outcomes stored for every block in the range, which
+/// requires the node to track the account's shard.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcContractLogsRequest {
+    pub account_id: AccountId,
+    pub from_height: BlockHeight,
+    pub to_height: BlockHeight,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcContractLogsResponse {
+    pub logs: Vec<ContractLogsView>,
+}
+
+#[derive(thiserror::Error, Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcContractLogsError {
+    #[error("Invalid block range {from_height}..={to_height}, at most {max_blocks} blocks can be requested")]
+    InvalidRange { from_height: BlockHeight, to_height: BlockHeight, max_blocks: u64 },
+    #[error("Block either has never been observed on the node or has been garbage collected: {error_message}")]
+    UnknownBlock {
+        #[serde(skip_serializing)]
+        error_message: String,
+    },
+    #[error("Node doesn't track the shard {shard_id} of account {account_id}")]
+    UnavailableShard { account_id: AccountId, shard_id: ShardId },
+    #[error("Internal error: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcContractLogsError> for crate::errors::RpcError {
+    fn from(error: RpcContractLogsError) -> Self {
+        let error_data = match &error {
+            RpcContractLogsError::UnknownBlock { error_message } => Some(Value::String(format!(
+                "DB Not Found Error: {} \n Cause: Unknown",
+                error_message
+            ))),
+            RpcContractLogsError::InvalidRange { .. }
+            | RpcContractLogsError::UnavailableShard { .. }
+            | RpcContractLogsError::InternalError { .. } => Some(Value::String(error.to_string())),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcContractLogsError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
@@ -4,6 +4,7 @@ pub mod chunks;
 pub mod client_config;
 pub mod config;
 pub mod congestion;
+pub mod contract_logs;
 pub mod entity_debug;
 pub mod gas_price;
 pub mod light_client;
@@ -1,5 +1,5 @@
 use near_primitives::network::PeerId;
-use near_primitives::types::AccountId;
+use near_primitives::types::{AccountId, BlockHeight};
 use std::net::SocketAddr;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
@@ -7,6 +7,21 @@ pub struct RpcPeerInfo {
     pub id: PeerId,
     pub addr: Option<SocketAddr>,
     pub account_id: Option<AccountId>,
+    /// Height of the latest block the peer told us about.
+    #[serde(default)]
+    pub height: Option<BlockHeight>,
+    /// Milliseconds since the last message received from the peer.
+    #[serde(default)]
+    pub last_time_received_message_millis: u64,
+    #[serde(default)]
+    pub received_bytes_per_sec: u64,
+    #[serde(default)]
+    pub sent_bytes_per_sec: u64,
+    #[serde(default)]
+    pub is_outbound_peer: bool,
+    /// Whether the peer has proven to be a current validator.
+    #[serde(default)]
+    pub is_validator: bool,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
@@ -4,9 +4,9 @@ use near_client_primitives::debug::{
 };
 #[cfg(feature = "debug_types")]
 use near_primitives::views::{
-    CatchupStatusView, ChainProcessingInfo, NetworkGraphView, NetworkRoutesView, PeerStoreView,
-    RecentOutboundConnectionsView, RequestedStatePartsView, SnapshotHostsView,
-    SplitStorageInfoView, SyncStatusView,
+    CatchupStatusView, ChainProcessingInfo, NetworkBandwidthView, NetworkGraphView,
+    NetworkRoutesView, PeerStoreView, RecentOutboundConnectionsView, RequestedStatePartsView,
+    SnapshotHostsView, SplitStorageInfoView, SyncStatusView,
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -35,6 +35,8 @@ pub enum DebugStatusResponse {
     RecentOutboundConnections(RecentOutboundConnectionsView),
     Routes(NetworkRoutesView),
     SnapshotHosts(SnapshotHostsView),
+    // Traffic by peer and message type.
+    NetworkBandwidth(NetworkBandwidthView),
     SplitStoreStatus(SplitStorageInfoView),
 }
 
@@ -1,8 +1,12 @@
+use std::ops::ControlFlow;
+
 use actix::System;
+use awc::http::StatusCode;
 use futures::{future, FutureExt};
 
 use near_actix_test_utils::run_actix;
 use near_jsonrpc::client::new_http_client;
+use near_network::test_utils::wait_or_timeout;
 use near_o11y::testonly::init_test_logger;
 use near_time::Clock;
 
@@ -28,3 +32,28 @@ fn test_status() {
         }));
     });
 }
+
+/// `/ready` fails once the node doesn't receive new blocks, while `/health`
+/// only checks that the node is alive.
+#[test]
+fn test_health_and_ready() {
+    init_test_logger();
+
+    run_actix(async {
+        let (_, addr) = test_utils::start_all(Clock::real(), test_utils::NodeType::NonValidator);
+
+        let client = awc::Client::new();
+        wait_or_timeout(300, 10000, || async {
+            let res = client.get(format!("http://{}/ready", addr)).send().await.unwrap();
+            if res.status() == StatusCode::SERVICE_UNAVAILABLE {
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        })
+        .await
+        .unwrap();
+        let res = client.get(format!("http://{}/health", addr)).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        System::current().stop()
+    });
+}
@@ -301,6 +301,8 @@ fn test_query_state() {
                     account_id: "test".parse().unwrap(),
                     prefix: vec![].into(),
                     include_proof: false,
+                    after_key: None,
+                    limit: None,
                 },
             })
             .await
@@ -376,6 +378,8 @@ fn test_status() {
         assert_eq!(status.sync_info.syncing, false);
         assert_eq!(status.sync_info.epoch_id, Some(EpochId::default()));
         assert_eq!(status.sync_info.epoch_start_height, Some(0));
+        assert_eq!(status.sync_info.latest_header_height, Some(0));
+        assert_eq!(status.sync_info.num_connected_peers, Some(0));
     });
 }
 
@@ -622,6 +626,36 @@ fn test_good_handler_error_status_code() {
     });
 }
 
+#[test]
+fn test_batch_request() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let json = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "block", "params": {"block_id": 0}},
+            {"jsonrpc": "2.0", "id": 2, "method": "no_such_method", "params": []},
+            {"jsonrpc": "2.0", "id": 3, "method": "status", "params": []},
+        ]);
+
+        let response = &mut client
+            .client
+            .post(&client.server_addr)
+            .insert_header(("Content-Type", "application/json"))
+            .send_json(&json)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let response: serde_json::Value = response.json().await.unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["header"]["height"], 0);
+        assert_eq!(responses[1]["id"], 2);
+        assert!(responses[1]["error"] != serde_json::json!(null));
+        assert_eq!(responses[2]["id"], 3);
+        assert!(responses[2]["result"]["chain_id"] != serde_json::json!(null));
+    });
+}
+
 #[test]
 fn test_get_chunk_with_object_in_params() {
     test_with_client!(test_utils::NodeType::NonValidator, client, async move {
@@ -0,0 +1,44 @@
+use near_async::messaging::AsyncSendError;
+use serde_json::Value;
+
+use near_client_primitives::types::GetContractLogsError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::contract_logs::{RpcContractLogsError, RpcContractLogsRequest};
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcContractLogsRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcContractLogsError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetContractLogsError> for RpcContractLogsError {
+    fn rpc_from(error: GetContractLogsError) -> Self {
+        match error {
+            GetContractLogsError::InvalidRange { from_height, to_height, max_blocks } => {
+                Self::InvalidRange { from_height, to_height, max_blocks }
+            }
+            GetContractLogsError::UnknownBlock(error_message) => {
+                Self::UnknownBlock { error_message }
+            }
+            GetContractLogsError::UnavailableShard { account_id, shard_id } => {
+                Self::UnavailableShard { account_id, shard_id }
+            }
+            GetContractLogsError::IOError(error_message) => Self::InternalError { error_message },
+            GetContractLogsError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcContractLogsError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
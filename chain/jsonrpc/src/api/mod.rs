@@ -10,6 +10,7 @@ mod chunks;
 mod client_config;
 mod config;
 mod congestion;
+mod contract_logs;
 mod gas_price;
 mod light_client;
 mod maintenance;
@@ -14,7 +14,17 @@ impl RpcFrom<AsyncSendError> for RpcNetworkInfoError {
 
 impl RpcFrom<PeerInfo> for RpcPeerInfo {
     fn rpc_from(peer_info: PeerInfo) -> Self {
-        Self { id: peer_info.id, addr: peer_info.addr, account_id: peer_info.account_id }
+        Self {
+            id: peer_info.id,
+            addr: peer_info.addr,
+            account_id: peer_info.account_id,
+            height: peer_info.height,
+            last_time_received_message_millis: peer_info.last_time_received_message_millis,
+            received_bytes_per_sec: peer_info.received_bytes_per_sec,
+            sent_bytes_per_sec: peer_info.sent_bytes_per_sec,
+            is_outbound_peer: peer_info.is_outbound_peer,
+            is_validator: peer_info.is_validator,
+        }
     }
 }
 
@@ -72,6 +72,8 @@ fn parse_path_data(path: String, data: String) -> Result<RpcQueryRequest, RpcPar
             account_id,
             prefix: parse_data()?.into(),
             include_proof: false,
+            after_key: None,
+            limit: None,
         },
         "call" => match maybe_extra_arg {
             Some(method_name) => QueryRequest::CallFunction {
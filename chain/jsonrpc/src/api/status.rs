@@ -74,6 +74,9 @@ impl RpcFrom<near_network::debug::DebugStatus>
             near_network::debug::DebugStatus::SnapshotHosts(x) => {
                 near_jsonrpc_primitives::types::status::DebugStatusResponse::SnapshotHosts(x)
             }
+            near_network::debug::DebugStatus::Bandwidth(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::NetworkBandwidth(x)
+            }
         }
     }
 }
@@ -11,10 +11,11 @@ use near_async::messaging::{
 };
 use near_chain_configs::GenesisConfig;
 use near_client::{
-    DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig, GetExecutionOutcome,
-    GetGasPrice, GetMaintenanceWindows, GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig,
-    GetReceipt, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered,
-    ProcessTxRequest, ProcessTxResponse, Query, Status, TxStatus,
+    DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig, GetContractLogs,
+    GetExecutionOutcome, GetGasPrice, GetMaintenanceWindows, GetNetworkInfo,
+    GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
+    GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered, ProcessTxRequest,
+    ProcessTxResponse, Query, Status, TxStatus,
 };
 use near_client_primitives::types::GetSplitStorageInfo;
 pub use near_jsonrpc_client as client;
@@ -46,6 +47,7 @@ use tracing::{error, info};
 
 mod api;
 mod metrics;
+mod rate_limit;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
 pub struct RpcPollingConfig {
@@ -66,11 +68,30 @@ impl Default for RpcPollingConfig {
 pub struct RpcLimitsConfig {
     /// Maximum byte size of the json payload.
     pub json_payload_max_size: usize,
+    /// Maximum number of requests in a single JSON RPC batch.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// Maximum number of JSON RPC requests per second accepted from a single IP
+    /// address, or a single /64 subnet for IPv6.  Each request of a batch counts
+    /// separately, so a batch larger than the limit is always rejected.  Requests
+    /// above the limit are rejected with 429 Too Many Requests.  Not limited if unset.
+    /// REST endpoints such as `/status`, `/health`, `/ready` and the debug pages
+    /// aren't limited.
+    #[serde(default)]
+    pub max_requests_per_second_per_ip: Option<u32>,
+}
+
+fn default_max_batch_size() -> usize {
+    100
 }
 
 impl Default for RpcLimitsConfig {
     fn default() -> Self {
-        Self { json_payload_max_size: 10 * 1024 * 1024 }
+        Self {
+            json_payload_max_size: 10 * 1024 * 1024,
+            max_batch_size: default_max_batch_size(),
+            max_requests_per_second_per_ip: None,
+        }
     }
 }
 
@@ -87,6 +108,11 @@ pub struct RpcConfig {
     pub polling_config: RpcPollingConfig,
     #[serde(default)]
     pub limits_config: RpcLimitsConfig,
+    // If provided, only the listed JSON RPC methods are served, others fail with
+    // "Method not found".  REST endpoints such as `/status`, `/health`, `/ready` and the
+    // debug pages aren't affected; `enable_debug_rpc` controls the latter.
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
     // If true, enable some debug RPC endpoints (like one to get the latest block).
     // We disable it by default, as some of those endpoints might be quite CPU heavy.
     #[serde(default = "default_enable_debug_rpc")]
@@ -105,6 +131,7 @@ impl Default for RpcConfig {
             cors_allowed_origins: vec!["*".to_owned()],
             polling_config: Default::default(),
             limits_config: Default::default(),
+            allowed_methods: None,
             enable_debug_rpc: false,
             experimental_debug_pages_src_path: None,
         }
@@ -244,6 +271,7 @@ pub struct ViewClientSenderForRpc(
     AsyncSender<GetBlock, ActixResult<GetBlock>>,
     AsyncSender<GetBlockProof, ActixResult<GetBlockProof>>,
     AsyncSender<GetChunk, ActixResult<GetChunk>>,
+    AsyncSender<GetContractLogs, ActixResult<GetContractLogs>>,
     AsyncSender<GetExecutionOutcome, ActixResult<GetExecutionOutcome>>,
     AsyncSender<GetGasPrice, ActixResult<GetGasPrice>>,
     AsyncSender<GetMaintenanceWindows, ActixResult<GetMaintenanceWindows>>,
@@ -280,6 +308,9 @@ struct JsonRpcHandler {
     gc_sender: GCSenderForRpc,
     polling_config: RpcPollingConfig,
     genesis_config: GenesisConfig,
+    max_batch_size: usize,
+    allowed_methods: Option<Vec<String>>,
+    rate_limiter: Option<Arc<rate_limit::RateLimiter>>,
     enable_debug_rpc: bool,
     debug_pages_src_path: Option<PathBuf>,
     entity_debug_handler: Arc<dyn EntityDebugHandler>,
@@ -290,12 +321,41 @@ impl JsonRpcHandler {
         let id = message.id();
         match message {
             Message::Request(request) => Message::response(id, self.process_request(request).await),
+            Message::Batch(messages) => self.process_batch(messages).await,
             _ => Message::error(RpcError::parse_error(
                 "JSON RPC Request format was expected".to_owned(),
             )),
         }
     }
 
+    /// Processes the requests of a batch concurrently.  The responses are
+    /// returned in the same order as the requests.
+    async fn process_batch(&self, messages: Vec<Message>) -> Message {
+        if messages.is_empty() {
+            return Message::error(RpcError::parse_error("JSON RPC batch is empty".to_owned()));
+        }
+        if messages.len() > self.max_batch_size {
+            return Message::error(RpcError::parse_error(format!(
+                "JSON RPC batch is limited to {} requests, got {}",
+                self.max_batch_size,
+                messages.len()
+            )));
+        }
+        let responses = futures::future::join_all(messages.into_iter().map(|message| async {
+            let id = message.id();
+            match message {
+                Message::Request(request) => {
+                    Message::response(id, self.process_request(request).await)
+                }
+                _ => Message::error(RpcError::parse_error(
+                    "JSON RPC Request format was expected".to_owned(),
+                )),
+            }
+        }))
+        .await;
+        Message::Batch(responses)
+    }
+
     // `process_request` increments affected metrics but the request processing is done by
     // `process_request_internal`.
     async fn process_request(&self, request: Request) -> Result<Value, RpcError> {
@@ -324,6 +384,9 @@ impl JsonRpcHandler {
         request: Request,
     ) -> (String, Result<Value, RpcError>) {
         let method_name = request.method.to_string();
+        if self.allowed_methods.as_ref().is_some_and(|methods| !methods.contains(&method_name)) {
+            return (method_name.clone(), Err(RpcError::method_not_found(method_name)));
+        }
         let request = match self.process_adversarial_request_internal(request).await {
             Ok(response) => return (method_name, response),
             Err(request) => request,
@@ -410,6 +473,9 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_congestion_level" => {
                 process_method_call(request, |params| self.congestion_level(params)).await
             }
+            "EXPERIMENTAL_contract_logs" => {
+                process_method_call(request, |params| self.contract_logs(params)).await
+            }
             "EXPERIMENTAL_genesis_config" => {
                 process_method_call(request, |_params: ()| async {
                     Result::<_, std::convert::Infallible>::Ok(&self.genesis_config)
@@ -728,6 +794,8 @@ impl JsonRpcHandler {
         .await
     }
 
+    /// Checks that the node is ready to serve requests, i.e. it isn't syncing
+    /// and keeps receiving new blocks.
     async fn health(
         &self,
     ) -> Result<
@@ -738,6 +806,18 @@ impl JsonRpcHandler {
         Ok(status.rpc_into())
     }
 
+    /// Checks that the node is alive, i.e. the client responds, even if the
+    /// node is still syncing.
+    async fn liveness(
+        &self,
+    ) -> Result<
+        near_jsonrpc_primitives::types::status::RpcHealthResponse,
+        near_jsonrpc_primitives::types::status::RpcStatusError,
+    > {
+        let status = self.client_send(Status { is_health_check: false, detailed: false }).await?;
+        Ok(status.rpc_into())
+    }
+
     pub async fn status(
         &self,
     ) -> Result<
@@ -819,6 +899,10 @@ impl JsonRpcHandler {
                         .peer_manager_send(near_network::debug::GetDebugStatus::SnapshotHosts)
                         .await?
                         .rpc_into(),
+                    "/debug/api/network_bandwidth" => self
+                        .peer_manager_send(near_network::debug::GetDebugStatus::Bandwidth)
+                        .await?
+                        .rpc_into(),
                     "/debug/api/split_store_info" => {
                         let split_storage_info: RpcSplitStorageInfoResponse = self
                             .split_storage_info(RpcSplitStorageInfoRequest {})
@@ -1160,6 +1244,23 @@ impl JsonRpcHandler {
         Ok(windows.iter().map(|r| (r.start, r.end)).collect())
     }
 
+    async fn contract_logs(
+        &self,
+        request: near_jsonrpc_primitives::types::contract_logs::RpcContractLogsRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::contract_logs::RpcContractLogsResponse,
+        near_jsonrpc_primitives::types::contract_logs::RpcContractLogsError,
+    > {
+        let near_jsonrpc_primitives::types::contract_logs::RpcContractLogsRequest {
+            account_id,
+            from_height,
+            to_height,
+        } = request;
+        let logs =
+            self.view_client_send(GetContractLogs { account_id, from_height, to_height }).await?;
+        Ok(near_jsonrpc_primitives::types::contract_logs::RpcContractLogsResponse { logs })
+    }
+
     async fn client_config(
         &self,
     ) -> Result<
@@ -1352,9 +1453,23 @@ impl JsonRpcHandler {
 }
 
 async fn rpc_handler(
+    req: HttpRequest,
     message: web::Json<Message>,
     handler: web::Data<JsonRpcHandler>,
 ) -> HttpResponse {
+    if let (Some(rate_limiter), Some(addr)) = (&handler.rate_limiter, req.peer_addr()) {
+        // Every request of a batch is charged, otherwise batching would
+        // multiply the limit by the maximum batch size.
+        let requests = match &message.0 {
+            Message::Batch(messages) => messages.len().max(1),
+            _ => 1,
+        };
+        let requests = u32::try_from(requests).unwrap_or(u32::MAX);
+        if !rate_limiter.allow(addr.ip(), Instant::now(), requests) {
+            metrics::RPC_RATE_LIMITED_TOTAL.inc();
+            return HttpResponse::TooManyRequests().finish();
+        }
+    }
     let message = handler.process(message.0).await;
     let mut response = if let Message::Response(response) = &message {
         match &response.result {
@@ -1372,6 +1487,9 @@ async fn rpc_handler(
                 None => HttpResponse::Ok(),
             },
         }
+    } else if let Message::Batch(_) = &message {
+        // Errors of the individual requests are reported in their responses.
+        HttpResponse::Ok()
     } else {
         HttpResponse::InternalServerError()
     };
@@ -1427,7 +1545,16 @@ async fn debug_block_status_handler(
     }
 }
 
+/// Liveness check, which doesn't fail while the node is syncing.
 async fn health_handler(handler: web::Data<JsonRpcHandler>) -> Result<HttpResponse, HttpError> {
+    match handler.liveness().await {
+        Ok(value) => Ok(HttpResponse::Ok().json(&value)),
+        Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
+    }
+}
+
+/// Readiness check, which fails while the node is syncing or doesn't receive new blocks.
+async fn ready_handler(handler: web::Data<JsonRpcHandler>) -> Result<HttpResponse, HttpError> {
     match handler.health().await {
         Ok(value) => Ok(HttpResponse::Ok().json(&value)),
         Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
@@ -1534,7 +1661,7 @@ async fn display_debug_html(
 /// Starts HTTP server(s) listening for RPC requests.
 ///
 /// Starts an HTTP server which handles JSON RPC calls as well as states
-/// endpoints such as `/status`, `/health`, `/metrics` etc.  Depending on
+/// endpoints such as `/status`, `/health`, `/ready`, `/metrics` etc.  Depending on
 /// configuration may also start another HTTP server just for providing
 /// Prometheus metrics (i.e. covering the `/metrics` path).
 ///
@@ -1557,11 +1684,15 @@ pub fn start_http(
         cors_allowed_origins,
         polling_config,
         limits_config,
+        allowed_methods,
         enable_debug_rpc,
         experimental_debug_pages_src_path: debug_pages_src_path,
     } = config;
     let prometheus_addr = prometheus_addr.filter(|it| it != &addr.to_string());
     let cors_allowed_origins_clone = cors_allowed_origins.clone();
+    let rate_limiter = limits_config
+        .max_requests_per_second_per_ip
+        .map(|limit| Arc::new(rate_limit::RateLimiter::new(limit)));
     info!(target:"network", "Starting http server at {}", addr);
     let mut servers = Vec::new();
     let listener = HttpServer::new(move || {
@@ -1573,6 +1704,9 @@ pub fn start_http(
                 peer_manager_sender: peer_manager_sender.clone(),
                 polling_config,
                 genesis_config: genesis_config.clone(),
+                max_batch_size: limits_config.max_batch_size,
+                allowed_methods: allowed_methods.clone(),
+                rate_limiter: rate_limiter.clone(),
                 enable_debug_rpc,
                 debug_pages_src_path: debug_pages_src_path.clone().map(Into::into),
                 entity_debug_handler: entity_debug_handler.clone(),
@@ -1592,6 +1726,11 @@ pub fn start_http(
                     .route(web::get().to(health_handler))
                     .route(web::head().to(health_handler)),
             )
+            .service(
+                web::resource("/ready")
+                    .route(web::get().to(ready_handler))
+                    .route(web::head().to(ready_handler)),
+            )
             .service(web::resource("/network_info").route(web::get().to(network_info_handler)))
             .service(web::resource("/metrics").route(web::get().to(prometheus_handler)))
             .service(web::resource("/debug/api/entity").route(web::post().to(handle_entity_debug)))
@@ -17,6 +17,13 @@ pub static RPC_TIMEOUT_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
     )
     .unwrap()
 });
+pub static RPC_RATE_LIMITED_TOTAL: LazyLock<IntCounter> = LazyLock::new(|| {
+    near_o11y::metrics::try_create_int_counter(
+        "near_rpc_rate_limited_total",
+        "Total count of rpc requests rejected due to the per IP rate limit",
+    )
+    .unwrap()
+});
 pub static PROMETHEUS_REQUEST_COUNT: LazyLock<IntCounter> = LazyLock::new(|| {
     near_o11y::metrics::try_create_int_counter(
         "near_http_prometheus_requests_total",
@@ -0,0 +1,124 @@
+use lru::LruCache;
+use std::net::{IpAddr, Ipv6Addr};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Length of the window in which requests from an address are counted.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Maximal number of tracked addresses.  Once reached, the least recently
+/// seen address is forgotten, which resets its count.
+const MAX_TRACKED_ADDRS: usize = 10_000;
+
+/// Length of the network prefix of IPv6 addresses limited together.  A single
+/// host usually gets a whole /64 subnet, so limiting IPv6 addresses one by one
+/// would allow a client to bypass the limit by rotating its addresses.
+const IPV6_PREFIX_LEN: u32 = 64;
+
+/// Limits the number of requests accepted from a single IP address per second.
+pub(crate) struct RateLimiter {
+    max_requests_per_second: u32,
+    /// Start of the current window and the number of requests in it, per address.
+    windows: Mutex<LruCache<IpAddr, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_requests_per_second: u32) -> Self {
+        Self {
+            max_requests_per_second,
+            windows: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_TRACKED_ADDRS).unwrap())),
+        }
+    }
+
+    /// Returns the key the requests from `addr` are counted under: the IPv4
+    /// address or the IPv6 subnet.
+    fn key(addr: IpAddr) -> IpAddr {
+        match addr {
+            IpAddr::V4(_) => addr,
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => IpAddr::V4(ip),
+                None => {
+                    let mask = u128::MAX << (128 - IPV6_PREFIX_LEN);
+                    IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+                }
+            },
+        }
+    }
+
+    /// Records `requests` requests from `addr` and returns whether they are
+    /// within the limit.  Rejected requests aren't counted.  A batch of more
+    /// requests than the limit per second is never allowed.
+    pub fn allow(&self, addr: IpAddr, now: Instant, requests: u32) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        let (start, count) = windows.get_or_insert_mut(Self::key(addr), || (now, 0));
+        if now.duration_since(*start) >= WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        let Some(new_count) =
+            count.checked_add(requests).filter(|c| *c <= self.max_requests_per_second)
+        else {
+            return false;
+        };
+        *count = new_count;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow() {
+        let limiter = RateLimiter::new(2);
+        let addr1: IpAddr = "1.2.3.4".parse().unwrap();
+        let addr2: IpAddr = "::1".parse().unwrap();
+        let now = Instant::now();
+        assert!(limiter.allow(addr1, now, 1));
+        assert!(limiter.allow(addr1, now, 1));
+        assert!(!limiter.allow(addr1, now + Duration::from_millis(500), 1));
+        // Addresses are limited independently.
+        assert!(limiter.allow(addr2, now, 1));
+        // The limit is reset in the next window.
+        assert!(limiter.allow(addr1, now + WINDOW, 1));
+        // Each request of a batch counts against the limit.
+        assert!(!limiter.allow(addr2, now, 2));
+        assert!(limiter.allow(addr2, now, 1));
+        assert!(!limiter.allow(addr2, now, 1));
+        // A batch above the limit is never allowed.
+        assert!(!limiter.allow(addr1, now + 10 * WINDOW, 3));
+    }
+
+    #[test]
+    fn ipv6_subnet() {
+        let limiter = RateLimiter::new(1);
+        let now = Instant::now();
+        let addr = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert!(limiter.allow(addr("2001:db8:1:2::1"), now, 1));
+        // Addresses of the same /64 subnet share the limit.
+        assert!(!limiter.allow(addr("2001:db8:1:2:ffff::2"), now, 1));
+        assert!(limiter.allow(addr("2001:db8:1:3::1"), now, 1));
+        // IPv4-mapped addresses share the limit with the IPv4 ones.
+        assert!(limiter.allow(addr("1.2.3.4"), now, 1));
+        assert!(!limiter.allow(addr("::ffff:1.2.3.4"), now, 1));
+    }
+
+    #[test]
+    fn lru() {
+        let limiter = RateLimiter::new(1);
+        let now = Instant::now();
+        let addr = |i: usize| IpAddr::from((i as u32).to_be_bytes());
+        for i in 0..MAX_TRACKED_ADDRS {
+            assert!(limiter.allow(addr(i), now, 1));
+        }
+        assert!(!limiter.allow(addr(0), now, 1));
+        // Tracking a new address forgets the least recently seen one,
+        // which is addr(1) since addr(0) has just been seen again.
+        assert!(limiter.allow(addr(MAX_TRACKED_ADDRS), now, 1));
+        assert!(limiter.allow(addr(1), now, 1));
+        assert!(!limiter.allow(addr(0), now, 1));
+        assert_eq!(limiter.windows.lock().unwrap().len(), MAX_TRACKED_ADDRS);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::store;
 use lru::LruCache;
+use near_async::time;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::types::AccountId;
 use parking_lot::Mutex;
@@ -19,13 +20,27 @@ struct Inner {
     /// It contains less entries than account_peers in case some AnnounceAccounts
     /// have been loaded from storage without broadcasting.
     account_peers_broadcasted: LruCache<AccountId, AnnounceAccount>,
+    /// Time at which the announcement of each account in account_peers has
+    /// been last refreshed by its owner.
+    ///
+    /// Announcements aren't expired from memory yet: relays running older
+    /// versions drop refreshes, so expiring would delete routes to validators
+    /// in a mixed-version network. Stored announcements expire through the
+    /// TTL of the AccountAnnouncements column, which each refresh renews.
+    refreshed: LruCache<AccountId, time::Instant>,
+    /// How long an announcement stays authoritative without being refreshed.
+    ttl: time::Duration,
     /// Access to store on disk
     store: store::Store,
 }
 
 impl Inner {
     /// Get AnnounceAccount for the given AccountId.
-    fn get_announce(&mut self, account_id: &AccountId) -> Option<AnnounceAccount> {
+    fn get_announce(
+        &mut self,
+        clock: &time::Clock,
+        account_id: &AccountId,
+    ) -> Option<AnnounceAccount> {
         if let Some(announce_account) = self.account_peers.get(account_id) {
             return Some(announce_account.clone());
         }
@@ -37,7 +52,10 @@ impl Inner {
             }
             Ok(None) => None,
             Ok(Some(stored_announce_account)) => {
+                // Age of the stored announcement is unknown, so it is treated
+                // as refreshed just now.
                 self.account_peers.put(account_id.clone(), stored_announce_account.clone());
+                self.refreshed.put(account_id.clone(), clock.now());
                 Some(stored_announce_account)
             }
         }
@@ -47,12 +65,14 @@ impl Inner {
 pub(crate) struct AnnounceAccountCache(Mutex<Inner>);
 
 impl AnnounceAccountCache {
-    pub fn new(store: store::Store) -> Self {
+    pub fn new(store: store::Store, ttl: time::Duration) -> Self {
         Self(Mutex::new(Inner {
             account_peers: LruCache::new(NonZeroUsize::new(ANNOUNCE_ACCOUNT_CACHE_SIZE).unwrap()),
             account_peers_broadcasted: LruCache::new(
                 NonZeroUsize::new(ANNOUNCE_ACCOUNT_CACHE_SIZE).unwrap(),
             ),
+            refreshed: LruCache::new(NonZeroUsize::new(ANNOUNCE_ACCOUNT_CACHE_SIZE).unwrap()),
+            ttl,
             store,
         }))
     }
@@ -60,8 +80,16 @@ impl AnnounceAccountCache {
     /// Adds accounts to the cache.
     /// Returns the diff: new values that should be broadcasted.
     /// Note: There is at most one peer id per account id.
+    ///
+    /// An announcement for the epoch we have broadcasted already is either
+    /// a refresh by the same peer, which is broadcasted again once per half
+    /// of the ttl, or a conflicting one, which replaces the old one only
+    /// if that hasn't been refreshed for the ttl. Validators refresh every
+    /// third of the ttl, so every other refresh gets through even if it
+    /// arrives late.
     pub(crate) fn add_accounts(
         &self,
+        clock: &time::Clock,
         account_announcements: Vec<AnnounceAccount>,
     ) -> Vec<AnnounceAccount> {
         let mut inner = self.0.lock();
@@ -71,14 +99,24 @@ impl AnnounceAccountCache {
             let epoch_id = &announcement.epoch_id;
 
             // We skip broadcasting stuff that is already broadcasted.
-            if inner.account_peers_broadcasted.get(account_id).map(|x| &x.epoch_id)
-                == Some(epoch_id)
-            {
-                continue;
+            if let Some(old) = inner.account_peers_broadcasted.get(account_id).cloned() {
+                if &old.epoch_id == epoch_id {
+                    let refreshed = inner.refreshed.get(account_id).copied();
+                    let age = refreshed.map(|t| clock.now() - t);
+                    let skip = if old.peer_id == announcement.peer_id {
+                        age.is_some_and(|age| age < inner.ttl / 2)
+                    } else {
+                        age.is_some_and(|age| age < inner.ttl)
+                    };
+                    if skip {
+                        continue;
+                    }
+                }
             }
 
             inner.account_peers.put(account_id.clone(), announcement.clone());
             inner.account_peers_broadcasted.put(account_id.clone(), announcement.clone());
+            inner.refreshed.put(account_id.clone(), clock.now());
 
             // Add account to store. Best effort
             if let Err(e) = inner.store.set_account_announcement(account_id, &announcement) {
@@ -90,8 +128,15 @@ impl AnnounceAccountCache {
     }
 
     /// Find peer that owns this AccountId.
-    pub(crate) fn get_account_owner(&self, account_id: &AccountId) -> Option<PeerId> {
-        self.0.lock().get_announce(account_id).map(|announce_account| announce_account.peer_id)
+    pub(crate) fn get_account_owner(
+        &self,
+        clock: &time::Clock,
+        account_id: &AccountId,
+    ) -> Option<PeerId> {
+        self.0
+            .lock()
+            .get_announce(clock, account_id)
+            .map(|announce_account| announce_account.peer_id)
     }
 
     /// Public interface for `account_peers`.
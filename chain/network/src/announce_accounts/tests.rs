@@ -1,17 +1,21 @@
 use crate::announce_accounts::*;
 use crate::test_utils::{random_epoch_id, random_peer_id};
+use near_async::time;
 use near_crypto::Signature;
 use near_primitives::network::AnnounceAccount;
 
+const TTL: time::Duration = time::Duration::hours(1);
+
 #[test]
 fn announcement_same_epoch() {
+    let clock = time::FakeClock::default();
     let store = crate::store::Store::from(near_store::db::TestDB::new());
 
     let peer_id0 = random_peer_id();
     let peer_id1 = random_peer_id();
     let epoch_id0 = random_epoch_id();
 
-    let announcements_cache = AnnounceAccountCache::new(store);
+    let announcements_cache = AnnounceAccountCache::new(store, TTL);
 
     let announce0 = AnnounceAccount {
         account_id: "near0".parse().unwrap(),
@@ -31,20 +35,28 @@ fn announcement_same_epoch() {
     // Adding multiple announcements for the same account_id and epoch_id.
     // The first one should win.
     assert_eq!(
-        announcements_cache.add_accounts(vec![announce0.clone(), announce1.clone()]),
+        announcements_cache
+            .add_accounts(&clock.clock(), vec![announce0.clone(), announce1.clone()]),
         vec![announce0.clone()]
     );
     assert_eq!(announcements_cache.get_announcements(), vec![announce0.clone()]);
-    assert_eq!(announcements_cache.get_account_owner(&announce0.account_id).unwrap(), peer_id0);
+    assert_eq!(
+        announcements_cache.get_account_owner(&clock.clock(), &announce0.account_id).unwrap(),
+        peer_id0
+    );
 
     // Adding a conflicting announcement later. Should be a noop.
-    assert_eq!(announcements_cache.add_accounts(vec![announce1]), vec![]);
+    assert_eq!(announcements_cache.add_accounts(&clock.clock(), vec![announce1]), vec![]);
     assert_eq!(announcements_cache.get_announcements(), vec![announce0.clone()]);
-    assert_eq!(announcements_cache.get_account_owner(&announce0.account_id).unwrap(), peer_id0);
+    assert_eq!(
+        announcements_cache.get_account_owner(&clock.clock(), &announce0.account_id).unwrap(),
+        peer_id0
+    );
 }
 
 #[test]
 fn dont_load_on_build() {
+    let clock = time::FakeClock::default();
     let store = crate::store::Store::from(near_store::db::TestDB::new());
 
     let peer_id0 = random_peer_id();
@@ -52,7 +64,7 @@ fn dont_load_on_build() {
     let epoch_id0 = random_epoch_id();
     let epoch_id1 = random_epoch_id();
 
-    let announcements_cache = AnnounceAccountCache::new(store.clone());
+    let announcements_cache = AnnounceAccountCache::new(store.clone(), TTL);
 
     let announce0 = AnnounceAccount {
         account_id: "near0".parse().unwrap(),
@@ -69,25 +81,26 @@ fn dont_load_on_build() {
         signature: Signature::default(),
     };
 
-    announcements_cache.add_accounts(vec![announce0.clone()]);
-    announcements_cache.add_accounts(vec![announce1.clone()]);
+    announcements_cache.add_accounts(&clock.clock(), vec![announce0.clone()]);
+    announcements_cache.add_accounts(&clock.clock(), vec![announce1.clone()]);
     let accounts: Vec<AnnounceAccount> = announcements_cache.get_announcements();
     assert!(vec![announce0, announce1].iter().all(|announce| { accounts.contains(&announce) }));
     assert_eq!(accounts.len(), 2);
 
-    let announcements_cache1 = AnnounceAccountCache::new(store);
+    let announcements_cache1 = AnnounceAccountCache::new(store, TTL);
     assert_eq!(announcements_cache1.get_announcements().len(), 0);
 }
 
 #[test]
 fn load_from_disk() {
+    let clock = time::FakeClock::default();
     let store = crate::store::Store::from(near_store::db::TestDB::new());
 
     let peer_id0 = random_peer_id();
     let epoch_id0 = random_epoch_id();
 
-    let announcements_cache = AnnounceAccountCache::new(store.clone());
-    let announcements_cache1 = AnnounceAccountCache::new(store);
+    let announcements_cache = AnnounceAccountCache::new(store.clone(), TTL);
+    let announcements_cache1 = AnnounceAccountCache::new(store, TTL);
 
     let announce0 = AnnounceAccount {
         account_id: "near0".parse().unwrap(),
@@ -97,12 +110,68 @@ fn load_from_disk() {
     };
 
     // Announcement is added to first cache and to disk
-    announcements_cache.add_accounts(vec![announce0.clone()]);
+    announcements_cache.add_accounts(&clock.clock(), vec![announce0.clone()]);
     assert_eq!(announcements_cache.get_announcements().len(), 1);
     // Second cache is empty
     assert_eq!(announcements_cache1.get_announcements().len(), 0);
     // Try to find this peer and load it from disk
-    assert_eq!(announcements_cache1.get_account_owner(&announce0.account_id).unwrap(), peer_id0);
+    assert_eq!(
+        announcements_cache1.get_account_owner(&clock.clock(), &announce0.account_id).unwrap(),
+        peer_id0
+    );
     // Second cache should contain account loaded from disk
     assert_eq!(announcements_cache1.get_announcements().len(), 1);
 }
+
+#[test]
+fn refresh() {
+    let clock = time::FakeClock::default();
+    let store = crate::store::Store::from(near_store::db::TestDB::new());
+    let announcements_cache = AnnounceAccountCache::new(store.clone(), TTL);
+
+    let announce0 = AnnounceAccount {
+        account_id: "near0".parse().unwrap(),
+        peer_id: random_peer_id(),
+        epoch_id: random_epoch_id(),
+        signature: Signature::default(),
+    };
+    // Same account and epoch, but announced by the node the validator moved to.
+    let announce1 = AnnounceAccount { peer_id: random_peer_id(), ..announce0.clone() };
+
+    assert_eq!(
+        announcements_cache.add_accounts(&clock.clock(), vec![announce0.clone()]),
+        vec![announce0.clone()]
+    );
+
+    // Refreshes are broadcasted only once per half of the ttl.
+    clock.advance(TTL / 4);
+    assert_eq!(announcements_cache.add_accounts(&clock.clock(), vec![announce0.clone()]), vec![]);
+    clock.advance(TTL / 4);
+    assert_eq!(
+        announcements_cache.add_accounts(&clock.clock(), vec![announce0.clone()]),
+        vec![announce0.clone()]
+    );
+
+    // A conflicting announcement is ignored until the old one has gone
+    // unrefreshed for the ttl.
+    clock.advance(TTL / 2);
+    assert_eq!(announcements_cache.add_accounts(&clock.clock(), vec![announce1.clone()]), vec![]);
+    clock.advance(TTL / 2);
+    assert_eq!(
+        announcements_cache.add_accounts(&clock.clock(), vec![announce1.clone()]),
+        vec![announce1.clone()]
+    );
+    assert_eq!(
+        announcements_cache.get_account_owner(&clock.clock(), &announce0.account_id).unwrap(),
+        announce1.peer_id
+    );
+
+    // Announcements which aren't refreshed stay in the cache and on disk.
+    clock.advance(TTL * 2);
+    assert_eq!(announcements_cache.get_announcements(), vec![announce1.clone()]);
+    let announcements_cache1 = AnnounceAccountCache::new(store, TTL);
+    assert_eq!(
+        announcements_cache1.get_account_owner(&clock.clock(), &announce0.account_id).unwrap(),
+        announce1.peer_id
+    );
+}
@@ -1,3 +1,4 @@
+use near_primitives::network::PeerId;
 use std::collections::HashSet;
 use std::net;
 
@@ -7,10 +8,11 @@ use std::net;
 /// it is presented as IPv6.
 /// TODO: alternatively we could use IpAddr::to_canonical(), but then the variants of
 /// the Entry enum would have to be private.
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub enum Entry {
     Ip(net::Ipv6Addr),
     IpPort(net::Ipv6Addr, u16),
+    PeerId(PeerId),
 }
 
 impl Entry {
@@ -32,19 +34,29 @@ impl Entry {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("expected IP, IP:port or PeerId, got {0:?}")]
+pub struct ParseEntryError(String);
+
 impl std::str::FromStr for Entry {
-    type Err = std::net::AddrParseError;
+    type Err = ParseEntryError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.parse::<std::net::IpAddr>() {
-            Ok(ip) => Ok(Entry::from_ip(ip)),
-            Err(_) => Ok(Entry::from_addr(s.parse::<net::SocketAddr>()?)),
+        if let Ok(ip) = s.parse::<net::IpAddr>() {
+            return Ok(Entry::from_ip(ip));
+        }
+        if let Ok(addr) = s.parse::<net::SocketAddr>() {
+            return Ok(Entry::from_addr(addr));
+        }
+        match s.parse::<near_crypto::PublicKey>() {
+            Ok(key) => Ok(Entry::PeerId(PeerId::new(key))),
+            Err(_) => Err(ParseEntryError(s.to_string())),
         }
     }
 }
 
-/// A blacklist for socket addresses.  Supports adding individual IP:port tuples
-/// to the blacklist or entire IPs.
+/// A blacklist for socket addresses and peers.  Supports adding individual
+/// IP:port tuples to the blacklist, entire IPs or PeerIds.
 #[derive(Debug, Default, Clone)]
 pub struct Blacklist(HashSet<Entry>);
 
@@ -61,6 +73,11 @@ impl Blacklist {
     pub fn contains(&self, addr: net::SocketAddr) -> bool {
         self.0.contains(&Entry::from_ip(addr.ip())) || self.0.contains(&Entry::from_addr(addr))
     }
+
+    /// Returns whether given peer is on the blacklist.
+    pub fn contains_peer(&self, peer_id: &PeerId) -> bool {
+        self.0.contains(&Entry::PeerId(peer_id.clone()))
+    }
 }
 
 #[cfg(test)]
@@ -94,6 +111,9 @@ mod test {
             Entry::from_addr(net::SocketAddr::new(LO4, 42)),
             parse("[::ffff:127.0.0.1]:42").unwrap()
         );
+
+        let peer_id = PeerId::random();
+        assert_eq!(Entry::PeerId(peer_id.clone()), parse(&peer_id.to_string()).unwrap());
     }
 
     #[test]
@@ -123,5 +143,11 @@ mod test {
         assert!(blacklist.contains(SocketAddr::new(mapped_lo4, 8080)));
         assert!(blacklist.contains(SocketAddr::new(mapped_ip, 42)));
         assert!(!blacklist.contains(SocketAddr::new(mapped_ip, 8080)));
+
+        let peer_id = PeerId::random();
+        let blacklist: Blacklist = [Entry::PeerId(peer_id.clone())].into_iter().collect();
+        assert!(blacklist.contains_peer(&peer_id));
+        assert!(!blacklist.contains_peer(&PeerId::random()));
+        assert!(!blacklist.contains(SocketAddr::new(LO4, 42)));
     }
 }
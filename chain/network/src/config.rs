@@ -17,7 +17,8 @@ use near_primitives::network::PeerId;
 use near_primitives::test_utils::create_test_signer;
 use near_primitives::types::AccountId;
 use near_primitives::validator_signer::ValidatorSigner;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::Arc;
 
 /// How much height horizon to give to consider peer up to date.
@@ -82,6 +83,47 @@ impl ValidatorConfig {
     }
 }
 
+/// Limit of inbound TIER2 connections from a single IP subnet.
+#[derive(Clone, Debug)]
+pub struct InboundSubnetLimit {
+    /// Maximal number of inbound connections from a single subnet.
+    pub max_peers: u32,
+    /// Length of the network prefix of IPv4 subnets.
+    pub ipv4_prefix_len: u8,
+    /// Length of the network prefix of IPv6 subnets.
+    pub ipv6_prefix_len: u8,
+}
+
+impl InboundSubnetLimit {
+    /// Returns the subnet of the IP, or None if the IP is not subject to the limit.
+    /// Loopback, private and link-local IPs are not, so that local networks are not affected.
+    pub fn subnet(&self, ip: IpAddr) -> Option<IpAddr> {
+        let ip = match ip {
+            IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+            ip => ip,
+        };
+        match ip {
+            IpAddr::V4(ip) => {
+                if ip.is_loopback() || ip.is_private() || ip.is_link_local() {
+                    return None;
+                }
+                let mask = u32::MAX.checked_shl(32 - self.ipv4_prefix_len as u32).unwrap_or(0);
+                Some(IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask)))
+            }
+            IpAddr::V6(ip) => {
+                // Unique local (fc00::/7) and link-local (fe80::/10) addresses are the IPv6
+                // counterparts of the private and link-local IPv4 addresses.
+                let first = ip.segments()[0];
+                if ip.is_loopback() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80 {
+                    return None;
+                }
+                let mask = u128::MAX.checked_shl(128 - self.ipv6_prefix_len as u32).unwrap_or(0);
+                Some(IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask)))
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Tier1 {
     /// Interval between attempts to connect to proxies of other TIER1 nodes.
@@ -119,12 +161,16 @@ impl SocketOptions {
 #[derive(Clone)]
 pub struct NetworkConfig {
     pub node_addr: Option<tcp::ListenerAddr>,
+    /// Address advertised to peers, if different from `node_addr`.
+    pub advertised_addr: Option<std::net::SocketAddr>,
     pub node_key: SecretKey,
     pub validator: ValidatorConfig,
 
     pub peer_store: peer_store::Config,
     pub snapshot_hosts: snapshot_hosts::Config,
     pub whitelist_nodes: Vec<PeerInfo>,
+    /// Whether to connect only to the whitelisted nodes.
+    pub whitelist_only: bool,
     pub handshake_timeout: time::Duration,
 
     /// Whether to re-establish connection to known reliable peers from previous neard run(s).
@@ -134,6 +180,13 @@ pub struct NetworkConfig {
     pub monitor_peers_max_period: time::Duration,
     /// Maximum number of active peers. Hard limit.
     pub max_num_peers: u32,
+    /// Maximum number of inbound peers, whitelisted ones excluded.
+    pub max_num_inbound_peers: u32,
+    /// Maximum number of outbound peers, whitelisted ones excluded.
+    pub max_num_outbound_peers: u32,
+    /// Limit of inbound connections from a single IP subnet, which makes it harder to
+    /// eclipse the node with many peers running in a single data center.
+    pub inbound_subnet_limit: Option<InboundSubnetLimit>,
     /// Minimum outbound connections a peer should have to avoid eclipse attacks.
     pub minimum_outbound_peers: u32,
     /// Lower bound of the ideal number of connections.
@@ -175,6 +228,30 @@ pub struct NetworkConfig {
     pub outbound_disabled: bool,
     /// Flag to disable inbound connections. When true, all the incoming handshake/connection requests will be rejected.
     pub inbound_disabled: bool,
+    /// Whether to compress large messages (blocks, chunk parts, state parts) sent to peers
+    /// which support compression, trading CPU for bandwidth.
+    pub message_compression: bool,
+    /// Whether to broadcast announcements of new blocks instead of full blocks to peers
+    /// which support them. Peers request the body only if they don't have the block yet.
+    pub block_announcements: bool,
+    /// Time to wait for a block requested after an announcement, before requesting it
+    /// from another peer which announced it.
+    pub block_fetch_timeout: time::Duration,
+    /// Limits on bytes per minute sent to a single peer, by message type (as in the
+    /// near_peer_message_sent_by_type_bytes metric). Messages over the limit are dropped.
+    pub send_bandwidth_limits: HashMap<String, u64>,
+    /// Maximal number of block, header and state requests from peers served at the same time.
+    /// Further requests wait until one of them is served.
+    pub max_concurrent_sync_requests: usize,
+    /// Maximal number of block, header and state requests from a single peer being served
+    /// or waiting to be served. Further requests from the peer are dropped. It should exceed
+    /// `sync_max_block_requests` of the peers' client config, which is how many blocks a
+    /// syncing node requests at once.
+    pub max_concurrent_sync_requests_per_peer: usize,
+    /// Size of the send queue of a connection (in bytes), above which low priority messages
+    /// (transactions, peer exchange) sent to the peer are dropped, so that an overloaded
+    /// connection doesn't delay consensus messages even more. None disables load shedding.
+    pub load_shedding_send_queue_bytes: Option<u64>,
     /// Whether this is an archival node.
     pub archive: bool,
     /// Maximal rate at which SyncAccountsData can be broadcasted.
@@ -201,6 +278,51 @@ pub struct NetworkConfig {
         near_async::messaging::Sender<crate::peer_manager::peer_manager_actor::Event>,
 }
 
+/// Parses the comma separated list of boot nodes.
+///
+/// Boot nodes given by `host:port` rather than an IP address are returned separately:
+/// they are resolved asynchronously by the PeerManagerActor, so that an unavailable DNS
+/// server doesn't block (or fail) the node startup.
+fn parse_boot_nodes(
+    boot_nodes: &str,
+) -> anyhow::Result<(Vec<PeerInfo>, Vec<peer_store::BootNodeHost>)> {
+    let mut peers = vec![];
+    let mut hosts = vec![];
+    if boot_nodes.is_empty() {
+        return Ok((peers, hosts));
+    }
+    for chunk in boot_nodes.split(',') {
+        let parts: Vec<&str> = chunk.split('@').collect();
+        let is_host = match parts.get(1) {
+            Some(addr) => {
+                addr.parse::<std::net::SocketAddr>().is_err()
+                    && addr.rsplit_once(':').is_some_and(|(host, port)| {
+                        !host.is_empty() && !host.contains(':') && port.parse::<u16>().is_ok()
+                    })
+            }
+            None => false,
+        };
+        if !is_host {
+            peers.push(chunk.parse().with_context(|| format!("boot_nodes: {chunk}"))?);
+            continue;
+        }
+        if parts.len() > 3 {
+            anyhow::bail!("boot_nodes: invalid format: {chunk}");
+        }
+        hosts.push(peer_store::BootNodeHost {
+            id: PeerId::new(parts[0].parse().with_context(|| format!("boot_nodes: {chunk}"))?),
+            host: parts[1].to_string(),
+            account_id: match parts.get(2) {
+                Some(account_id) => {
+                    Some(account_id.parse().with_context(|| format!("boot_nodes: {chunk}"))?)
+                }
+                None => None,
+            },
+        });
+    }
+    Ok((peers, hosts))
+}
+
 impl NetworkConfig {
     /// Overrides values of NetworkConfig with values for the JSON config.
     /// We need all the values from NetworkConfig to be configurable.
@@ -283,6 +405,7 @@ impl NetworkConfig {
                 }
             }
         }
+        let (boot_nodes, boot_node_hosts) = parse_boot_nodes(&cfg.boot_nodes)?;
         let mut this = Self {
             node_key,
             validator: ValidatorConfig {
@@ -299,16 +422,13 @@ impl NetworkConfig {
                     addr.parse().context("Failed to parse SocketAddr")?,
                 )),
             },
+            advertised_addr: match cfg.advertised_addr.as_str() {
+                "" => None,
+                addr => Some(addr.parse().context("Failed to parse advertised_addr")?),
+            },
             peer_store: peer_store::Config {
-                boot_nodes: if cfg.boot_nodes.is_empty() {
-                    vec![]
-                } else {
-                    cfg.boot_nodes
-                        .split(',')
-                        .map(|chunk| chunk.parse())
-                        .collect::<Result<_, _>>()
-                        .context("boot_nodes")?
-                },
+                boot_nodes,
+                boot_node_hosts,
                 blacklist: cfg
                     .blacklist
                     .iter()
@@ -317,6 +437,15 @@ impl NetworkConfig {
                     .context("failed to parse blacklist")?,
                 peer_states_cache_size: cfg.peer_states_cache_size,
                 connect_only_to_boot_nodes: cfg.experimental.connect_only_to_boot_nodes,
+                private_peers: if cfg.private_peers.is_empty() {
+                    vec![]
+                } else {
+                    cfg.private_peers
+                        .split(',')
+                        .map(|chunk| chunk.parse().map(PeerId::new))
+                        .collect::<Result<_, _>>()
+                        .context("private_peers")?
+                },
                 ban_window: cfg.ban_window.try_into()?,
                 peer_expiration_duration: cfg.peer_expiration_duration.try_into()?,
             },
@@ -339,10 +468,23 @@ impl NetworkConfig {
                     .collect::<anyhow::Result<_>>()
                     .context("whitelist_nodes")?
             },
+            whitelist_only: cfg.whitelist_only,
             connect_to_reliable_peers_on_startup: true,
             handshake_timeout: cfg.handshake_timeout.try_into()?,
             monitor_peers_max_period: cfg.monitor_peers_max_period.try_into()?,
             max_num_peers: cfg.max_num_peers,
+            max_num_inbound_peers: cfg
+                .max_num_inbound_peers
+                .unwrap_or(cfg.max_num_peers.saturating_sub(cfg.minimum_outbound_peers)),
+            max_num_outbound_peers: cfg.max_num_outbound_peers.unwrap_or(cfg.max_num_peers),
+            inbound_subnet_limit: match cfg.experimental.max_inbound_peers_per_subnet {
+                0 => None,
+                max_peers => Some(InboundSubnetLimit {
+                    max_peers,
+                    ipv4_prefix_len: cfg.experimental.inbound_subnet_ipv4_prefix_len,
+                    ipv6_prefix_len: cfg.experimental.inbound_subnet_ipv6_prefix_len,
+                }),
+            },
             minimum_outbound_peers: cfg.minimum_outbound_peers,
             ideal_connections_lo: cfg.ideal_connections_lo,
             ideal_connections_hi: cfg.ideal_connections_hi,
@@ -373,6 +515,18 @@ impl NetworkConfig {
                 enable_outbound: cfg.experimental.tier1_enable_outbound,
             }),
             inbound_disabled: cfg.experimental.inbound_disabled,
+            message_compression: cfg.experimental.message_compression,
+            block_announcements: cfg.experimental.block_announcements,
+            block_fetch_timeout: cfg.experimental.block_fetch_timeout.try_into()?,
+            send_bandwidth_limits: cfg.experimental.send_bandwidth_limits,
+            max_concurrent_sync_requests: cfg.experimental.max_concurrent_sync_requests,
+            max_concurrent_sync_requests_per_peer: cfg
+                .experimental
+                .max_concurrent_sync_requests_per_peer,
+            load_shedding_send_queue_bytes: match cfg.experimental.load_shedding_send_queue_bytes {
+                0 => None,
+                bytes => Some(bytes),
+            },
             skip_tombstones: if cfg.experimental.skip_sending_tombstones_seconds > 0 {
                 Some(time::Duration::seconds(cfg.experimental.skip_sending_tombstones_seconds))
             } else {
@@ -393,6 +547,11 @@ impl NetworkConfig {
         PeerId::new(self.node_key.public_key())
     }
 
+    /// Address under which other nodes can connect to this node.
+    pub fn public_addr(&self) -> Option<std::net::SocketAddr> {
+        self.advertised_addr.or(self.node_addr.as_ref().map(|a| **a))
+    }
+
     /// TEST-ONLY: Returns network config with given seed used for peer id.
     pub fn from_seed(seed: &str, node_addr: tcp::ListenerAddr) -> Self {
         let node_key = SecretKey::from_seed(KeyType::ED25519, seed);
@@ -408,25 +567,32 @@ impl NetworkConfig {
         };
         NetworkConfig {
             node_addr: Some(node_addr),
+            advertised_addr: None,
             node_key,
             validator,
             peer_store: peer_store::Config {
                 boot_nodes: vec![],
+                boot_node_hosts: vec![],
                 blacklist: blacklist::Blacklist::default(),
                 peer_states_cache_size: 1000,
                 ban_window: time::Duration::seconds(1),
                 peer_expiration_duration: time::Duration::seconds(60 * 60),
                 connect_only_to_boot_nodes: false,
+                private_peers: vec![],
             },
             snapshot_hosts: snapshot_hosts::Config {
                 snapshot_hosts_cache_size: 1000,
                 part_selection_cache_batch_size: 10,
             },
             whitelist_nodes: vec![],
+            whitelist_only: false,
             handshake_timeout: time::Duration::seconds(5),
             connect_to_reliable_peers_on_startup: true,
             monitor_peers_max_period: time::Duration::seconds(100),
             max_num_peers: 40,
+            max_num_inbound_peers: 35,
+            max_num_outbound_peers: 40,
+            inbound_subnet_limit: None,
             minimum_outbound_peers: 5,
             ideal_connections_lo: 30,
             ideal_connections_hi: 35,
@@ -443,6 +609,13 @@ impl NetworkConfig {
             push_info_period: time::Duration::milliseconds(100),
             outbound_disabled: false,
             inbound_disabled: false,
+            message_compression: false,
+            block_announcements: false,
+            block_fetch_timeout: time::Duration::seconds(2),
+            send_bandwidth_limits: HashMap::new(),
+            max_concurrent_sync_requests: 32,
+            max_concurrent_sync_requests_per_peer: 16,
+            load_shedding_send_queue_bytes: None,
             archive: false,
             accounts_data_broadcast_rate_limit: rate::Limit { qps: 100., burst: 1000000 },
             snapshot_hosts_broadcast_rate_limit: rate::Limit { qps: 100., burst: 1000000 },
@@ -481,6 +654,28 @@ impl NetworkConfig {
             );
         }
 
+        if !(self.max_num_inbound_peers <= self.max_num_peers
+            && self.max_num_outbound_peers <= self.max_num_peers)
+        {
+            anyhow::bail!(
+                "max_num_inbound_peers({}) and max_num_outbound_peers({}) can't exceed max_num_peers({}).",
+                self.max_num_inbound_peers, self.max_num_outbound_peers, self.max_num_peers
+            );
+        }
+
+        if let Some(addr) = self.advertised_addr {
+            if addr.ip().is_unspecified() || addr.port() == 0 {
+                anyhow::bail!("advertised_addr({addr}) must specify an IP and a port");
+            }
+            if self.node_addr.is_none() {
+                anyhow::bail!("advertised_addr is set but addr is empty");
+            }
+        }
+
+        if self.whitelist_only && self.whitelist_nodes.is_empty() {
+            anyhow::bail!("whitelist_only is set but whitelist_nodes is empty");
+        }
+
         if !(self.safe_set_size > self.minimum_outbound_peers) {
             anyhow::bail!(
                 "safe_set_size({}) must be larger than minimum_outbound_peers({}).",
@@ -496,6 +691,21 @@ impl NetworkConfig {
             );
         }
 
+        if let Some(limit) = &self.inbound_subnet_limit {
+            if limit.ipv4_prefix_len > 32 || limit.ipv6_prefix_len > 128 {
+                anyhow::bail!("Invalid inbound subnet prefix lengths: {limit:?}");
+            }
+        }
+
+        if self.max_concurrent_sync_requests == 0 || self.max_concurrent_sync_requests_per_peer == 0
+        {
+            anyhow::bail!(
+                "max_concurrent_sync_requests({}) and max_concurrent_sync_requests_per_peer({}) must be positive",
+                self.max_concurrent_sync_requests,
+                self.max_concurrent_sync_requests_per_peer
+            );
+        }
+
         if !(self.max_send_peers <= PEERS_RESPONSE_MAX_PEERS) {
             anyhow::bail!(
                 "max_send_peers({}) can be at most {}",
@@ -573,13 +783,90 @@ mod test {
         nc.ideal_connections_hi = nc.max_num_peers + 1;
         assert!(nc.verify().is_err());
 
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.max_num_inbound_peers = nc.max_num_peers + 1;
+        assert!(nc.verify().is_err());
+
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.max_num_outbound_peers = nc.max_num_peers + 1;
+        assert!(nc.verify().is_err());
+
         let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
         nc.safe_set_size = nc.minimum_outbound_peers;
         assert!(nc.verify().is_err());
 
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.whitelist_only = true;
+        assert!(nc.verify().is_err());
+
         let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
         nc.peer_recent_time_window = UPDATE_INTERVAL_LAST_TIME_RECEIVED_MESSAGE;
         assert!(nc.verify().is_err());
+
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.advertised_addr = Some("0.0.0.0:24567".parse().unwrap());
+        assert!(nc.verify().is_err());
+
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.max_concurrent_sync_requests_per_peer = 0;
+        assert!(nc.verify().is_err());
+
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.inbound_subnet_limit = Some(config::InboundSubnetLimit {
+            max_peers: 1,
+            ipv4_prefix_len: 33,
+            ipv6_prefix_len: 48,
+        });
+        assert!(nc.verify().is_err());
+
+        let mut nc = config::NetworkConfig::from_seed("123", tcp::ListenerAddr::reserve_for_test());
+        nc.advertised_addr = Some("8.8.8.8:24567".parse().unwrap());
+        assert_eq!(nc.public_addr(), nc.advertised_addr);
+        assert!(nc.verify().is_ok());
+    }
+
+    #[test]
+    fn test_inbound_subnet() {
+        let limit =
+            config::InboundSubnetLimit { max_peers: 1, ipv4_prefix_len: 24, ipv6_prefix_len: 48 };
+        let subnet = |ip: &str| limit.subnet(ip.parse().unwrap()).map(|ip| ip.to_string());
+        assert_eq!(subnet("8.8.8.8"), Some("8.8.8.0".to_string()));
+        assert_eq!(subnet("::ffff:8.8.4.4"), Some("8.8.4.0".to_string()));
+        assert_eq!(subnet("2001:db8:1:2::1"), Some("2001:db8:1::".to_string()));
+        assert_eq!(subnet("127.0.0.1"), None);
+        assert_eq!(subnet("10.1.2.3"), None);
+        assert_eq!(subnet("::1"), None);
+        assert_eq!(subnet("fd12:3456::1"), None);
+        assert_eq!(subnet("fe80::1"), None);
+        assert_eq!(subnet("fec0::1"), Some("fec0::".to_string()));
+
+        let limit =
+            config::InboundSubnetLimit { max_peers: 1, ipv4_prefix_len: 0, ipv6_prefix_len: 128 };
+        assert_eq!(limit.subnet("8.8.8.8".parse().unwrap()), Some("0.0.0.0".parse().unwrap()));
+        assert_eq!(
+            limit.subnet("2001:db8::1".parse().unwrap()),
+            Some("2001:db8::1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_boot_nodes() {
+        let id = "ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV";
+        let (peers, hosts) = config::parse_boot_nodes(&format!(
+            "{id}@127.0.0.1:24567,{id}@[::1]:24567@test.near,{id}@boot.invalid:24567@test.near,{id}@boot.invalid:24568"
+        ))
+        .unwrap();
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[1].addr, Some("[::1]:24567".parse().unwrap()));
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].id.to_string(), id);
+        assert_eq!(hosts[0].host, "boot.invalid:24567");
+        assert_eq!(hosts[0].account_id, Some("test.near".parse().unwrap()));
+        assert_eq!(hosts[1].host, "boot.invalid:24568");
+        assert_eq!(hosts[1].account_id, None);
+
+        assert!(config::parse_boot_nodes(&format!("{id}@boot.invalid:24567@bad@account")).is_err());
+        assert_eq!(config::parse_boot_nodes("").unwrap(), (vec![], vec![]));
     }
 
     #[test]
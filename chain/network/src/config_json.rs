@@ -2,6 +2,7 @@ use crate::network_protocol::PeerAddr;
 use crate::rate_limits::messages_limits;
 use crate::stun;
 use near_async::time::Duration;
+use std::collections::HashMap;
 
 /// Time to persist Accounts Id in the router without removing them in seconds.
 pub const TTL_ACCOUNT_ID_ROUTER: i64 = 60 * 60;
@@ -87,10 +88,21 @@ pub(crate) fn default_trusted_stun_servers() -> Vec<stun::ServerAddr> {
 pub struct Config {
     /// Local address to listen for incoming connections.
     pub addr: String,
+    /// Address under which this node is reachable by other nodes, if different from
+    /// `addr` (e.g. when running behind NAT, in a container or behind a load balancer).
+    /// It is advertised to peers in the handshake in place of `addr` and, if this node is
+    /// a validator with empty `public_addrs`, used as its TIER1 proxy instead of querying
+    /// `trusted_stun_servers`. Format `<ip>:<port>`, empty means that `addr` is advertised.
+    /// Peers ignore an advertised address whose IP differs from the one they see this
+    /// node's connection coming from.
+    #[serde(default)]
+    pub advertised_addr: String,
     /// Comma separated list of nodes to connect to.
     /// Examples:
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@31.192.22.209:24567
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@nearnode.com:24567
+    /// Hostnames are resolved in the background (retried until they resolve)
+    /// and re-resolved periodically, so that a DNS outage doesn't prevent the node from starting.
     pub boot_nodes: String,
     /// Comma separated list of whitelisted nodes. Inbound connections from the nodes on
     /// the whitelist are accepted even if the limit of the inbound connection has been reached.
@@ -100,9 +112,30 @@ pub struct Config {
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@nearnode.com:24567
     #[serde(default)]
     pub whitelist_nodes: String,
+    /// If true, connections are established only with the nodes from whitelist_nodes and
+    /// all other peers are rejected at handshake. Meant for private networks.
+    #[serde(default)]
+    pub whitelist_only: bool,
+    /// Comma separated list of PeerIds of nodes whose addresses are never shared with other
+    /// peers. Meant for proxy (sentry) nodes of a validator which hides its IP behind them.
+    /// The validator in turn lists the proxies as its boot_nodes, sets
+    /// experimental.connect_only_to_boot_nodes and experimental.inbound_disabled and
+    /// whitelists the proxies, so that it is connected only to the proxies and messages
+    /// routed to its account reach it through them.
+    #[serde(default)]
+    pub private_peers: String,
     /// Maximum number of active peers. Hard limit.
     #[serde(default = "default_max_num_peers")]
     pub max_num_peers: u32,
+    /// Maximum number of inbound peers. Whitelisted nodes are accepted even if the
+    /// limit has been reached. Defaults to `max_num_peers - minimum_outbound_peers`,
+    /// which keeps room for the connections we initiate ourselves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_num_inbound_peers: Option<u32>,
+    /// Maximum number of outbound peers. Whitelisted nodes are connected to even if the
+    /// limit has been reached. Defaults to `max_num_peers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_num_outbound_peers: Option<u32>,
     /// Minimum outbound connections a peer should have to avoid eclipse attacks.
     #[serde(default = "default_minimum_outbound_connections")]
     pub minimum_outbound_peers: u32,
@@ -136,8 +169,9 @@ pub struct Config {
     /// Ban window for peers who misbehave.
     #[serde(with = "near_async::time::serde_duration_as_std")]
     pub ban_window: Duration,
-    /// List of addresses that will not be accepted as valid neighbors.
-    /// It can be IP:Port or IP (to blacklist all connections coming from this address).
+    /// List of addresses and peers that will not be accepted as valid neighbors.
+    /// It can be IP:Port, IP (to blacklist all connections coming from this address)
+    /// or PeerId.
     #[serde(default)]
     pub blacklist: Vec<String>,
     /// Time to persist Accounts Id in the router without removing them in seconds.
@@ -231,6 +265,34 @@ fn default_tier1_new_connections_per_attempt() -> u64 {
     50
 }
 
+fn default_max_inbound_peers_per_subnet() -> u32 {
+    8
+}
+
+fn default_inbound_subnet_ipv4_prefix_len() -> u8 {
+    24
+}
+
+fn default_inbound_subnet_ipv6_prefix_len() -> u8 {
+    48
+}
+
+fn default_block_fetch_timeout() -> Duration {
+    Duration::seconds(2)
+}
+
+fn default_max_concurrent_sync_requests() -> usize {
+    32
+}
+
+fn default_max_concurrent_sync_requests_per_peer() -> usize {
+    16
+}
+
+fn default_load_shedding_send_queue_bytes() -> u64 {
+    16 * bytesize::MIB
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct ExperimentalConfig {
     // If true - don't allow any inbound connections.
@@ -265,6 +327,50 @@ pub struct ExperimentalConfig {
     #[serde(default = "default_tier1_new_connections_per_attempt")]
     pub tier1_new_connections_per_attempt: u64,
 
+    /// See `near_network::config::NetworkConfig::message_compression`.
+    #[serde(default)]
+    pub message_compression: bool,
+
+    /// See `near_network::config::NetworkConfig::block_announcements`.
+    #[serde(default)]
+    pub block_announcements: bool,
+
+    /// See `near_network::config::NetworkConfig::block_fetch_timeout`.
+    #[serde(default = "default_block_fetch_timeout")]
+    #[serde(with = "near_async::time::serde_duration_as_std")]
+    pub block_fetch_timeout: Duration,
+
+    /// See `near_network::config::NetworkConfig::send_bandwidth_limits`.
+    #[serde(default)]
+    pub send_bandwidth_limits: HashMap<String, u64>,
+
+    /// See `near_network::config::NetworkConfig::max_concurrent_sync_requests`.
+    #[serde(default = "default_max_concurrent_sync_requests")]
+    pub max_concurrent_sync_requests: usize,
+
+    /// See `near_network::config::NetworkConfig::max_concurrent_sync_requests_per_peer`.
+    #[serde(default = "default_max_concurrent_sync_requests_per_peer")]
+    pub max_concurrent_sync_requests_per_peer: usize,
+
+    /// See `near_network::config::NetworkConfig::load_shedding_send_queue_bytes`.
+    /// 0 disables load shedding.
+    #[serde(default = "default_load_shedding_send_queue_bytes")]
+    pub load_shedding_send_queue_bytes: u64,
+
+    /// Maximal number of inbound TIER2 connections from a single IP subnet.
+    /// Connections from loopback and private IPs and from whitelisted nodes are not limited.
+    /// 0 disables the limit.
+    #[serde(default = "default_max_inbound_peers_per_subnet")]
+    pub max_inbound_peers_per_subnet: u32,
+
+    /// Network prefix length of the IPv4 subnets for `max_inbound_peers_per_subnet`.
+    #[serde(default = "default_inbound_subnet_ipv4_prefix_len")]
+    pub inbound_subnet_ipv4_prefix_len: u8,
+
+    /// Network prefix length of the IPv6 subnets for `max_inbound_peers_per_subnet`.
+    #[serde(default = "default_inbound_subnet_ipv6_prefix_len")]
+    pub inbound_subnet_ipv6_prefix_len: u8,
+
     /// See `NetworkConfig`.
     /// Fields set here will override the NetworkConfig fields.
     #[serde(default)]
@@ -299,6 +405,16 @@ impl Default for ExperimentalConfig {
             tier1_enable_outbound: default_tier1_enable_outbound(),
             tier1_connect_interval: default_tier1_connect_interval(),
             tier1_new_connections_per_attempt: default_tier1_new_connections_per_attempt(),
+            message_compression: false,
+            block_announcements: false,
+            block_fetch_timeout: default_block_fetch_timeout(),
+            send_bandwidth_limits: HashMap::new(),
+            max_concurrent_sync_requests: default_max_concurrent_sync_requests(),
+            max_concurrent_sync_requests_per_peer: default_max_concurrent_sync_requests_per_peer(),
+            load_shedding_send_queue_bytes: default_load_shedding_send_queue_bytes(),
+            max_inbound_peers_per_subnet: default_max_inbound_peers_per_subnet(),
+            inbound_subnet_ipv4_prefix_len: default_inbound_subnet_ipv4_prefix_len(),
+            inbound_subnet_ipv6_prefix_len: default_inbound_subnet_ipv6_prefix_len(),
             network_config_overrides: Default::default(),
         }
     }
@@ -308,9 +424,14 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             addr: "0.0.0.0:24567".to_string(),
+            advertised_addr: "".to_string(),
             boot_nodes: "".to_string(),
             whitelist_nodes: "".to_string(),
+            whitelist_only: false,
+            private_peers: "".to_string(),
             max_num_peers: default_max_num_peers(),
+            max_num_inbound_peers: None,
+            max_num_outbound_peers: None,
             minimum_outbound_peers: default_minimum_outbound_connections(),
             ideal_connections_lo: default_ideal_connections_lo(),
             ideal_connections_hi: default_ideal_connections_hi(),
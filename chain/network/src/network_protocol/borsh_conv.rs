@@ -14,6 +14,9 @@ impl From<&net::Handshake> for mem::Handshake {
             sender_chain_info: x.sender_chain_info.clone(),
             partial_edge_info: x.partial_edge_info.clone(),
             owned_account: None,
+            supports_compression: false,
+            supports_block_announcements: false,
+            sender_advertised_addr: None,
         }
     }
 }
@@ -178,6 +181,7 @@ impl TryFrom<&net::PeerMessage> for mem::PeerMessage {
                 // is a reason to remove the connection from storage (for example, a peer ban).
                 // In the absence of such information, it should default to false.
                 remove_from_connection_store: false,
+                reason: mem::DisconnectReason::Unknown,
             }),
             net::PeerMessage::Challenge(c) => mem::PeerMessage::Challenge(c),
             net::PeerMessage::_HandshakeV2 => return Err(Self::Error::DeprecatedHandshakeV2),
@@ -242,6 +246,11 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
             mem::PeerMessage::BlockHeaders(bhs) => net::PeerMessage::BlockHeaders(bhs),
             mem::PeerMessage::BlockRequest(bh) => net::PeerMessage::BlockRequest(bh),
             mem::PeerMessage::Block(b) => net::PeerMessage::Block(b),
+            // This message is not supported, we translate it to an empty RoutingTableUpdate.
+            // PeerActor sends the whole block to Borsh peers instead.
+            mem::PeerMessage::BlockAnnouncement(_) => {
+                net::PeerMessage::SyncRoutingTable(net::RoutingTableUpdate::default())
+            }
             mem::PeerMessage::Transaction(t) => net::PeerMessage::Transaction(t),
             mem::PeerMessage::Routed(r) => net::PeerMessage::Routed(Box::new(r.msg.clone())),
             mem::PeerMessage::Disconnect(_) => net::PeerMessage::Disconnect,
@@ -362,6 +362,12 @@ pub struct Handshake {
     pub(crate) partial_edge_info: PartialEdgeInfo,
     /// Account owned by the sender.
     pub(crate) owned_account: Option<SignedOwnedAccount>,
+    /// Whether the sender accepts compressed messages.
+    pub(crate) supports_compression: bool,
+    /// Whether the sender accepts block announcements in place of full blocks.
+    pub(crate) supports_block_announcements: bool,
+    /// Sender's advertised addr, if it differs from the observed one.
+    pub(crate) sender_advertised_addr: Option<std::net::SocketAddr>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr)]
@@ -398,12 +404,43 @@ pub struct PeersResponse {
     pub direct_peers: Vec<PeerInfo>,
 }
 
+/// Announcement of a new block, broadcasted instead of the whole block.
+/// Receivers which don't have the block yet request it with BlockRequest.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BlockAnnouncement {
+    pub hash: CryptoHash,
+    pub height: BlockHeight,
+}
+
+impl BlockAnnouncement {
+    pub fn new(block: &Block) -> Self {
+        Self { hash: *block.hash(), height: block.header().height() }
+    }
+}
+
 /// Message sent when gracefully disconnecting from the other peer.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Disconnect {
     /// Advises the other peer to remove the connection from storage
     /// Used when it is not expected that a reconnect attempt would succeed
     pub remove_from_connection_store: bool,
+    /// Why the connection is being closed.
+    pub reason: DisconnectReason,
+}
+
+/// Reason for closing a connection, see `Disconnect`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, strum::IntoStaticStr)]
+pub enum DisconnectReason {
+    /// Not specified, e.g. sent by a peer running an older version.
+    Unknown,
+    /// The peer has reached its limit of connections.
+    TooManyPeers,
+    /// The peer has banned or blacklisted this node.
+    Banned,
+    /// The peer doesn't support any protocol version supported by this node.
+    IncompatibleVersion,
+    /// The peer is shutting down.
+    ShuttingDown,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr, strum::EnumVariantNames)]
@@ -430,6 +467,7 @@ pub enum PeerMessage {
 
     BlockRequest(CryptoHash),
     Block(Block),
+    BlockAnnouncement(BlockAnnouncement),
 
     Transaction(SignedTransaction),
     Routed(Box<RoutedMessageV2>),
@@ -456,6 +494,18 @@ pub enum Encoding {
     Proto,
 }
 
+/// Serialized messages smaller than this are never compressed; compressing
+/// them would save little bandwidth for the CPU spent.
+const COMPRESSION_THRESHOLD_BYTES: usize = 16 * 1024;
+
+/// zstd level used for compressing messages.  Messages are compressed on the
+/// hot path of sending, hence a fast level.
+const COMPRESSION_LEVEL: i32 = 1;
+
+/// Limit on size of a decompressed message, protecting against zip bombs.
+/// Same as the limit on size of a frame in the stream.
+const MAX_DECOMPRESSED_SIZE_BYTES: u64 = 512 * bytesize::MIB;
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerMessageError {
     #[error("BorshDecode")]
@@ -466,6 +516,8 @@ pub enum ParsePeerMessageError {
     ProtoDecode(#[source] protobuf::Error),
     #[error("ProtoConv")]
     ProtoConv(#[source] proto_conv::ParsePeerMessageError),
+    #[error("Decompress")]
+    Decompress(#[source] std::io::Error),
 }
 
 impl PeerMessage {
@@ -483,6 +535,60 @@ impl PeerMessage {
         }
     }
 
+    /// Serializes a message in the `Proto` encoding, compressing it if it is
+    /// large and of a type worth compressing.  Use only for peers which
+    /// declared support of compression in the handshake.
+    pub(crate) fn serialize_compressed(&self) -> Vec<u8> {
+        let bytes = self.serialize(Encoding::Proto);
+        if bytes.len() < COMPRESSION_THRESHOLD_BYTES || !self.is_compressible() {
+            return bytes;
+        }
+        let compressed = match zstd::bulk::compress(&bytes, COMPRESSION_LEVEL) {
+            Ok(compressed) if compressed.len() < bytes.len() => compressed,
+            _ => return bytes,
+        };
+        let msg = proto::PeerMessage {
+            message_type: Some(proto::peer_message::Message_type::Compressed(compressed)),
+            ..Default::default()
+        };
+        msg.write_to_bytes().unwrap()
+    }
+
+    /// Whether the message is one of the large ones which compress well.
+    /// Messages which are compressed already, like state witnesses, aren't.
+    fn is_compressible(&self) -> bool {
+        match self {
+            PeerMessage::Block(_)
+            | PeerMessage::BlockHeaders(_)
+            | PeerMessage::VersionedStateResponse(_) => true,
+            PeerMessage::Routed(msg) => matches!(
+                msg.body,
+                RoutedMessageBody::VersionedPartialEncodedChunk(_)
+                    | RoutedMessageBody::PartialEncodedChunkResponse(_)
+                    | RoutedMessageBody::PartialEncodedChunkForward(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the message is of low priority and can be dropped when the
+    /// connection is overloaded. Losing it doesn't affect consensus: transactions
+    /// are resubmitted by clients and peers are exchanged periodically.
+    pub(crate) fn is_sheddable(&self) -> bool {
+        match self {
+            PeerMessage::Transaction(_)
+            | PeerMessage::PeersRequest(_)
+            | PeerMessage::PeersResponse(_) => true,
+            PeerMessage::Routed(msg) => matches!(
+                msg.body,
+                RoutedMessageBody::ForwardTx(_)
+                    | RoutedMessageBody::TxStatusRequest(..)
+                    | RoutedMessageBody::TxStatusResponse(_)
+            ),
+            _ => false,
+        }
+    }
+
     pub(crate) fn deserialize(
         enc: Encoding,
         data: &[u8],
@@ -494,8 +600,17 @@ impl PeerMessage {
                 .try_into()
                 .map_err(ParsePeerMessageError::BorshConv)?,
             Encoding::Proto => {
-                let proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
+                let mut proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
                     .map_err(ParsePeerMessageError::ProtoDecode)?;
+                // Compressed message is unwrapped just once, a compressed message nested in
+                // it is rejected during conversion.
+                if let Some(proto::peer_message::Message_type::Compressed(compressed)) =
+                    &proto_msg.message_type
+                {
+                    let data = decompress(compressed).map_err(ParsePeerMessageError::Decompress)?;
+                    proto_msg = proto::PeerMessage::parse_from_bytes(&data)
+                        .map_err(ParsePeerMessageError::ProtoDecode)?;
+                }
                 if let Ok(extracted_span_context) = extract_span_context(&proto_msg.trace_context) {
                     span.clone().or_current().add_link(extracted_span_context);
                 }
@@ -512,6 +627,21 @@ impl PeerMessage {
     }
 }
 
+/// Decompresses a message compressed by [`PeerMessage::serialize_compressed`].
+fn decompress(compressed: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Read as _;
+    let mut data = Vec::new();
+    zstd::stream::read::Decoder::new(compressed)?
+        .take(MAX_DECOMPRESSED_SIZE_BYTES + 1)
+        .read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_DECOMPRESSED_SIZE_BYTES {
+        return Err(std::io::Error::other(format!(
+            "decompressed message exceeds {MAX_DECOMPRESSED_SIZE_BYTES} bytes"
+        )));
+    }
+    Ok(data)
+}
+
 // TODO(#1313): Use Box
 #[derive(
     borsh::BorshSerialize,
@@ -77,6 +77,8 @@ pub enum ParseHandshakeError {
     PartialEdgeInfo(ParseRequiredError<ParsePartialEdgeInfoError>),
     #[error("owned_account {0}")]
     OwnedAccount(ParseSignedOwnedAccountError),
+    #[error("sender_advertised_addr {0}")]
+    SenderAdvertisedAddr(ParseSocketAddrError),
 }
 
 impl From<&Handshake> for proto::Handshake {
@@ -90,6 +92,9 @@ impl From<&Handshake> for proto::Handshake {
             sender_chain_info: MF::some((&x.sender_chain_info).into()),
             partial_edge_info: MF::some((&x.partial_edge_info).into()),
             owned_account: x.owned_account.as_ref().map(Into::into).into(),
+            supports_compression: x.supports_compression,
+            supports_block_announcements: x.supports_block_announcements,
+            sender_advertised_addr: x.sender_advertised_addr.as_ref().map(Into::into).into(),
             ..Self::default()
         }
     }
@@ -120,6 +125,10 @@ impl TryFrom<&proto::Handshake> for Handshake {
                 .map_err(Self::Error::PartialEdgeInfo)?,
             owned_account: try_from_optional(&p.owned_account)
                 .map_err(Self::Error::OwnedAccount)?,
+            supports_compression: p.supports_compression,
+            supports_block_announcements: p.supports_block_announcements,
+            sender_advertised_addr: try_from_optional(&p.sender_advertised_addr)
+                .map_err(Self::Error::SenderAdvertisedAddr)?,
         })
     }
 }
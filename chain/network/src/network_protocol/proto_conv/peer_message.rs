@@ -5,8 +5,8 @@ use crate::network_protocol::proto::peer_message::Message_type as ProtoMT;
 use crate::network_protocol::proto::{self};
 use crate::network_protocol::state_sync::{SnapshotHostInfo, SyncSnapshotHosts};
 use crate::network_protocol::{
-    AdvertisedPeerDistance, Disconnect, DistanceVector, PeerMessage, PeersRequest, PeersResponse,
-    RoutingTableUpdate, SyncAccountsData,
+    AdvertisedPeerDistance, BlockAnnouncement, Disconnect, DisconnectReason, DistanceVector,
+    PeerMessage, PeersRequest, PeersResponse, RoutingTableUpdate, SyncAccountsData,
 };
 use crate::network_protocol::{RoutedMessage, RoutedMessageV2};
 use crate::types::StateResponseInfo;
@@ -228,6 +228,32 @@ impl TryFrom<&proto::SyncSnapshotHosts> for SyncSnapshotHosts {
 
 //////////////////////////////////////////
 
+impl From<&DisconnectReason> for proto::disconnect::Reason {
+    fn from(x: &DisconnectReason) -> Self {
+        match x {
+            DisconnectReason::Unknown => Self::UNKNOWN,
+            DisconnectReason::TooManyPeers => Self::TooManyPeers,
+            DisconnectReason::Banned => Self::Banned,
+            DisconnectReason::IncompatibleVersion => Self::IncompatibleVersion,
+            DisconnectReason::ShuttingDown => Self::ShuttingDown,
+        }
+    }
+}
+
+impl From<proto::disconnect::Reason> for DisconnectReason {
+    fn from(x: proto::disconnect::Reason) -> Self {
+        match x {
+            proto::disconnect::Reason::UNKNOWN => Self::Unknown,
+            proto::disconnect::Reason::TooManyPeers => Self::TooManyPeers,
+            proto::disconnect::Reason::Banned => Self::Banned,
+            proto::disconnect::Reason::IncompatibleVersion => Self::IncompatibleVersion,
+            proto::disconnect::Reason::ShuttingDown => Self::ShuttingDown,
+        }
+    }
+}
+
+//////////////////////////////////////////
+
 impl From<&PeerMessage> for proto::PeerMessage {
     fn from(x: &PeerMessage) -> Self {
         Self {
@@ -292,6 +318,13 @@ impl From<&PeerMessage> for proto::PeerMessage {
                     block: MF::some(b.into()),
                     ..Default::default()
                 }),
+                PeerMessage::BlockAnnouncement(ba) => {
+                    ProtoMT::BlockAnnouncement(proto::BlockAnnouncement {
+                        block_hash: MF::some((&ba.hash).into()),
+                        height: ba.height,
+                        ..Default::default()
+                    })
+                }
                 PeerMessage::Transaction(t) => ProtoMT::Transaction(proto::SignedTransaction {
                     borsh: borsh::to_vec(&t).unwrap(),
                     ..Default::default()
@@ -304,6 +337,7 @@ impl From<&PeerMessage> for proto::PeerMessage {
                 }),
                 PeerMessage::Disconnect(r) => ProtoMT::Disconnect(proto::Disconnect {
                     remove_from_connection_store: r.remove_from_connection_store,
+                    reason: proto::disconnect::Reason::from(&r.reason).into(),
                     ..Default::default()
                 }),
                 PeerMessage::Challenge(r) => ProtoMT::Challenge(proto::Challenge {
@@ -373,6 +407,8 @@ pub enum ParsePeerMessageError {
     BlockRequest(ParseRequiredError<ParseCryptoHashError>),
     #[error("block_response: {0}")]
     BlockResponse(ParseRequiredError<ParseBlockError>),
+    #[error("block_announcement: {0}")]
+    BlockAnnouncement(ParseRequiredError<ParseCryptoHashError>),
     #[error("transaction: {0}")]
     Transaction(ParseTransactionError),
     #[error("routed: {0}")]
@@ -387,6 +423,8 @@ pub enum ParsePeerMessageError {
     StateResponse(ParseRequiredError<ParseStateInfoError>),
     #[error("sync_snapshot_hosts: {0}")]
     SyncSnapshotHosts(ParseSyncSnapshotHostsError),
+    #[error("compressed: nested compression is not allowed")]
+    NestedCompressed,
 }
 
 impl TryFrom<&proto::PeerMessage> for PeerMessage {
@@ -457,6 +495,10 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::BlockResponse(br) => PeerMessage::Block(
                 try_from_required(&br.block).map_err(Self::Error::BlockResponse)?,
             ),
+            ProtoMT::BlockAnnouncement(ba) => PeerMessage::BlockAnnouncement(BlockAnnouncement {
+                hash: try_from_required(&ba.block_hash).map_err(Self::Error::BlockAnnouncement)?,
+                height: ba.height,
+            }),
             ProtoMT::Transaction(t) => PeerMessage::Transaction(
                 SignedTransaction::try_from_slice(&t.borsh).map_err(Self::Error::Transaction)?,
             ),
@@ -472,6 +514,7 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             })),
             ProtoMT::Disconnect(d) => PeerMessage::Disconnect(Disconnect {
                 remove_from_connection_store: d.remove_from_connection_store,
+                reason: d.reason.enum_value_or_default().into(),
             }),
             ProtoMT::Challenge(c) => PeerMessage::Challenge(
                 Challenge::try_from_slice(&c.borsh).map_err(Self::Error::Challenge)?,
@@ -491,6 +534,8 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::SyncSnapshotHosts(srh) => PeerMessage::SyncSnapshotHosts(
                 srh.try_into().map_err(Self::Error::SyncSnapshotHosts)?,
             ),
+            // Compressed messages are unwrapped by `PeerMessage::deserialize`.
+            ProtoMT::Compressed(_) => return Err(Self::Error::NestedCompressed),
         })
     }
 }
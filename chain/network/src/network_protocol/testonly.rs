@@ -348,6 +348,9 @@ pub fn make_handshake<R: Rng>(rng: &mut R, chain: &Chain) -> Handshake {
         sender_chain_info: chain.get_peer_chain_info(),
         partial_edge_info: make_partial_edge(rng),
         owned_account: None,
+        supports_compression: false,
+        supports_block_announcements: false,
+        sender_advertised_addr: None,
     }
 }
 
@@ -61,8 +61,11 @@ fn serialize_deserialize_protobuf_only() {
     let mut rng = make_rng(39521947542);
     let mut clock = time::FakeClock::default();
     let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let mut handshake = data::make_handshake(&mut rng, &chain);
+    handshake.sender_advertised_addr = Some("8.8.8.8:24567".parse().unwrap());
     let msgs = [
         PeerMessage::Tier1Handshake(data::make_handshake(&mut rng, &chain)),
+        PeerMessage::Tier2Handshake(handshake),
         PeerMessage::SyncAccountsData(SyncAccountsData {
             accounts_data: (0..4)
                 .map(|_| Arc::new(data::make_signed_account_data(&mut rng, &clock.clock())))
@@ -70,6 +73,11 @@ fn serialize_deserialize_protobuf_only() {
             incremental: true,
             requesting_full_sync: true,
         }),
+        PeerMessage::BlockAnnouncement(BlockAnnouncement::new(&chain.blocks[5])),
+        PeerMessage::Disconnect(Disconnect {
+            remove_from_connection_store: true,
+            reason: DisconnectReason::TooManyPeers,
+        }),
     ];
     for m in msgs {
         let m2 = PeerMessage::deserialize(Encoding::Proto, &m.serialize(Encoding::Proto))
@@ -79,6 +87,46 @@ fn serialize_deserialize_protobuf_only() {
     }
 }
 
+#[test]
+fn serialize_deserialize_compressed() {
+    let mut rng = make_rng(3948572034);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+
+    // Headers are repeated so that the message is large and compresses well.
+    let large = PeerMessage::BlockHeaders(vec![chain.blocks[0].header().clone(); 100]);
+    let bytes = large.serialize_compressed();
+    assert!(bytes.len() < large.serialize(Encoding::Proto).len());
+    assert_eq!(large, PeerMessage::deserialize(Encoding::Proto, &bytes).unwrap());
+
+    // Small messages are sent as is.
+    let small = PeerMessage::BlockRequest(*chain.blocks[5].hash());
+    assert_eq!(small.serialize_compressed(), small.serialize(Encoding::Proto));
+
+    // Compressed message can't be compressed again.
+    let nested = proto::PeerMessage {
+        message_type: Some(proto::peer_message::Message_type::Compressed(
+            zstd::bulk::compress(&bytes, 1).unwrap(),
+        )),
+        ..Default::default()
+    };
+    let nested = protobuf::Message::write_to_bytes(&nested).unwrap();
+    assert!(PeerMessage::deserialize(Encoding::Proto, &nested).is_err());
+}
+
+#[test]
+fn sheddable_messages() {
+    let mut rng = make_rng(2093847523);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+
+    assert!(PeerMessage::Transaction(data::make_signed_transaction(&mut rng)).is_sheddable());
+    assert!(PeerMessage::PeersRequest(PeersRequest { max_peers: None, max_direct_peers: None })
+        .is_sheddable());
+    assert!(!PeerMessage::Block(chain.blocks[5].clone()).is_sheddable());
+    assert!(!PeerMessage::BlockRequest(*chain.blocks[5].hash()).is_sheddable());
+}
+
 #[test]
 fn serialize_deserialize() -> anyhow::Result<()> {
     let mut rng = make_rng(89028037453);
@@ -127,7 +175,10 @@ fn serialize_deserialize() -> anyhow::Result<()> {
         PeerMessage::Transaction(data::make_signed_transaction(&mut rng)),
         PeerMessage::Routed(routed_message1),
         PeerMessage::Routed(routed_message2),
-        PeerMessage::Disconnect(Disconnect { remove_from_connection_store: false }),
+        PeerMessage::Disconnect(Disconnect {
+            remove_from_connection_store: false,
+            reason: DisconnectReason::Unknown,
+        }),
         PeerMessage::Challenge(data::make_challenge(&mut rng)),
     ];
 
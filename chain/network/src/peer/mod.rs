@@ -1,7 +1,7 @@
 pub(crate) mod peer_actor;
 mod stream;
 mod tracker;
-mod transfer_stats;
+pub(crate) mod transfer_stats;
 
 #[cfg(test)]
 pub(crate) mod testonly;
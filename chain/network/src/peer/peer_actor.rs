@@ -1,7 +1,8 @@
 use crate::accounts_data::AccountDataError;
 use crate::client::{
     AnnounceAccountRequest, BlockHeadersRequest, BlockHeadersResponse, BlockRequest, BlockResponse,
-    ProcessTxRequest, RecvChallenge, StateRequestHeader, StateRequestPart, StateResponse,
+    ProcessTxRequest, ProcessTxResponse, RecvChallenge, StateRequestHeader, StateRequestPart,
+    StateResponse,
 };
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
@@ -27,7 +28,8 @@ use crate::snapshot_hosts::SnapshotHostInfoError;
 use crate::stats::metrics;
 use crate::tcp;
 use crate::types::{
-    BlockInfo, Disconnect, Handshake, HandshakeFailureReason, PeerMessage, PeerType, ReasonForBan,
+    BlockInfo, Disconnect, DisconnectReason, Handshake, HandshakeFailureReason, PeerMessage,
+    PeerOffense, PeerType, ReasonForBan,
 };
 use actix::fut::future::wrap_future;
 use actix::{Actor as _, ActorContext as _, ActorFutureExt as _, AsyncContext as _};
@@ -37,6 +39,7 @@ use near_async::time;
 use near_crypto::Signature;
 use near_o11y::{handler_debug_span, log_assert, WithSpanContext};
 use near_performance_metrics_macros::perf;
+use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::types::EpochId;
@@ -93,7 +96,7 @@ pub struct HandshakeCompletedEvent {
     pub(crate) tier: tcp::Tier,
 }
 
-#[derive(thiserror::Error, Clone, PartialEq, Eq, Debug)]
+#[derive(thiserror::Error, Clone, PartialEq, Eq, Debug, strum::IntoStaticStr)]
 pub(crate) enum ClosingReason {
     #[error("too many inbound connections in connecting state")]
     TooManyInbound,
@@ -144,6 +147,21 @@ impl ClosingReason {
             ClosingReason::Unknown => false,        // only happens in tests
         }
     }
+
+    /// Reason reported to the peer in the Disconnect message.
+    pub(crate) fn disconnect_reason(&self) -> DisconnectReason {
+        match self {
+            ClosingReason::TooManyInbound
+            | ClosingReason::RejectedByPeerManager(
+                RegisterPeerError::ConnectionLimitExceeded | RegisterPeerError::SubnetLimitExceeded,
+            ) => DisconnectReason::TooManyPeers,
+            ClosingReason::Ban(_)
+            | ClosingReason::RejectedByPeerManager(
+                RegisterPeerError::Banned | RegisterPeerError::Blacklisted,
+            ) => DisconnectReason::Banned,
+            _ => DisconnectReason::Unknown,
+        }
+    }
 }
 
 pub(crate) struct PeerActor {
@@ -172,6 +190,9 @@ pub(crate) struct PeerActor {
     routed_message_cache: LruCache<(PeerId, PeerIdOrHash, Signature), time::Instant>,
     /// Whether we detected support for protocol buffers during handshake.
     protocol_buffers_supported: bool,
+    /// Whether large messages sent to the peer are compressed. Set once both
+    /// sides declared support of compression in the handshake.
+    compression_enabled: bool,
     /// Whether the PeerActor should skip protobuf support detection and use
     /// a given encoding right away.
     force_encoding: Option<Encoding>,
@@ -187,6 +208,9 @@ pub(crate) struct PeerActor {
 
     /// Per-message rate limits for incoming messages.
     received_messages_rate_limits: messages_limits::RateLimits,
+    /// Semaphore limiting block, header and state requests from this peer
+    /// which are being served or wait to be served.
+    sync_requests_permits: Arc<tokio::sync::Semaphore>,
 }
 
 impl Debug for PeerActor {
@@ -317,7 +341,7 @@ impl PeerActor {
         };
         let my_node_info = PeerInfo {
             id: network_state.config.node_id(),
-            addr: network_state.config.node_addr.as_ref().map(|a| **a),
+            addr: network_state.config.public_addr(),
             // TODO(validator-key-hot-swap) Consider using mutable validator signer instead of PeerInfo.account_id ?
             // That likely requires bigger changes and account_id here is later used for debug / logging purposes only.
             account_id: network_state.config.validator.account_id(),
@@ -355,6 +379,7 @@ impl PeerActor {
                         NonZeroUsize::new(ROUTED_MESSAGE_CACHE_SIZE).unwrap(),
                     ),
                     protocol_buffers_supported: false,
+                    compression_enabled: false,
                     force_encoding,
                     peer_info: match &stream_type {
                         tcp::StreamType::Inbound => None,
@@ -365,6 +390,9 @@ impl PeerActor {
                         }),
                     }
                     .into(),
+                    sync_requests_permits: Arc::new(tokio::sync::Semaphore::new(
+                        network_state.config.max_concurrent_sync_requests_per_peer,
+                    )),
                     network_state,
                     received_messages_rate_limits,
                 }
@@ -424,6 +452,14 @@ impl PeerActor {
         fields(msg_type = msg.msg_variant())
     )]
     fn send_message_with_encoding(&self, msg: &PeerMessage, enc: Encoding) {
+        // Borsh doesn't support block announcements, such peers get the whole block.
+        if let (Encoding::Borsh, PeerMessage::BlockAnnouncement(announcement)) = (enc, msg) {
+            let Some(block) = self.network_state.recent_broadcast_block(&announcement.hash) else {
+                tracing::debug!(target: "network", peer = %self.peer_info, "Dropping announcement of a block which is no longer cached");
+                return;
+            };
+            return self.send_message_with_encoding(&PeerMessage::Block(block), enc);
+        }
         // Skip sending block and headers if we received it or header from this peer.
         // Record block requests in tracker.
         match msg {
@@ -431,7 +467,7 @@ impl PeerActor {
             // peers to update its height at the peer. In the future we will introduce a new
             // peer message type for that and then we can enable this check again.
             //PeerMessage::Block(b) if self.tracker.lock().has_received(b.hash()) => return,
-            PeerMessage::BlockRequest(h) => self.tracker.lock().push_request(*h),
+            PeerMessage::BlockRequest(h) => self.tracker.lock().push_request(&self.clock, *h),
             PeerMessage::SyncAccountsData(d) => metrics::SYNC_ACCOUNTS_DATA
                 .with_label_values(&[
                     "sent",
@@ -448,13 +484,30 @@ impl PeerActor {
             _ => (),
         };
 
-        let bytes = msg.serialize(enc);
-        self.tracker.lock().increment_sent(&self.clock, bytes.len() as u64);
+        if let Some(limit) = self.network_state.config.load_shedding_send_queue_bytes {
+            if msg.is_sheddable() && self.stats.bytes_to_send.load(Ordering::Relaxed) > limit {
+                tracing::debug!(target: "network", msg_type = msg.msg_variant(), peer = %self.peer_info, "Dropping low priority message, send queue is full");
+                metrics::MessageDropped::LoadShedding.inc_peer_msg(msg);
+                return;
+            }
+        }
+
+        let bytes = match enc {
+            Encoding::Proto if self.compression_enabled => msg.serialize_compressed(),
+            _ => msg.serialize(enc),
+        };
         let bytes_len = bytes.len();
+        let msg_type = msg.msg_variant();
+        let limit = self.network_state.config.send_bandwidth_limits.get(msg_type).copied();
+        if !self.stats.record_sent(&self.clock, msg_type, bytes_len as u64, limit) {
+            tracing::debug!(target: "network", msg_type, peer = %self.peer_info, "Dropping message over the bandwidth limit");
+            metrics::MessageDropped::BandwidthLimitExceeded.inc_peer_msg(msg);
+            return;
+        }
+        self.tracker.lock().increment_sent(&self.clock, bytes_len as u64);
         tracing::trace!(target: "network", msg_len = bytes_len);
         self.framed.send(stream::Frame(bytes));
         metrics::PEER_DATA_SENT_BYTES.inc_by(bytes_len as u64);
-        let msg_type = msg.msg_variant();
         metrics::PEER_MESSAGE_SENT_BY_TYPE_TOTAL.with_label_values(&[msg_type]).inc();
         metrics::PEER_MESSAGE_SENT_BY_TYPE_BYTES
             .with_label_values(&[msg_type])
@@ -473,7 +526,7 @@ impl PeerActor {
             oldest_supported_version: PEER_MIN_ALLOWED_PROTOCOL_VERSION,
             sender_peer_id: self.network_state.config.node_id(),
             target_peer_id: spec.peer_id,
-            sender_listen_port: self.network_state.config.node_addr.as_ref().map(|a| a.port()),
+            sender_listen_port: self.network_state.config.public_addr().map(|a| a.port()),
             sender_chain_info: PeerChainInfoV2 {
                 genesis_id: self.network_state.genesis_id.clone(),
                 // TODO: remove `height` from PeerChainInfo
@@ -490,6 +543,9 @@ impl PeerActor {
                 }
                 .sign(&signer)
             }),
+            supports_compression: self.network_state.config.message_compression,
+            supports_block_announcements: self.network_state.config.block_announcements,
+            sender_advertised_addr: self.network_state.config.advertised_addr,
         };
         let msg = match spec.tier {
             tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
@@ -630,6 +686,32 @@ impl PeerActor {
             }
         }
 
+        // The advertised address is chosen by the peer. It is stored and gossiped
+        // to other peers, so an address on another IP than the one the connection
+        // comes from is ignored, otherwise any peer could make the network dial an
+        // arbitrary host.
+        let advertised_addr =
+            handshake.sender_advertised_addr.filter(|addr| addr.ip() == self.peer_addr.ip());
+        if advertised_addr != handshake.sender_advertised_addr {
+            tracing::debug!(target: "network", peer_addr = ?self.peer_addr, advertised_addr = ?handshake.sender_advertised_addr, "Ignoring advertised address on a different IP");
+        }
+        // Both the observed and the advertised address have to pass the blacklist.
+        if let Some(advertised_addr) = &advertised_addr {
+            if self.network_state.peer_store.is_blacklisted(&self.peer_addr)
+                || self.network_state.peer_store.is_blacklisted(advertised_addr)
+            {
+                tracing::debug!(target: "network", peer_addr = ?self.peer_addr, ?advertised_addr, "Dropping connection from blacklisted address");
+                self.stop(
+                    ctx,
+                    ClosingReason::RejectedByPeerManager(RegisterPeerError::Blacklisted),
+                );
+                return;
+            }
+        }
+
+        self.compression_enabled =
+            self.network_state.config.message_compression && handshake.supports_compression;
+
         // Merge partial edges.
         let nonce = handshake.partial_edge_info.nonce;
         let partial_edge_info = match cs {
@@ -655,9 +737,9 @@ impl PeerActor {
         // preemptively.
         let peer_info = PeerInfo {
             id: handshake.sender_peer_id.clone(),
-            addr: handshake
+            addr: advertised_addr.or(handshake
                 .sender_listen_port
-                .map(|port| SocketAddr::new(self.peer_addr.ip(), port)),
+                .map(|port| SocketAddr::new(self.peer_addr.ip(), port))),
             account_id: None,
         };
 
@@ -666,10 +748,13 @@ impl PeerActor {
             tier,
             addr: ctx.address(),
             peer_info: peer_info.clone(),
+            peer_addr: self.peer_addr,
             owned_account: handshake.owned_account.clone(),
             genesis_id: handshake.sender_chain_info.genesis_id.clone(),
             tracked_shards: handshake.sender_chain_info.tracked_shards.clone(),
             archival: handshake.sender_chain_info.archival,
+            block_announcements: self.network_state.config.block_announcements
+                && handshake.supports_block_announcements,
             last_block: Default::default(),
             peer_type: self.peer_type,
             stats: self.stats.clone(),
@@ -695,9 +780,26 @@ impl PeerActor {
             time::Interval::new(clock.now(), self.network_state.config.peer_stats_period);
         ctx.spawn({
             let conn = conn.clone();
+            let network_state = self.network_state.clone();
             wrap_future(async move {
                 loop {
                     interval.tick(&clock).await;
+                    // A peer doesn't respond to requests for blocks it doesn't have,
+                    // e.g. garbage collected ones, so only the blocks it announced
+                    // count against it if it didn't respond in time. Other requests
+                    // are retried by the requesting subsystems on their own timers.
+                    let expired = {
+                        let mut tracker = tracker.lock();
+                        let expired = tracker.take_expired(&clock);
+                        expired.into_iter().filter(|hash| tracker.has_announced(hash)).count()
+                    };
+                    for _ in 0..expired {
+                        network_state.report_offense(
+                            &clock,
+                            &conn.peer_info.id,
+                            PeerOffense::Timeout,
+                        );
+                    }
                     let sent = tracker.lock().sent_bytes.minute_stats(&clock);
                     let received = tracker.lock().received_bytes.minute_stats(&clock);
                     conn.stats
@@ -892,6 +994,11 @@ impl PeerActor {
                             || common_version < PEER_MIN_ALLOWED_PROTOCOL_VERSION
                         {
                             tracing::warn!(target: "network", "Unable to connect to a node ({}) due to a network protocol version mismatch. Our version: {:?}, their: {:?}", peer_info, (PROTOCOL_VERSION, PEER_MIN_ALLOWED_PROTOCOL_VERSION), (version, oldest_supported_version));
+                            self.network_state.peer_store.peer_disconnect_reason(
+                                &self.clock,
+                                &handshake_spec.peer_id,
+                                DisconnectReason::IncompatibleVersion,
+                            );
                             self.stop(ctx, ClosingReason::HandshakeFailed);
                             return;
                         }
@@ -948,6 +1055,19 @@ impl PeerActor {
                     actix::fut::ready(())
                 }));
             }
+            // The peer rejected the connection after receiving our handshake.
+            (
+                PeerStatus::Connecting(_, ConnectingStatus::Outbound { handshake_spec, .. }),
+                PeerMessage::Disconnect(d),
+            ) => {
+                tracing::debug!(target: "network", reason = ?d.reason, peer_id = %handshake_spec.peer_id, "Connection rejected by peer");
+                self.network_state.peer_store.peer_disconnect_reason(
+                    &self.clock,
+                    &handshake_spec.peer_id,
+                    d.reason,
+                );
+                self.stop(ctx, ClosingReason::DisconnectMessage);
+            }
             (PeerStatus::Connecting { .. }, PeerMessage::Tier1Handshake(msg)) => {
                 self.process_handshake(ctx, tcp::Tier::T1, msg)
             }
@@ -1000,24 +1120,86 @@ impl PeerActor {
             PeerMessage::Block(block) => {
                 self.network_state.txns_since_last_block.store(0, Ordering::Release);
                 let hash = *block.hash();
-                let height = block.header().height();
-                conn.last_block.rcu(|last_block| {
-                    if last_block.is_none() || last_block.unwrap().height <= height {
-                        Arc::new(Some(BlockInfo { height, hash }))
-                    } else {
-                        last_block.clone()
-                    }
-                });
+                conn.update_last_block(BlockInfo { height: block.header().height(), hash });
+                self.network_state.mark_block_seen(hash);
                 let mut tracker = self.tracker.lock();
-                tracker.push_received(hash);
-                tracker.has_request(&hash)
+                if let Some(latency) = tracker.push_received(&self.clock, hash) {
+                    conn.stats.record_response_latency(latency);
+                }
+                // Blocks fetched after an announcement are new blocks being
+                // propagated, which the client is expected to rebroadcast.
+                tracker.has_request(&hash) && !tracker.has_announced(&hash)
+            }
+            PeerMessage::BlockAnnouncement(announcement) => {
+                let hash = announcement.hash;
+                conn.update_last_block(BlockInfo { height: announcement.height, hash });
+                self.tracker.lock().push_announced(hash);
+                if self.network_state.mark_block_announced(hash, &conn.peer_info.id) {
+                    self.network_state.schedule_announced_block_refetch(&self.clock, hash);
+                    self.send_message_or_log(&PeerMessage::BlockRequest(hash));
+                }
+                false
+            }
+            _ => false,
+        };
+        // The same block or transaction is usually broadcasted to us by many
+        // peers; pass only the first copy to the client. Requested blocks are
+        // always passed, since the client may need them again (e.g. during sync).
+        // Copies are compared by the hash of the whole message rather than the
+        // block or transaction hash, which doesn't cover the block body or the
+        // transaction signature, so an invalid copy can't suppress valid ones.
+        let is_duplicate = match &msg {
+            PeerMessage::Block(block) => {
+                !was_requested
+                    && !self.network_state.mark_message_received(CryptoHash::hash_borsh(block))
+            }
+            PeerMessage::Transaction(transaction) => {
+                !self.network_state.mark_message_received(CryptoHash::hash_borsh(transaction))
             }
             _ => false,
         };
+        if is_duplicate {
+            tracing::trace!(target: "network", msg_type = msg.msg_variant(), peer = %conn.peer_info, "Dropping duplicate message");
+            metrics::MessageDropped::Duplicate.inc_peer_msg(&msg);
+            #[cfg(test)]
+            message_processed_event();
+            return;
+        }
+        // Serving sync requests is expensive, so a peer can have only a few of
+        // them in flight, and only a few are served at once across all peers.
+        // Blocks broadcasted recently are served from memory, so fetches after
+        // our announcements aren't limited.
+        let is_sync_request = match &msg {
+            PeerMessage::BlockRequest(hash) => !self.network_state.has_recent_broadcast_block(hash),
+            PeerMessage::BlockHeadersRequest(_)
+            | PeerMessage::StateRequestHeader(..)
+            | PeerMessage::StateRequestPart(..) => true,
+            _ => false,
+        };
+        let sync_request_permit = match is_sync_request {
+            true => match self.sync_requests_permits.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    tracing::debug!(target: "network", msg_type = msg.msg_variant(), peer = %conn.peer_info, "Dropping sync request over the per-peer limit");
+                    metrics::MessageDropped::MaxCapacityExceeded.inc_peer_msg(&msg);
+                    #[cfg(test)]
+                    message_processed_event();
+                    return;
+                }
+            },
+            false => None,
+        };
         let clock = self.clock.clone();
         let network_state = self.network_state.clone();
         let peer_id = conn.peer_info.id.clone();
         let handling_future = async move {
+            let _permits = match sync_request_permit {
+                Some(permit) => Some((
+                    permit,
+                    network_state.sync_requests_permits.clone().acquire_owned().await.unwrap(),
+                )),
+                None => None,
+            };
             Ok(match msg {
                 PeerMessage::Routed(msg) => {
                     let msg_hash = msg.hash();
@@ -1036,13 +1218,18 @@ impl PeerActor {
                         ))
                     })
                 }
-                PeerMessage::BlockRequest(hash) => network_state
-                    .client
-                    .send_async(BlockRequest(hash))
-                    .await
-                    .ok()
-                    .flatten()
-                    .map(|block| PeerMessage::Block(*block)),
+                PeerMessage::BlockRequest(hash) => {
+                    match network_state.recent_broadcast_block(&hash) {
+                        Some(block) => Some(PeerMessage::Block(block)),
+                        None => network_state
+                            .client
+                            .send_async(BlockRequest(hash))
+                            .await
+                            .ok()
+                            .flatten()
+                            .map(|block| PeerMessage::Block(*block)),
+                    }
+                }
                 PeerMessage::BlockHeadersRequest(hashes) => network_state
                     .client
                     .send_async(BlockHeadersRequest(hashes))
@@ -1059,7 +1246,10 @@ impl PeerActor {
                     None
                 }
                 PeerMessage::Transaction(transaction) => {
-                    network_state
+                    // Honest peers relay transactions which became invalid in the
+                    // meantime, e.g. because of nonce races or being included in a
+                    // block already, so only malformed transactions are penalized.
+                    if let Ok(ProcessTxResponse::InvalidTx(err)) = network_state
                         .client
                         .send_async(ProcessTxRequest {
                             transaction,
@@ -1067,7 +1257,15 @@ impl PeerActor {
                             check_only: false,
                         })
                         .await
-                        .ok();
+                    {
+                        if is_malformed_transaction(&err) {
+                            network_state.report_offense(
+                                &clock,
+                                &peer_id,
+                                PeerOffense::InvalidTransaction,
+                            );
+                        }
+                    }
                     None
                 }
                 PeerMessage::BlockHeaders(headers) => {
@@ -1103,8 +1301,11 @@ impl PeerActor {
                     network_state.client.send_async(StateResponse(info.into())).await.ok();
                     None
                 }
+                // Handled above, before spawning the future.
+                PeerMessage::BlockAnnouncement(_) => None,
                 msg => {
                     tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
+                    network_state.report_offense(&clock, &peer_id, PeerOffense::ProtocolViolation);
                     None
                 }
             })
@@ -1113,7 +1314,12 @@ impl PeerActor {
             |res, act: &mut PeerActor, ctx| {
                 match res {
                     // TODO(gprusak): make sure that for routed messages we drop routeback info correctly.
-                    Ok(Some(resp)) => act.send_message_or_log(&resp),
+                    Ok(Some(resp)) => {
+                        if is_sync_request {
+                            act.stats.requests_served.fetch_add(1, Ordering::Relaxed);
+                        }
+                        act.send_message_or_log(&resp)
+                    }
                     Ok(None) => {}
                     Err(ban_reason) => act.stop(ctx, ClosingReason::Ban(ban_reason)),
                 }
@@ -1148,13 +1354,18 @@ impl PeerActor {
 
         match peer_msg {
             PeerMessage::Disconnect(d) => {
-                tracing::debug!(target: "network", "Disconnect signal. Me: {:?} Peer: {:?}", self.my_node_info.id, self.other_peer_id());
+                tracing::debug!(target: "network", reason = ?d.reason, "Disconnect signal. Me: {:?} Peer: {:?}", self.my_node_info.id, self.other_peer_id());
 
                 if d.remove_from_connection_store {
                     self.network_state
                         .connection_store
                         .remove_from_connection_store(self.other_peer_id().unwrap())
                 }
+                self.network_state.peer_store.peer_disconnect_reason(
+                    &self.clock,
+                    self.other_peer_id().unwrap(),
+                    d.reason,
+                );
 
                 self.stop(ctx, ClosingReason::DisconnectMessage);
             }
@@ -1439,6 +1650,7 @@ impl PeerActor {
                         metrics::ROUTED_MESSAGE_DROPPED
                             .with_label_values(&[msg.body_variant()])
                             .inc();
+                        metrics::MessageDropped::TtlExpired.inc(&msg.body);
                     }
                 }
             }
@@ -1487,7 +1699,7 @@ impl PeerActor {
             .collect();
         match network_state.client.send_async(AnnounceAccountRequest(accounts)).await {
             Ok(Err(ban_reason)) => conn.stop(Some(ban_reason)),
-            Ok(Ok(accounts)) => network_state.add_accounts(accounts).await,
+            Ok(Ok(accounts)) => network_state.add_accounts(clock, accounts).await,
             Err(_) => {}
         }
     }
@@ -1570,6 +1782,7 @@ impl actix::Actor for PeerActor {
 
                 self.send_message_or_log(&PeerMessage::Disconnect(Disconnect {
                     remove_from_connection_store,
+                    reason: reason.disconnect_reason(),
                 }));
             }
         }
@@ -1579,6 +1792,8 @@ impl actix::Actor for PeerActor {
             // it was not registered in the NetworkState,
             // so there is nothing to be done.
             PeerStatus::Connecting(..) => {
+                let reason = self.closing_reason.as_ref().map_or("Unknown", |reason| reason.into());
+                metrics::PEER_HANDSHAKE_FAILURES_TOTAL.with_label_values(&[reason]).inc();
                 // TODO(gprusak): reporting ConnectionClosed event is quite scattered right now and
                 // it is very ugly: it may happen here, in spawn_inner, or in NetworkState::unregister().
                 // Centralize it, once we get rid of actix.
@@ -1683,9 +1898,19 @@ impl actix::Handler<stream::Frame> for PeerActor {
             metrics::PEER_MESSAGE_RECEIVED_BY_TYPE_BYTES
                 .with_label_values(&labels)
                 .inc_by(msg.len() as u64);
+            self.stats.record_received(&self.clock, labels[0], msg.len() as u64);
             if !self.received_messages_rate_limits.is_allowed(&peer_msg, now) {
                 metrics::PEER_MESSAGE_RATE_LIMITED_BY_TYPE_TOTAL.with_label_values(&labels).inc();
                 tracing::debug!(target: "network", "Peer {} is being rate limited for message {}", self.peer_info, peer_msg.msg_variant());
+                if self.received_messages_rate_limits.should_report_exceeded(now) {
+                    if let Some(peer_id) = self.other_peer_id() {
+                        self.network_state.report_offense(
+                            &self.clock,
+                            peer_id,
+                            PeerOffense::RateLimitExceeded,
+                        );
+                    }
+                }
                 return;
             }
         }
@@ -1761,6 +1986,19 @@ impl actix::Handler<WithSpanContext<Stop>> for PeerActor {
     }
 }
 
+/// Whether the transaction is invalid regardless of the chain state.
+fn is_malformed_transaction(err: &InvalidTxError) -> bool {
+    matches!(
+        err,
+        InvalidTxError::InvalidSignerId { .. }
+            | InvalidTxError::InvalidReceiverId { .. }
+            | InvalidTxError::InvalidSignature
+            | InvalidTxError::ActionsValidation(_)
+            | InvalidTxError::TransactionSizeExceeded { .. }
+            | InvalidTxError::InvalidTransactionVersion
+    )
+}
+
 type InboundHandshakePermit = tokio::sync::OwnedSemaphorePermit;
 
 #[derive(Debug)]
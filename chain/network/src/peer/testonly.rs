@@ -1,5 +1,5 @@
 use crate::broadcast;
-use crate::client::{ClientSenderForNetworkInput, ClientSenderForNetworkMessage};
+use crate::client::{BlockRequest, ClientSenderForNetworkInput, ClientSenderForNetworkMessage};
 use crate::config::NetworkConfig;
 use crate::network_protocol::testonly as data;
 use crate::network_protocol::{
@@ -18,10 +18,12 @@ use crate::state_witness::{
 use crate::store;
 use crate::tcp;
 use crate::testonly::actix::ActixSystem;
-use near_async::messaging::{IntoMultiSender, Sender};
+use near_async::messaging::{IntoMultiSender, MessageWithCallback, Sender};
 use near_async::time;
 use near_o11y::WithSpanContextExt;
+use near_primitives::block::Block;
 use near_primitives::network::PeerId;
+use parking_lot::Mutex;
 use std::sync::Arc;
 
 pub struct PeerConfig {
@@ -54,6 +56,10 @@ pub(crate) struct PeerHandle {
     actix: ActixSystem<PeerActor>,
     pub events: broadcast::Receiver<Event>,
     pub edge: Option<Edge>,
+    /// Block requests passed to the client and not answered yet.
+    /// `None` if the client answers block requests right away.
+    held_block_requests:
+        Arc<Mutex<Option<Vec<MessageWithCallback<BlockRequest, Option<Box<Block>>>>>>>,
 }
 
 impl PeerHandle {
@@ -81,6 +87,21 @@ impl PeerHandle {
         );
     }
 
+    /// Makes the client keep block requests unanswered, so that they stay
+    /// in flight until `release_block_requests` is called.
+    pub fn hold_block_requests(&self) {
+        *self.held_block_requests.lock() = Some(vec![]);
+    }
+
+    /// Answers the held block requests (without a block) and makes the client
+    /// answer further block requests right away.
+    pub fn release_block_requests(&self) {
+        let held = self.held_block_requests.lock().take();
+        for req in held.unwrap_or_default() {
+            (req.callback)(Ok(None));
+        }
+    }
+
     pub fn routed_message(
         &self,
         body: RoutedMessageBody,
@@ -111,10 +132,21 @@ impl PeerHandle {
                 send.send(Event::Network(event));
             }
         });
+        let held_block_requests = Arc::new(Mutex::new(None));
         let client_sender = Sender::from_fn({
             let send = send.clone();
-            move |event: ClientSenderForNetworkMessage| {
-                send.send(Event::Client(event.into_input()));
+            let held_block_requests = held_block_requests.clone();
+            move |event: ClientSenderForNetworkMessage| match event {
+                ClientSenderForNetworkMessage::_block_request(msg) => {
+                    send.send(Event::Client(ClientSenderForNetworkInput::_block_request(
+                        msg.message.clone(),
+                    )));
+                    // Dropping the callback answers the request right away.
+                    if let Some(held) = held_block_requests.lock().as_mut() {
+                        held.push(msg);
+                    }
+                }
+                event => send.send(Event::Client(event.into_input())),
             }
         });
         let shards_manager_sender = Sender::from_fn({
@@ -132,7 +164,8 @@ impl PeerHandle {
         let network_state = Arc::new(NetworkState::new(
             &clock,
             store.clone(),
-            peer_store::PeerStore::new(&clock, network_cfg.peer_store.clone()).unwrap(),
+            peer_store::PeerStore::new(&clock, network_cfg.peer_store.clone(), store.clone())
+                .unwrap(),
             network_cfg.verify().unwrap(),
             cfg.chain.genesis_id.clone(),
             client_sender.break_apart().into_multi_sender(),
@@ -146,6 +179,6 @@ impl PeerHandle {
             move || PeerActor::spawn(clock, stream, cfg.force_encoding, network_state).unwrap().0
         })
         .await;
-        Self { actix, cfg, events: recv, edge: None }
+        Self { actix, cfg, events: recv, edge: None, held_block_requests }
     }
 }
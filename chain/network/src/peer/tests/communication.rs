@@ -211,6 +211,9 @@ async fn test_handshake(outbound_encoding: Option<Encoding>, inbound_encoding: O
         partial_edge_info: outbound_cfg
             .partial_edge_info(&inbound.cfg.id(), Edge::create_fresh_nonce(&clock.clock())),
         owned_account: None,
+        supports_compression: false,
+        supports_block_announcements: false,
+        sender_advertised_addr: None,
     };
     // We will also introduce chain_id mismatch, but ProtocolVersionMismatch is expected to take priority.
     handshake.sender_chain_info.genesis_id.chain_id = "unknown_chain".to_string();
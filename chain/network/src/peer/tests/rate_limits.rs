@@ -1,4 +1,5 @@
 use crate::broadcast::Receiver;
+use crate::client::ClientSenderForNetworkInput;
 use crate::config::NetworkConfig;
 use crate::network_protocol::{testonly as data, PartialEncodedChunkRequestMsg, RoutedMessageBody};
 use crate::network_protocol::{Encoding, PeerMessage};
@@ -86,6 +87,68 @@ async fn test_message_rate_limits_over_time() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+// Verifies that sync requests of a peer over its budget are dropped rather than queued.
+async fn test_sync_requests_per_peer_limit() -> anyhow::Result<()> {
+    init_test_logger();
+    tracing::info!("test_sync_requests_per_peer_limit");
+
+    let clock = FakeClock::default();
+    let mut rng = make_rng(89028037453);
+    let chain = Arc::new(data::Chain::make(&clock, &mut rng, 12));
+
+    const LIMIT: usize = 2;
+    const REQUESTS: usize = 5;
+    let mut inbound_network = chain.make_config(&mut rng);
+    inbound_network.max_concurrent_sync_requests_per_peer = LIMIT;
+    let inbound_cfg = PeerConfig {
+        chain: chain.clone(),
+        network: inbound_network,
+        force_encoding: Some(Encoding::Proto),
+    };
+    let outbound_cfg = PeerConfig {
+        chain: chain.clone(),
+        network: chain.make_config(&mut rng),
+        force_encoding: Some(Encoding::Proto),
+    };
+    let (outbound_stream, inbound_stream) =
+        tcp::Stream::loopback(inbound_cfg.id(), tcp::Tier::T2).await;
+    let mut inbound = PeerHandle::start_endpoint(clock.clock(), inbound_cfg, inbound_stream).await;
+    let mut outbound =
+        PeerHandle::start_endpoint(clock.clock(), outbound_cfg, outbound_stream).await;
+    outbound.complete_handshake().await;
+    inbound.complete_handshake().await;
+
+    tracing::info!(target:"test","flood the inbound peer with block requests");
+    inbound.hold_block_requests();
+    let mut events = inbound.events.from_now();
+    for i in 0..REQUESTS {
+        outbound.send(PeerMessage::BlockRequest(CryptoHash::hash_bytes(&i.to_le_bytes()))).await;
+    }
+    let (mut served, mut dropped) = (0, 0);
+    while served + dropped < REQUESTS {
+        match events.recv().await {
+            Event::Client(ClientSenderForNetworkInput::_block_request(_)) => served += 1,
+            Event::Network(PME::MessageProcessed(_, PeerMessage::BlockRequest(_))) => dropped += 1,
+            _ => {}
+        }
+    }
+    assert_eq!(served, LIMIT);
+    assert_eq!(dropped, REQUESTS - LIMIT);
+
+    tracing::info!(target:"test","requests are served again once the in-flight ones complete");
+    inbound.release_block_requests();
+    outbound.send(PeerMessage::BlockRequest(CryptoHash::hash_bytes(b"after release"))).await;
+    events
+        .recv_until(|ev| match ev {
+            Event::Client(ClientSenderForNetworkInput::_block_request(_)) => Some(()),
+            _ => None,
+        })
+        .await;
+
+    Ok(())
+}
+
 /// Waits up to `duration` and then checks how many events equal (in type only) to each one of `samples`
 /// have been received.
 ///
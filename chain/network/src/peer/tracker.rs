@@ -1,10 +1,14 @@
 use crate::peer::transfer_stats::TransferStats;
 use near_async::time;
 use near_primitives::hash::CryptoHash;
+use std::collections::HashMap;
 
 /// Maximum number of requests and responses to track.
 const MAX_TRACK_SIZE: usize = 30;
 
+/// Time within which a peer is expected to respond to our block request.
+const REQUEST_TIMEOUT: time::Duration = time::Duration::seconds(10);
+
 /// Internal structure to keep a circular queue within a tracker with unique hashes.
 struct CircularUniqueQueue {
     v: Vec<CryptoHash>,
@@ -49,6 +53,13 @@ pub(crate) struct Tracker {
     requested: CircularUniqueQueue,
     /// Received elements.
     received: CircularUniqueQueue,
+    /// Blocks requested because the peer announced them.
+    announced: CircularUniqueQueue,
+    /// Times at which the block requests which haven't been responded to yet
+    /// were sent, keyed by the requested hash. Only block requests are tracked:
+    /// chunk part and state part requests are routed messages, which their
+    /// subsystems retry on their own timers.
+    pending: HashMap<CryptoHash, time::Instant>,
 }
 
 impl Default for Tracker {
@@ -58,6 +69,8 @@ impl Default for Tracker {
             received_bytes: TransferStats::default(),
             requested: CircularUniqueQueue::new(MAX_TRACK_SIZE),
             received: CircularUniqueQueue::new(MAX_TRACK_SIZE),
+            announced: CircularUniqueQueue::new(MAX_TRACK_SIZE),
+            pending: HashMap::new(),
         }
     }
 }
@@ -78,17 +91,54 @@ impl Tracker {
     }
      */
 
-    pub(crate) fn push_received(&mut self, hash: CryptoHash) {
+    /// Records a received element. If it has been requested, returns how long
+    /// it took the peer to respond.
+    pub(crate) fn push_received(
+        &mut self,
+        clock: &time::Clock,
+        hash: CryptoHash,
+    ) -> Option<time::Duration> {
         self.received.push(hash);
+        self.pending.remove(&hash).map(|sent| clock.now() - sent)
     }
 
     pub(crate) fn has_request(&self, hash: &CryptoHash) -> bool {
         self.requested.contains(hash)
     }
 
-    pub(crate) fn push_request(&mut self, hash: CryptoHash) {
+    pub(crate) fn push_request(&mut self, clock: &time::Clock, hash: CryptoHash) {
+        // Repeated requests don't extend the deadline of the original one.
+        if self.pending.len() < MAX_TRACK_SIZE {
+            self.pending.entry(hash).or_insert(clock.now());
+        }
         self.requested.push(hash);
     }
+
+    /// Records that the block is about to be requested because the peer
+    /// announced it, rather than because we were missing it.
+    pub(crate) fn push_announced(&mut self, hash: CryptoHash) {
+        self.announced.push(hash);
+    }
+
+    pub(crate) fn has_announced(&self, hash: &CryptoHash) -> bool {
+        self.announced.contains(hash)
+    }
+
+    /// Stops tracking block requests which weren't responded to in time and
+    /// returns the requested hashes. The requesting subsystem isn't notified.
+    pub(crate) fn take_expired(&mut self, clock: &time::Clock) -> Vec<CryptoHash> {
+        let now = clock.now();
+        let expired: Vec<CryptoHash> = self
+            .pending
+            .iter()
+            .filter(|(_, sent)| **sent + REQUEST_TIMEOUT <= now)
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in &expired {
+            self.pending.remove(hash);
+        }
+        expired
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +147,26 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_expired_requests() {
+        let clock = time::FakeClock::default();
+        let mut tracker = Tracker::default();
+        tracker.push_request(&clock.clock(), hash(&[1]));
+        tracker.push_request(&clock.clock(), hash(&[2]));
+        clock.advance(time::Duration::milliseconds(300));
+        assert_eq!(
+            tracker.push_received(&clock.clock(), hash(&[1])),
+            Some(time::Duration::milliseconds(300))
+        );
+        assert_eq!(tracker.push_received(&clock.clock(), hash(&[3])), None);
+        assert!(tracker.take_expired(&clock.clock()).is_empty());
+
+        clock.advance(REQUEST_TIMEOUT);
+        assert_eq!(tracker.take_expired(&clock.clock()), vec![hash(&[2])]);
+        assert!(tracker.take_expired(&clock.clock()).is_empty());
+        assert!(tracker.has_request(&hash(&[2])));
+    }
+
     #[test]
     #[should_panic]
     fn test_circular_queue_zero_capacity() {
@@ -2,11 +2,12 @@ use crate::concurrency::arc_mutex::ArcMutex;
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
 use crate::network_protocol::{
-    PeerInfo, PeerMessage, RoutedMessageBody, SignedAccountData, SignedOwnedAccount,
-    SnapshotHostInfo, SyncAccountsData, SyncSnapshotHosts,
+    BlockAnnouncement, PeerInfo, PeerMessage, RoutedMessageBody, SignedAccountData,
+    SignedOwnedAccount, SnapshotHostInfo, SyncAccountsData, SyncSnapshotHosts,
 };
 use crate::peer::peer_actor;
 use crate::peer::peer_actor::PeerActor;
+use crate::peer::transfer_stats::TransferStats;
 use crate::private_actix::SendMessage;
 use crate::stats::metrics;
 use crate::tcp;
@@ -15,13 +16,15 @@ use arc_swap::ArcSwap;
 use near_async::time;
 use near_crypto::PublicKey;
 use near_o11y::WithSpanContextExt;
-use near_primitives::block::GenesisId;
+use near_primitives::block::{Block, GenesisId};
 use near_primitives::network::PeerId;
 use near_primitives::types::ShardId;
+use near_primitives::views::MessageTypeBandwidthView;
+use parking_lot::Mutex;
 use std::collections::{hash_map::Entry, HashMap};
 use std::fmt;
 use std::future::Future;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
 
 #[cfg(test)]
@@ -74,6 +77,91 @@ pub(crate) struct Stats {
     pub messages_to_send: AtomicU64,
     /// Number of bytes (sum of message sizes) in the buffer to send.
     pub bytes_to_send: AtomicU64,
+
+    /// Traffic by message type, see `PeerMessage::msg_variant`.
+    pub transfer_by_type: Mutex<HashMap<&'static str, MessageTypeTransfer>>,
+
+    /// Number of invalid messages (blocks, chunks, transactions failing validation
+    /// and messages violating the protocol) received from the peer.
+    pub invalid_messages: AtomicU64,
+    /// Number of block, header and state requests of the peer we've responded to.
+    pub requests_served: AtomicU64,
+    /// Number of our requests the peer has responded to.
+    pub responses_received: AtomicU64,
+    /// Sum of the times it took the peer to respond to our requests, in microseconds.
+    pub response_latency_total_us: AtomicU64,
+}
+
+/// Traffic of a single message type on a connection.
+#[derive(Default)]
+pub(crate) struct MessageTypeTransfer {
+    pub sent_bytes_total: u64,
+    pub received_bytes_total: u64,
+    pub sent: TransferStats,
+    pub received: TransferStats,
+}
+
+impl Stats {
+    /// Records a message sent to the peer, unless it would exceed `limit` of
+    /// bytes sent per minute for its type. Returns whether to send the message.
+    pub fn record_sent(
+        &self,
+        clock: &time::Clock,
+        msg_type: &'static str,
+        bytes: u64,
+        limit: Option<u64>,
+    ) -> bool {
+        let mut transfer_by_type = self.transfer_by_type.lock();
+        let transfer = transfer_by_type.entry(msg_type).or_default();
+        if let Some(limit) = limit {
+            if transfer.sent.minute_stats(clock).bytes_per_min + bytes > limit {
+                return false;
+            }
+        }
+        transfer.sent_bytes_total += bytes;
+        transfer.sent.record(clock, bytes);
+        true
+    }
+
+    pub fn record_received(&self, clock: &time::Clock, msg_type: &'static str, bytes: u64) {
+        let mut transfer_by_type = self.transfer_by_type.lock();
+        let transfer = transfer_by_type.entry(msg_type).or_default();
+        transfer.received_bytes_total += bytes;
+        transfer.received.record(clock, bytes);
+    }
+
+    pub fn record_response_latency(&self, latency: time::Duration) {
+        self.responses_received.fetch_add(1, Ordering::Relaxed);
+        self.response_latency_total_us
+            .fetch_add(latency.whole_microseconds().max(0) as u64, Ordering::Relaxed);
+    }
+
+    /// Average time it took the peer to respond to our requests.
+    pub fn avg_response_latency(&self) -> Option<time::Duration> {
+        let responses = self.responses_received.load(Ordering::Relaxed);
+        if responses == 0 {
+            return None;
+        }
+        let total = self.response_latency_total_us.load(Ordering::Relaxed);
+        Some(time::Duration::microseconds((total / responses) as i64))
+    }
+
+    /// Traffic by message type, sorted by message type.
+    pub fn bandwidth_view(&self, clock: &time::Clock) -> Vec<MessageTypeBandwidthView> {
+        let mut transfer_by_type = self.transfer_by_type.lock();
+        let mut view: Vec<_> = transfer_by_type
+            .iter_mut()
+            .map(|(msg_type, transfer)| MessageTypeBandwidthView {
+                message_type: msg_type.to_string(),
+                sent_bytes_total: transfer.sent_bytes_total,
+                received_bytes_total: transfer.received_bytes_total,
+                sent_bytes_per_min: transfer.sent.minute_stats(clock).bytes_per_min,
+                received_bytes_per_min: transfer.received.minute_stats(clock).bytes_per_min,
+            })
+            .collect();
+        view.sort_by(|a, b| a.message_type.cmp(&b.message_type));
+        view
+    }
 }
 
 /// Contains information relevant to a connected peer.
@@ -85,6 +173,9 @@ pub(crate) struct Connection {
     pub addr: actix::Addr<PeerActor>,
 
     pub peer_info: PeerInfo,
+    /// Address of the peer observed on the TCP connection. Unlike `peer_info.addr`,
+    /// it is not chosen by the peer.
+    pub peer_addr: std::net::SocketAddr,
     /// AccountKey ownership proof.
     pub owned_account: Option<SignedOwnedAccount>,
     /// Chain Id and hash of genesis block.
@@ -93,6 +184,8 @@ pub(crate) struct Connection {
     pub tracked_shards: Vec<ShardId>,
     /// Denote if a node is running in archival mode or not.
     pub archival: bool,
+    /// Whether new blocks are announced to the peer rather than sent in full.
+    pub block_announcements: bool,
     pub last_block: ArcSwap<Option<BlockInfo>>,
 
     /// Who started connection. Inbound (other) or Outbound (us).
@@ -126,6 +219,14 @@ impl fmt::Debug for Connection {
 }
 
 impl Connection {
+    /// Updates the latest block known to the peer, unless it knows a higher one already.
+    pub fn update_last_block(&self, info: BlockInfo) {
+        self.last_block.rcu(|last_block| match **last_block {
+            Some(last) if last.height > info.height => last_block.clone(),
+            _ => Arc::new(Some(info)),
+        });
+    }
+
     pub fn full_peer_info(&self) -> FullPeerInfo {
         let chain_info = PeerChainInfo {
             genesis_id: self.genesis_id.clone(),
@@ -502,4 +603,16 @@ impl Pool {
             peer.send_message(msg.clone());
         }
     }
+
+    /// Broadcasts a new block: peers which accept block announcements get
+    /// just the announcement and fetch the body if they need it.
+    pub fn broadcast_block(&self, block: Block) {
+        let announcement = Arc::new(PeerMessage::BlockAnnouncement(BlockAnnouncement::new(&block)));
+        let block = Arc::new(PeerMessage::Block(block));
+        metrics::BROADCAST_MESSAGES.with_label_values(&[block.msg_variant()]).inc();
+        for peer in self.load().ready.values() {
+            let msg = if peer.block_announcements { &announcement } else { &block };
+            peer.send_message(msg.clone());
+        }
+    }
 }
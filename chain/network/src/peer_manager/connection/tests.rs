@@ -120,3 +120,35 @@ async fn duplicate_connections() {
     );
     drop(conn1);
 }
+
+#[test]
+fn send_bandwidth_limit() {
+    let clock = time::FakeClock::default();
+    let stats = connection::Stats::default();
+    assert!(stats.record_sent(&clock.clock(), "Block", 600, Some(1000)));
+    // The message would exceed the per-minute limit.
+    assert!(!stats.record_sent(&clock.clock(), "Block", 600, Some(1000)));
+    // Other message types are accounted separately.
+    assert!(stats.record_sent(&clock.clock(), "Transaction", 600, Some(1000)));
+    // Unlimited types are always sent.
+    assert!(stats.record_sent(&clock.clock(), "Block", 600, None));
+    clock.advance(time::Duration::seconds(61));
+    assert!(stats.record_sent(&clock.clock(), "Block", 600, Some(1000)));
+
+    let view = stats.bandwidth_view(&clock.clock());
+    assert_eq!(view.len(), 2);
+    assert_eq!(view[0].message_type, "Block");
+    assert_eq!(view[0].sent_bytes_total, 1800);
+    assert_eq!(view[0].sent_bytes_per_min, 600);
+    assert_eq!(view[1].message_type, "Transaction");
+    assert_eq!(view[1].sent_bytes_per_min, 0);
+}
+
+#[test]
+fn response_latency() {
+    let stats = connection::Stats::default();
+    assert_eq!(stats.avg_response_latency(), None);
+    stats.record_response_latency(time::Duration::milliseconds(100));
+    stats.record_response_latency(time::Duration::milliseconds(300));
+    assert_eq!(stats.avg_response_latency(), Some(time::Duration::milliseconds(200)));
+}
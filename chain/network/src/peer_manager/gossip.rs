@@ -0,0 +1,111 @@
+use crate::stats::metrics;
+use parking_lot::Mutex;
+
+/// Gossip is sent to all peers if there are at most that many of them.
+/// It is large enough to make it very unlikely that gossip doesn't reach
+/// some node, given that each node forwards it to that many peers.
+const MIN_FANOUT: usize = 12;
+
+/// Lower bound on the fraction of peers the gossip is sent to.
+const MIN_FANOUT_RATIO: f64 = 0.25;
+
+/// Number of received gossip items after which the fanout is adjusted.
+const ADJUST_PERIOD: usize = 200;
+
+/// Expected number of copies of every new gossip item received by the node.
+/// If we receive more, the network is flooded with duplicates and the fanout
+/// is decreased; if less, some nodes may miss the gossip and it is increased.
+const MIN_COPIES: f64 = 4.;
+const MAX_COPIES: f64 = 8.;
+
+/// Factor by which the fanout ratio is changed in a single adjustment.
+const ADJUST_FACTOR: f64 = 0.9;
+
+/// How important it is for a broadcast message to reach every peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GossipPriority {
+    /// Sent to all peers, e.g. blocks, challenges and the data required
+    /// to establish TIER1 connections.
+    Critical,
+    /// Sent to a random subset of peers, e.g. snapshot hosts.
+    Normal,
+}
+
+struct Inner {
+    /// Fraction of peers the normal priority gossip is sent to.
+    ratio: f64,
+    /// Number of gossip items received since the last adjustment.
+    received: usize,
+    /// Number of new gossip items received since the last adjustment.
+    new: usize,
+}
+
+/// Number of peers the gossip is forwarded to, tuned by the number of
+/// duplicate gossip items received.
+pub(crate) struct GossipFanout(Mutex<Inner>);
+
+impl GossipFanout {
+    pub fn new() -> Self {
+        Self(Mutex::new(Inner { ratio: 1., received: 0, new: 0 }))
+    }
+
+    /// Number of peers out of `num_peers` to send gossip with the given priority to.
+    pub fn fanout(&self, num_peers: usize, priority: GossipPriority) -> usize {
+        let ratio = match priority {
+            GossipPriority::Critical => return num_peers,
+            GossipPriority::Normal => self.0.lock().ratio,
+        };
+        ((num_peers as f64 * ratio).ceil() as usize).max(MIN_FANOUT).min(num_peers)
+    }
+
+    /// Records that `received` gossip items have been received from a peer,
+    /// out of which `new` were not known before.
+    pub fn record(&self, received: usize, new: usize) {
+        let mut inner = self.0.lock();
+        inner.received += received;
+        inner.new += new;
+        if inner.received < ADJUST_PERIOD {
+            return;
+        }
+        let copies = inner.received as f64 / inner.new.max(1) as f64;
+        if copies > MAX_COPIES {
+            inner.ratio = (inner.ratio * ADJUST_FACTOR).max(MIN_FANOUT_RATIO);
+        } else if copies < MIN_COPIES {
+            inner.ratio = (inner.ratio / ADJUST_FACTOR).min(1.);
+        }
+        metrics::GOSSIP_FANOUT_RATIO.set(inner.ratio);
+        inner.received = 0;
+        inner.new = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fanout() {
+        let f = GossipFanout::new();
+        // Initially gossip is sent to all peers.
+        assert_eq!(f.fanout(40, GossipPriority::Normal), 40);
+
+        // Lots of duplicates decrease the fanout down to the lower bound.
+        for _ in 0..100 {
+            f.record(ADJUST_PERIOD, ADJUST_PERIOD / 20);
+        }
+        assert_eq!(f.fanout(100, GossipPriority::Normal), 25);
+        assert_eq!(f.fanout(100, GossipPriority::Critical), 100);
+        assert_eq!(f.fanout(40, GossipPriority::Normal), MIN_FANOUT);
+        assert_eq!(f.fanout(5, GossipPriority::Normal), 5);
+
+        // Number of copies within bounds doesn't change the fanout.
+        f.record(ADJUST_PERIOD, ADJUST_PERIOD / 6);
+        assert_eq!(f.fanout(100, GossipPriority::Normal), 25);
+
+        // Few duplicates increase the fanout back.
+        for _ in 0..100 {
+            f.record(ADJUST_PERIOD, ADJUST_PERIOD / 2);
+        }
+        assert_eq!(f.fanout(100, GossipPriority::Normal), 100);
+    }
+}
@@ -1,5 +1,6 @@
 pub(crate) mod connection;
 pub(crate) mod connection_store;
+pub(crate) mod gossip;
 pub(crate) mod network_state;
 pub(crate) mod peer_manager_actor;
 pub(crate) mod peer_store;
@@ -15,6 +15,7 @@ use crate::peer::peer_actor::ClosingReason;
 use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
 use crate::peer_manager::connection_store;
+use crate::peer_manager::gossip::{GossipFanout, GossipPriority};
 use crate::peer_manager::peer_store;
 use crate::private_actix::RegisterPeerError;
 use crate::routing::route_back_cache::RouteBackCache;
@@ -29,22 +30,24 @@ use crate::stats::metrics;
 use crate::store;
 use crate::tcp;
 use crate::types::{
-    ChainInfo, PeerType, ReasonForBan, StatePartRequestBody, Tier3Request, Tier3RequestBody,
+    ChainInfo, PeerOffense, PeerType, ReasonForBan, StatePartRequestBody, Tier3Request,
+    Tier3RequestBody,
 };
 use anyhow::Context;
 use arc_swap::ArcSwap;
 use near_async::messaging::{CanSend, SendAsync, Sender};
 use near_async::time;
-use near_primitives::block::GenesisId;
+use near_primitives::block::{Block, GenesisId};
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::PeerId;
 use near_primitives::stateless_validation::chunk_endorsement::ChunkEndorsement;
 use near_primitives::types::AccountId;
 use parking_lot::{Mutex, RwLock};
+use rand::seq::IteratorRandom as _;
 use std::collections::VecDeque;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tracing::Instrument as _;
 
@@ -59,6 +62,23 @@ pub(crate) const LIMIT_PENDING_PEERS: usize = 60;
 /// production of 1 block should fit).
 const RECENT_ROUTED_MESSAGES_CACHE_SIZE: usize = 10000;
 
+/// Number of blocks we broadcasted recently, kept to serve the peers
+/// fetching them after an announcement, before the chain has stored them.
+const RECENT_BROADCAST_BLOCKS_CACHE_SIZE: usize = 16;
+
+/// Number of hashes of recently received or fetched blocks, kept to request
+/// each announced block only once.
+const RECENT_BLOCKS_CACHE_SIZE: usize = 1000;
+
+/// Number of other peers which announced a block being fetched, kept to fetch
+/// the block from them if the peer it is being fetched from doesn't respond.
+const MAX_FALLBACK_BLOCK_ANNOUNCERS: usize = 4;
+
+/// Number of hashes of recently received blocks and transactions, kept to
+/// pass each of them to the client only once. It should be large enough to
+/// fit all the transactions received during production of 1 block.
+const RECENT_RECEIVED_MESSAGES_CACHE_SIZE: usize = 10000;
+
 /// How long a peer has to be unreachable, until we prune it from the in-memory graph.
 const PRUNE_UNREACHABLE_PEERS_AFTER: time::Duration = time::Duration::hours(1);
 
@@ -71,6 +91,12 @@ pub(crate) const RECONNECT_ATTEMPT_INTERVAL: time::Duration = time::Duration::se
 /// Limit number of pending tier3 requests to avoid OOM.
 pub(crate) const LIMIT_TIER3_REQUESTS: usize = 60;
 
+/// How often to re-resolve the address of a boot node given by a hostname.
+const BOOT_NODE_RESOLVE_INTERVAL: time::Duration = time::Duration::minutes(10);
+/// Bounds on the backoff between failed attempts to resolve a boot node hostname.
+const BOOT_NODE_RESOLVE_MIN_BACKOFF: time::Duration = time::Duration::seconds(1);
+const BOOT_NODE_RESOLVE_MAX_BACKOFF: time::Duration = time::Duration::minutes(5);
+
 impl WhitelistNode {
     pub fn from_peer_info(pi: &PeerInfo) -> anyhow::Result<Self> {
         Ok(Self {
@@ -124,6 +150,8 @@ pub(crate) struct NetworkState {
     pub tier3: connection::Pool,
     /// Semaphore limiting inflight inbound handshakes.
     pub inbound_handshake_permits: Arc<tokio::sync::Semaphore>,
+    /// Semaphore limiting block, header and state requests from peers served concurrently.
+    pub sync_requests_permits: Arc<tokio::sync::Semaphore>,
     /// The public IP of this node; available after connecting to any one peer.
     pub my_public_addr: Arc<RwLock<Option<std::net::SocketAddr>>>,
     /// Peer store that provides read/write access to peers.
@@ -143,6 +171,15 @@ pub(crate) struct NetworkState {
     /// Hashes of the body of recently received routed messages.
     /// It allows us to determine whether messages arrived faster over TIER1 or TIER2 network.
     pub recent_routed_messages: Mutex<lru::LruCache<CryptoHash, ()>>,
+    /// Blocks recently broadcasted by this node.
+    recent_broadcast_blocks: Mutex<lru::LruCache<CryptoHash, Block>>,
+    /// Hashes of blocks recently received or requested after an announcement,
+    /// with the other peers which announced a block being fetched.
+    recent_blocks: Mutex<lru::LruCache<CryptoHash, Vec<PeerId>>>,
+    /// Hashes of serialized blocks and transactions recently received from any peer.
+    recent_received_messages: Mutex<lru::LruCache<CryptoHash, ()>>,
+    /// Number of peers the gossip is forwarded to.
+    gossip_fanout: GossipFanout,
 
     /// Hash of messages that requires routing back to respective previous hop.
     pub tier2_route_back: Mutex<RouteBackCache>,
@@ -208,19 +245,35 @@ impl NetworkState {
             tier1: connection::Pool::new(config.node_id()),
             tier3: connection::Pool::new(config.node_id()),
             inbound_handshake_permits: Arc::new(tokio::sync::Semaphore::new(LIMIT_PENDING_PEERS)),
-            my_public_addr: Arc::new(RwLock::new(None)),
+            sync_requests_permits: Arc::new(tokio::sync::Semaphore::new(
+                config.max_concurrent_sync_requests,
+            )),
+            my_public_addr: Arc::new(RwLock::new(config.advertised_addr)),
             peer_store,
             snapshot_hosts: Arc::new(SnapshotHostsCache::new(config.snapshot_hosts.clone())),
             connection_store: connection_store::ConnectionStore::new(store.clone()).unwrap(),
             pending_reconnect: Mutex::new(Vec::<PeerInfo>::new()),
             accounts_data: Arc::new(AccountDataCache::new()),
-            account_announcements: Arc::new(AnnounceAccountCache::new(store)),
+            account_announcements: Arc::new(AnnounceAccountCache::new(
+                store,
+                config.ttl_account_id_router,
+            )),
             tier2_route_back: Mutex::new(RouteBackCache::default()),
             tier1_route_back: Mutex::new(RouteBackCache::default()),
             tier3_requests: Mutex::new(VecDeque::<Tier3Request>::new()),
             recent_routed_messages: Mutex::new(lru::LruCache::new(
                 NonZeroUsize::new(RECENT_ROUTED_MESSAGES_CACHE_SIZE).unwrap(),
             )),
+            recent_broadcast_blocks: Mutex::new(lru::LruCache::new(
+                NonZeroUsize::new(RECENT_BROADCAST_BLOCKS_CACHE_SIZE).unwrap(),
+            )),
+            recent_blocks: Mutex::new(lru::LruCache::new(
+                NonZeroUsize::new(RECENT_BLOCKS_CACHE_SIZE).unwrap(),
+            )),
+            recent_received_messages: Mutex::new(lru::LruCache::new(
+                NonZeroUsize::new(RECENT_RECEIVED_MESSAGES_CACHE_SIZE).unwrap(),
+            )),
+            gossip_fanout: GossipFanout::new(),
             txns_since_last_block: AtomicUsize::new(0),
             whitelist_nodes,
             add_edges_demux: demux::Demux::new(config.routing_table_update_rate_limit),
@@ -265,6 +318,122 @@ impl NetworkState {
         }
     }
 
+    /// Lowers reputation of the peer for the offense and, if it fell below
+    /// the threshold, disconnects and bans the peer.
+    pub fn report_offense(&self, clock: &time::Clock, peer_id: &PeerId, offense: PeerOffense) {
+        match offense {
+            PeerOffense::InvalidBlock
+            | PeerOffense::InvalidTransaction
+            | PeerOffense::ProtocolViolation => {
+                if let Some(conn) = self.tier2.load().ready.get(peer_id) {
+                    conn.stats.invalid_messages.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            PeerOffense::Timeout | PeerOffense::RateLimitExceeded => {}
+        }
+        if let Some(ban_reason) = self.peer_store.peer_offense(clock, peer_id, offense) {
+            self.disconnect_and_ban(clock, peer_id, ban_reason);
+        }
+    }
+
+    /// Broadcasts a new block to TIER2 peers. The block is kept around for a
+    /// while, so that the peers which got just an announcement can fetch it.
+    pub fn broadcast_block(&self, block: Block) {
+        let hash = *block.hash();
+        self.recent_blocks.lock().put(hash, vec![]);
+        self.recent_broadcast_blocks.lock().put(hash, block.clone());
+        self.tier2.broadcast_block(block);
+    }
+
+    /// Returns a block recently broadcasted by this node.
+    pub fn recent_broadcast_block(&self, hash: &CryptoHash) -> Option<Block> {
+        self.recent_broadcast_blocks.lock().get(hash).cloned()
+    }
+
+    /// Checks whether a block has been recently broadcasted by this node.
+    pub fn has_recent_broadcast_block(&self, hash: &CryptoHash) -> bool {
+        self.recent_broadcast_blocks.lock().contains(hash)
+    }
+
+    /// Records that a block has been received.
+    pub fn mark_block_seen(&self, hash: CryptoHash) {
+        self.recent_blocks.lock().put(hash, vec![]);
+    }
+
+    /// Records that a peer announced a block. Returns true if the block should
+    /// be fetched from the peer, i.e. it hasn't been received or fetched yet.
+    /// Otherwise the peer is kept as a fallback for the ongoing fetch.
+    pub fn mark_block_announced(&self, hash: CryptoHash, peer_id: &PeerId) -> bool {
+        let mut recent_blocks = self.recent_blocks.lock();
+        let Some(announcers) = recent_blocks.get_mut(&hash) else {
+            recent_blocks.put(hash, vec![]);
+            return true;
+        };
+        if announcers.len() < MAX_FALLBACK_BLOCK_ANNOUNCERS && !announcers.contains(peer_id) {
+            announcers.push(peer_id.clone());
+        }
+        false
+    }
+
+    /// Fetches an announced block from the other peers which announced it, one
+    /// after another, until it is received. Each peer gets `block_fetch_timeout`
+    /// to respond. Should be called before the block is requested from the first
+    /// peer which announced it.
+    pub fn schedule_announced_block_refetch(
+        self: &Arc<Self>,
+        clock: &time::Clock,
+        hash: CryptoHash,
+    ) {
+        let this = self.clone();
+        let clock = clock.clone();
+        let mut deadline = clock.now() + self.config.block_fetch_timeout;
+        self.spawn(async move {
+            loop {
+                clock.sleep_until(deadline).await;
+                if !this.refetch_announced_block(hash) {
+                    return;
+                }
+                deadline = clock.now() + this.config.block_fetch_timeout;
+            }
+        });
+    }
+
+    /// Requests an announced block from the next peer which announced it.
+    /// Returns false if the block has been received or there is no such peer.
+    fn refetch_announced_block(&self, hash: CryptoHash) -> bool {
+        loop {
+            let peer_id = match self.recent_blocks.lock().get_mut(&hash) {
+                Some(announcers) if !announcers.is_empty() => announcers.remove(0),
+                _ => return false,
+            };
+            if self.tier2.send_message(peer_id, Arc::new(PeerMessage::BlockRequest(hash))) {
+                return true;
+            }
+        }
+    }
+
+    /// Records that a block or transaction message with the given hash of its
+    /// serialized content has been received. Returns false if it has been
+    /// received already.
+    pub fn mark_message_received(&self, hash: CryptoHash) -> bool {
+        self.recent_received_messages.lock().put(hash, ()).is_none()
+    }
+
+    /// Selects a random subset of TIER2 peers to forward gossip with the given priority to.
+    fn gossip_targets(&self, priority: GossipPriority) -> Vec<Arc<connection::Connection>> {
+        let tier2 = self.tier2.load();
+        let fanout = self.gossip_fanout.fanout(tier2.ready.len(), priority);
+        tier2.ready.values().cloned().choose_multiple(&mut rand::thread_rng(), fanout)
+    }
+
+    /// Broadcasts a message to a subset of TIER2 peers depending on its priority.
+    pub fn broadcast_gossip(&self, msg: Arc<PeerMessage>, priority: GossipPriority) {
+        metrics::BROADCAST_MESSAGES.with_label_values(&[msg.msg_variant()]).inc();
+        for peer in self.gossip_targets(priority) {
+            peer.send_message(msg.clone());
+        }
+    }
+
     /// is_peer_whitelisted checks whether a peer is a whitelisted node.
     /// whitelisted nodes are allowed to connect, even if the inbound connections limit has
     /// been reached. This predicate should be evaluated AFTER the Handshake.
@@ -276,11 +445,35 @@ impl NetworkState {
             .any(|wn| wn.account_id.is_none() || wn.account_id == peer_info.account_id)
     }
 
+    /// Checks whether the inbound connection doesn't exceed the limit of connections
+    /// from a single IP subnet.
+    fn is_inbound_subnet_allowed(&self, conn: &connection::Connection) -> bool {
+        let Some(limit) = &self.config.inbound_subnet_limit else {
+            return true;
+        };
+        let Some(subnet) = limit.subnet(conn.peer_addr.ip()) else {
+            return true;
+        };
+        if self.is_peer_whitelisted(&conn.peer_info) {
+            return true;
+        }
+        let tier2 = self.tier2.load();
+        let same_subnet = tier2
+            .ready
+            .values()
+            .filter(|p| p.peer_type == PeerType::Inbound)
+            .filter(|p| limit.subnet(p.peer_addr.ip()) == Some(subnet))
+            .count();
+        same_subnet < limit.max_peers as usize
+    }
+
     /// predicate checking whether we should allow an inbound connection from peer_info.
     fn is_inbound_allowed(&self, peer_info: &PeerInfo) -> bool {
         // Check if we have spare inbound connections capacity.
         let tier2 = self.tier2.load();
+        let inbound = tier2.ready.values().filter(|p| p.peer_type == PeerType::Inbound).count();
         if tier2.ready.len() + tier2.outbound_handshakes.len() < self.config.max_num_peers as usize
+            && inbound < self.config.max_num_inbound_peers as usize
             && !self.config.inbound_disabled
         {
             return true;
@@ -293,6 +486,14 @@ impl NetworkState {
         false
     }
 
+    /// predicate checking whether we should allow an outbound connection to peer_info.
+    fn is_outbound_allowed(&self, peer_info: &PeerInfo) -> bool {
+        let tier2 = self.tier2.load();
+        let outbound = tier2.ready.values().filter(|p| p.peer_type == PeerType::Outbound).count();
+        outbound < self.config.max_num_outbound_peers as usize
+            || self.is_peer_whitelisted(peer_info)
+    }
+
     /// Register a direct connection to a new peer. This will be called after successfully
     /// establishing a connection with another peer. It becomes part of the connected peers.
     ///
@@ -310,11 +511,18 @@ impl NetworkState {
         self.spawn(async move {
             let peer_info = &conn.peer_info;
             // Check if this is a blacklisted peer.
-            if peer_info.addr.as_ref().map_or(true, |addr| this.peer_store.is_blacklisted(addr)) {
+            if peer_info.addr.as_ref().map_or(true, |addr| this.peer_store.is_blacklisted(addr))
+                || this.peer_store.is_peer_blacklisted(&peer_info.id)
+            {
                 tracing::debug!(target: "network", peer_info = ?peer_info, "Dropping connection from blacklisted peer or unknown address");
                 return Err(RegisterPeerError::Blacklisted);
             }
 
+            if this.config.whitelist_only && !this.is_peer_whitelisted(peer_info) {
+                tracing::debug!(target: "network", peer_info = ?peer_info, "Dropping connection from peer which is not whitelisted");
+                return Err(RegisterPeerError::NotWhitelisted);
+            }
+
             if this.peer_store.is_banned(&peer_info.id) {
                 tracing::debug!(target: "network", id = ?peer_info.id, "Dropping connection from banned peer");
                 return Err(RegisterPeerError::Banned);
@@ -349,6 +557,20 @@ impl NetworkState {
                             );
                             return Err(RegisterPeerError::ConnectionLimitExceeded);
                         }
+                        if !this.is_inbound_subnet_allowed(&conn) {
+                            tracing::debug!(target: "network",
+                                peer_addr = %conn.peer_addr,
+                                "Dropping handshake (too many inbound connections from the subnet)."
+                            );
+                            return Err(RegisterPeerError::SubnetLimitExceeded);
+                        }
+                    }
+                    if conn.peer_type == PeerType::Outbound && !this.is_outbound_allowed(&peer_info) {
+                        tracing::debug!(target: "network",
+                            max_num_outbound_peers = this.config.max_num_outbound_peers,
+                            "Dropping handshake (too many outbound connections)."
+                        );
+                        return Err(RegisterPeerError::ConnectionLimitExceeded);
                     }
                     // First verify and broadcast the edge of the connection, so that in case
                     // it is invalid, the connection is not added to the pool.
@@ -493,6 +715,38 @@ impl NetworkState {
         }
     }
 
+    /// Resolves the hostname of a boot node and adds it to the peer store,
+    /// retrying with exponential backoff on failure.
+    /// Once resolved, the address is re-resolved periodically, in case it changes.
+    pub async fn resolve_boot_node(&self, clock: time::Clock, boot_node: peer_store::BootNodeHost) {
+        let mut backoff = BOOT_NODE_RESOLVE_MIN_BACKOFF;
+        loop {
+            let addr = match tokio::net::lookup_host(&boot_node.host).await {
+                Ok(mut addrs) => addrs.next(),
+                Err(err) => {
+                    tracing::warn!(target: "network", host = %boot_node.host, ?err, "Failed to resolve boot node");
+                    None
+                }
+            };
+            let Some(addr) = addr else {
+                clock.sleep(backoff).await;
+                backoff = std::cmp::min(2 * backoff, BOOT_NODE_RESOLVE_MAX_BACKOFF);
+                continue;
+            };
+            tracing::debug!(target: "network", host = %boot_node.host, ?addr, "Resolved boot node");
+            self.peer_store.add_boot_node(
+                &clock,
+                PeerInfo {
+                    id: boot_node.id.clone(),
+                    addr: Some(addr),
+                    account_id: boot_node.account_id.clone(),
+                },
+            );
+            backoff = BOOT_NODE_RESOLVE_MIN_BACKOFF;
+            clock.sleep(BOOT_NODE_RESOLVE_INTERVAL).await;
+        }
+    }
+
     /// Determine if the given target is referring to us.
     pub fn message_for_me(&self, target: &PeerIdOrHash) -> bool {
         let my_peer_id = self.config.node_id();
@@ -670,7 +924,9 @@ impl NetworkState {
         let target = if let Some(peer_id) = peer_id_from_account_data {
             metrics::ACCOUNT_TO_PEER_LOOKUPS.with_label_values(&["AccountData"]).inc();
             peer_id
-        } else if let Some(peer_id) = self.account_announcements.get_account_owner(account_id) {
+        } else if let Some(peer_id) =
+            self.account_announcements.get_account_owner(clock, account_id)
+        {
             metrics::ACCOUNT_TO_PEER_LOOKUPS.with_label_values(&["AnnounceAccount"]).inc();
             peer_id
         } else {
@@ -825,10 +1081,9 @@ impl NetworkState {
             // This will prevent a malicious peer from forcing us to re-verify valid
             // datasets. See accounts_data::Cache documentation for details.
             if !new_data.is_empty() {
-                let tier2 = this.tier2.load();
-                let tasks: Vec<_> = tier2
-                    .ready
-                    .values()
+                let tasks: Vec<_> = this
+                    .gossip_targets(GossipPriority::Critical)
+                    .into_iter()
                     .map(|p| this.spawn(p.send_accounts_data(new_data.clone())))
                     .collect();
                 for t in tasks {
@@ -847,15 +1102,18 @@ impl NetworkState {
     ) -> Option<SnapshotHostInfoError> {
         let this = self.clone();
         self.spawn(async move {
+            let received = hosts.len();
             // Verify and add the new data to the internal state.
             let (new_data, err) = this.snapshot_hosts.clone().insert(hosts).await;
+            // Snapshot hosts are the only gossip sent to a subset of peers,
+            // so the duplicates received among them are used to tune the fanout.
+            this.gossip_fanout.record(received, new_data.len());
             // Broadcast any valid new data, even if an err was returned.
             // The presence of one invalid entry doesn't invalidate the remaining ones.
             if !new_data.is_empty() {
-                let tier2 = this.tier2.load();
-                let tasks: Vec<_> = tier2
-                    .ready
-                    .values()
+                let tasks: Vec<_> = this
+                    .gossip_targets(GossipPriority::Normal)
+                    .into_iter()
                     .map(|p| this.spawn(p.send_snapshot_hosts(new_data.clone())))
                     .collect();
                 for t in tasks {
@@ -975,8 +1233,17 @@ impl NetworkState {
     }
 
     /// Collects and returns PeerInfos for all directly connected TIER2 peers.
+    /// Returns peers we are directly connected to, except for the private ones.
     pub fn get_direct_peers(self: &Arc<Self>) -> Vec<PeerInfo> {
-        return self.tier2.load().ready.values().map(|c| c.peer_info.clone()).collect();
+        let private_peers = &self.config.peer_store.private_peers;
+        return self
+            .tier2
+            .load()
+            .ready
+            .values()
+            .filter(|c| !private_peers.contains(&c.peer_info.id))
+            .map(|c| c.peer_info.clone())
+            .collect();
     }
 
     /// Sets the chain info, and updates the set of TIER1 keys.
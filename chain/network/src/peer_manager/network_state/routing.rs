@@ -41,10 +41,15 @@ impl NetworkState {
 
     /// Adds AnnounceAccounts (without validating them) to the routing table.
     /// Then it broadcasts all the AnnounceAccounts that haven't been seen before.
-    pub async fn add_accounts(self: &Arc<NetworkState>, accounts: Vec<AnnounceAccount>) {
+    pub async fn add_accounts(
+        self: &Arc<NetworkState>,
+        clock: &time::Clock,
+        accounts: Vec<AnnounceAccount>,
+    ) {
         let this = self.clone();
+        let clock = clock.clone();
         self.spawn(async move {
-            let new_accounts = this.account_announcements.add_accounts(accounts);
+            let new_accounts = this.account_announcements.add_accounts(&clock, accounts);
             tracing::debug!(target: "network", account_id = ?this.config.validator.account_id(), ?new_accounts, "Received new accounts");
             #[cfg(test)]
             this.config.event_sink.send(crate::peer_manager::peer_manager_actor::Event::AccountsAdded(new_accounts.clone()));
@@ -101,15 +101,20 @@ impl super::NetworkState {
 
         let vc = self.tier1_validator_config(&accounts_data)?;
         let signer = vc.signer?;
-        let proxies = match (&self.config.node_addr, &vc.proxies) {
-            (None, _) => vec![],
-            (_, config::ValidatorProxies::Static(peer_addrs)) => peer_addrs.clone(),
+        let proxies = match (&self.config.node_addr, self.config.advertised_addr, &vc.proxies) {
+            (None, _, _) => vec![],
+            (_, _, config::ValidatorProxies::Static(peer_addrs)) => peer_addrs.clone(),
+            // If the node has an advertised address, it is its own proxy
+            // and there is no need to discover its public IP.
+            (_, Some(addr), config::ValidatorProxies::Dynamic(_)) => {
+                vec![PeerAddr { peer_id: self.config.node_id(), addr }]
+            }
             // If Dynamic are specified,
             // it means that this node is its own proxy.
             // Discover the public IP of this node using those STUN servers.
             // We do not require all stun servers to be available, but
             // we require the received responses to be consistent.
-            (Some(node_addr), config::ValidatorProxies::Dynamic(stun_servers)) => {
+            (Some(node_addr), None, config::ValidatorProxies::Dynamic(stun_servers)) => {
                 // Query all the STUN servers in parallel.
                 let queries = stun_servers.iter().map(|addr| {
                     let clock = clock.clone();
@@ -4,11 +4,12 @@ use crate::debug::{DebugStatus, GetDebugStatus};
 use crate::network_protocol;
 use crate::network_protocol::SyncSnapshotHosts;
 use crate::network_protocol::{
-    Disconnect, Edge, PeerIdOrHash, PeerMessage, Ping, Pong, RawRoutedMessage, RoutedMessageBody,
-    StatePartRequest,
+    Disconnect, DisconnectReason, Edge, PeerIdOrHash, PeerMessage, Ping, Pong, RawRoutedMessage,
+    RoutedMessageBody, StatePartRequest,
 };
 use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
+use crate::peer_manager::gossip::GossipPriority;
 use crate::peer_manager::network_state::{NetworkState, WhitelistNode};
 use crate::peer_manager::peer_store;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
@@ -33,8 +34,9 @@ use near_primitives::block::GenesisId;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::stateless_validation::chunk_endorsement::ChunkEndorsement;
 use near_primitives::views::{
-    ConnectionInfoView, EdgeView, KnownPeerStateView, NetworkGraphView, PeerStoreView,
-    RecentOutboundConnectionsView, SnapshotHostInfoView, SnapshotHostsView,
+    ConnectionInfoView, EdgeView, KnownPeerStateView, NetworkBandwidthView, NetworkGraphView,
+    PeerBandwidthView, PeerStoreView, RecentOutboundConnectionsView, SnapshotHostInfoView,
+    SnapshotHostsView,
 };
 use network_protocol::MAX_SHARDS_PER_SNAPSHOT_HOST_INFO;
 use rand::seq::{IteratorRandom, SliceRandom};
@@ -200,7 +202,9 @@ impl actix::Actor for PeerManagerActor {
         tracing::warn!("PeerManager: stopping");
         self.state.tier2.broadcast_message(Arc::new(PeerMessage::Disconnect(Disconnect {
             remove_from_connection_store: false,
+            reason: DisconnectReason::ShuttingDown,
         })));
+        self.state.peer_store.flush();
         actix::Running::Stop
     }
 
@@ -221,11 +225,13 @@ impl PeerManagerActor {
     ) -> anyhow::Result<actix::Addr<Self>> {
         let config = config.verify().context("config")?;
         let store = store::Store::from(store);
-        let peer_store = peer_store::PeerStore::new(&clock, config.peer_store.clone())
-            .context("PeerStore::new")?;
+        let peer_store =
+            peer_store::PeerStore::new(&clock, config.peer_store.clone(), store.clone())
+                .context("PeerStore::new")?;
         tracing::debug!(target: "network",
                len = peer_store.len(),
                boot_nodes = config.peer_store.boot_nodes.len(),
+               boot_node_hosts = config.peer_store.boot_node_hosts.len(),
                banned = peer_store.count_banned(),
                "Found known peers");
         tracing::debug!(target: "network", blacklist = ?config.peer_store.blacklist, "Blacklist");
@@ -344,6 +350,16 @@ impl PeerManagerActor {
                         }
                     }
                 });
+                // Resolve boot nodes given by hostnames.
+                for boot_node in state.config.peer_store.boot_node_hosts.clone() {
+                    arbiter.spawn({
+                        let clock = clock.clone();
+                        let state = state.clone();
+                        async move {
+                            state.resolve_boot_node(clock, boot_node).await;
+                        }
+                    });
+                }
                 // Periodically process pending Tier3 requests.
                 arbiter.spawn({
                     let clock = clock.clone();
@@ -454,7 +470,8 @@ impl PeerManagerActor {
     }
 
     /// Check if it is needed to create a new outbound connection.
-    /// If the number of active connections is less than `ideal_connections_lo` or
+    /// If the number of outgoing connections is less than `max_num_outbound_peers` and
+    /// either the number of active connections is less than `ideal_connections_lo` or
     /// (the number of outgoing connections is less than `minimum_outbound_peers`
     ///     and the total connections is less than `max_num_peers`)
     fn is_outbound_bootstrap_needed(&self) -> bool {
@@ -468,6 +485,7 @@ impl PeerManagerActor {
             || (total_connections < self.state.config.max_num_peers as usize
                 && potential_outbound_connections
                     < self.state.config.minimum_outbound_peers as usize))
+            && potential_outbound_connections < self.state.config.max_num_outbound_peers as usize
             && !self.state.config.outbound_disabled
     }
 
@@ -529,16 +547,21 @@ impl PeerManagerActor {
     }
 
     /// Check if the number of connections (excluding whitelisted ones) exceeds ideal_connections_hi.
-    /// If so, constructs a safe set of peers and selects one random peer outside of that set
+    /// If so, constructs a safe set of peers, selects one peer outside of that set
     /// and sends signal to stop connection to it gracefully.
     ///
     /// Safe set construction process:
     /// 1. Add all whitelisted peers to the safe set.
     /// 2. If the number of outbound connections is less or equal than minimum_outbound_connections,
     ///    add all outbound connections to the safe set.
-    /// 3. Find all peers who sent us a message within the last peer_recent_time_window,
+    /// 3. Add all peers owning a validator account to the safe set.
+    /// 4. Find all peers who sent us a message within the last peer_recent_time_window,
     ///    and add them one by one to the safe_set (starting from earliest connection time)
     ///    until safe set has safe_set_size elements.
+    ///
+    /// Inbound peers outside of the safe set are evicted before outbound ones. Among those
+    /// the peer with the lowest reputation is chosen, and the one which stayed idle for the
+    /// longest time if reputations are equal.
     fn maybe_stop_active_connection(&self) {
         let tier2 = self.state.tier2.load();
         let filter_peers = |predicate: &dyn Fn(&connection::Connection) -> bool| -> Vec<_> {
@@ -580,6 +603,9 @@ impl PeerManagerActor {
             }
         }
 
+        // Validators are needed for block and chunk distribution, keep them.
+        safe_set.extend(filter_peers(&|p| p.owned_account.is_some()));
+
         // Find all recently active peers.
         let now = self.clock.now();
         let mut active_peers: Vec<Arc<connection::Connection>> = tier2
@@ -605,8 +631,26 @@ impl PeerManagerActor {
 
         // Build valid candidate list to choose the peer to be removed. All peers outside the safe set.
         let candidates = tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id));
-        if let Some(p) = candidates.choose(&mut rand::thread_rng()) {
+        let victim = candidates
+            .map(|p| {
+                let reputation =
+                    self.state.peer_store.peer_reputation(&self.clock, &p.peer_info.id);
+                (p, reputation)
+            })
+            .min_by(|(a, a_reputation), (b, b_reputation)| {
+                (a.peer_type == PeerType::Outbound)
+                    .cmp(&(b.peer_type == PeerType::Outbound))
+                    .then(a_reputation.total_cmp(b_reputation))
+                    .then(
+                        a.last_time_received_message
+                            .load()
+                            .cmp(&b.last_time_received_message.load()),
+                    )
+            });
+        if let Some((p, reputation)) = victim {
             tracing::debug!(target: "network", id = ?p.peer_info.id,
+                peer_type = ?p.peer_type,
+                reputation,
                 tier2_len = tier2.ready.len(),
                 ideal_connections_hi = self.state.config.ideal_connections_hi,
                 "Stop active connection"
@@ -671,9 +715,14 @@ impl PeerManagerActor {
                 |peer_state| {
                     // Ignore connecting to ourself
                     self.my_peer_id == peer_state.peer_info.id
-                    || self.state.config.node_addr.as_ref().map(|a|**a) == peer_state.peer_info.addr
+                    || self.state.config.public_addr() == peer_state.peer_info.addr
+                    // Or to peers which asked us to hold off reconnecting
+                    || peer_state.reconnect_delayed(self.clock.now_utc())
                     // Or to peers we are currently trying to connect to
                     || tier2.outbound_handshakes.contains(&peer_state.peer_info.id)
+                    // Or to peers which would be rejected anyway
+                    || (self.state.config.whitelist_only
+                        && !self.state.is_peer_whitelisted(&peer_state.peer_info))
                 },
                 prefer_previously_connected_peer,
             ) {
@@ -750,6 +799,7 @@ impl PeerManagerActor {
         let tier2 = self.state.tier2.load();
         let now = self.clock.now();
         let graph = self.state.graph.load();
+        let accounts_data = self.state.accounts_data.load();
         let connected_peer = |cp: &Arc<connection::Connection>| ConnectedPeerInfo {
             full_peer_info: cp.full_peer_info(),
             received_bytes_per_sec: cp.stats.received_bytes_per_sec.load(Ordering::Relaxed),
@@ -762,6 +812,13 @@ impl PeerManagerActor {
                 Some(e) => e.nonce(),
                 None => 0,
             },
+            is_validator: cp
+                .owned_account
+                .as_ref()
+                .is_some_and(|a| accounts_data.keys.contains(&a.account_key)),
+            invalid_messages: cp.stats.invalid_messages.load(Ordering::Relaxed),
+            requests_served: cp.stats.requests_served.load(Ordering::Relaxed),
+            avg_response_latency: cp.stats.avg_response_latency(),
         };
         NetworkInfo {
             connected_peers: tier2.ready.values().map(connected_peer).collect(),
@@ -836,7 +893,7 @@ impl PeerManagerActor {
         metrics::REQUEST_COUNT_BY_TYPE_TOTAL.with_label_values(&[msg.as_ref()]).inc();
         match msg {
             NetworkRequests::Block { block } => {
-                self.state.tier2.broadcast_message(Arc::new(PeerMessage::Block(block)));
+                self.state.broadcast_block(block);
                 NetworkResponses::NoResponse
             }
             NetworkRequests::Approval { approval_message } => {
@@ -970,10 +1027,15 @@ impl PeerManagerActor {
                 self.state.disconnect_and_ban(&self.clock, &peer_id, ban_reason);
                 NetworkResponses::NoResponse
             }
+            NetworkRequests::ReportPeer { peer_id, offense } => {
+                self.state.report_offense(&self.clock, &peer_id, offense);
+                NetworkResponses::NoResponse
+            }
             NetworkRequests::AnnounceAccount(announce_account) => {
                 let state = self.state.clone();
+                let clock = self.clock.clone();
                 ctx.spawn(wrap_future(async move {
-                    state.add_accounts(vec![announce_account]).await;
+                    state.add_accounts(&clock, vec![announce_account]).await;
                 }));
                 NetworkResponses::NoResponse
             }
@@ -987,7 +1049,10 @@ impl PeerManagerActor {
                 // and if it fails, against the preference.
                 for prefer_peer in &[target.prefer_peer, !target.prefer_peer] {
                     if !prefer_peer {
-                        if let Some(account_id) = target.account_id.as_ref() {
+                        // Fall back to the next account if there is no route to the previous one.
+                        for account_id in
+                            target.account_id.iter().chain(&target.fallback_account_ids)
+                        {
                             if self.state.send_message_to_account(
                                 &self.clock,
                                 account_id,
@@ -997,6 +1062,9 @@ impl PeerManagerActor {
                                 break;
                             }
                         }
+                        if success {
+                            break;
+                        }
                     } else {
                         let mut matching_peers = vec![];
                         for (peer_id, peer) in &self.state.tier2.load().ready {
@@ -1104,7 +1172,10 @@ impl PeerManagerActor {
             }
             NetworkRequests::Challenge(challenge) => {
                 // TODO(illia): smarter routing?
-                self.state.tier2.broadcast_message(Arc::new(PeerMessage::Challenge(challenge)));
+                self.state.broadcast_gossip(
+                    Arc::new(PeerMessage::Challenge(challenge)),
+                    GossipPriority::Critical,
+                );
                 NetworkResponses::NoResponse
             }
             NetworkRequests::ChunkStateWitnessAck(target, ack) => {
@@ -1377,6 +1448,21 @@ impl actix::Handler<GetDebugStatus> for PeerManagerActor {
                     })
                     .collect::<Vec<_>>(),
             }),
+            GetDebugStatus::Bandwidth => {
+                let tier1 = self.state.tier1.load();
+                let tier2 = self.state.tier2.load();
+                DebugStatus::Bandwidth(NetworkBandwidthView {
+                    peers: tier1
+                        .ready
+                        .values()
+                        .chain(tier2.ready.values())
+                        .map(|conn| PeerBandwidthView {
+                            peer_id: conn.peer_info.id.clone(),
+                            message_types: conn.stats.bandwidth_view(&self.clock),
+                        })
+                        .collect(),
+                })
+            }
         }
     }
 }
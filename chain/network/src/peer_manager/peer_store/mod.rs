@@ -1,12 +1,15 @@
 use crate::blacklist;
 use crate::network_protocol::PeerInfo;
-use crate::types::{KnownPeerState, KnownPeerStatus, ReasonForBan};
-use anyhow::bail;
+use crate::stats::metrics;
+use crate::store;
+use crate::types::{DisconnectReason, KnownPeerState, KnownPeerStatus, PeerOffense, ReasonForBan};
+use anyhow::{bail, Context};
 use im::hashmap::Entry;
 use im::{HashMap, HashSet};
 use lru::LruCache;
 use near_async::time;
 use near_primitives::network::PeerId;
+use near_primitives::types::AccountId;
 use parking_lot::Mutex;
 use rand::seq::IteratorRandom;
 use rand::thread_rng;
@@ -14,6 +17,7 @@ use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::ops::Not;
 
+mod reputation;
 #[cfg(test)]
 mod testonly;
 #[cfg(test)]
@@ -25,8 +29,18 @@ mod tests;
 ///     - Respond to requests from other peers for known peers (see PeerStore::healthy_peers).
 ///     - Select peers to which we may try to connect directly (see PeerStore::unconnected_peer).
 ///
-/// Contents of the PeerStore are not persisted to the database. Upon starting a node,
-/// the PeerStore is initialized from the boot nodes in its config.
+/// Known peers and their connection history are persisted to the database, as are the
+/// bans of misbehaving peers. Upon starting a node, the PeerStore is initialized from
+/// the boot nodes in its config, the peers known before the restart and the bans which
+/// haven't expired yet.
+
+/// Number of consecutive failed connection attempts after which a peer which
+/// isn't a boot node is considered dead and removed from the store.
+const MAX_FAILED_ATTEMPTS: u32 = 10;
+
+/// Minimal interval between two writes of the known peers to the database.
+/// They are also written by PeerStore::flush, on shutdown.
+const SAVE_KNOWN_PEERS_INTERVAL: time::Duration = time::Duration::minutes(1);
 
 /// Level of trust we have about a new (PeerId, Addr) pair.
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
@@ -51,6 +65,16 @@ impl VerifiedPeer {
     }
 }
 
+/// Boot node given by a hostname rather than an IP address.
+/// It is resolved by the PeerManagerActor asynchronously and re-resolved periodically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BootNodeHost {
+    pub id: PeerId,
+    /// `host:port` of the boot node.
+    pub host: String,
+    pub account_id: Option<AccountId>,
+}
+
 #[derive(Clone)]
 pub struct Config {
     /// A list of nodes to connect to on the first run of the neard server.
@@ -61,10 +85,14 @@ pub struct Config {
     /// file, but you can modify the boot_nodes field to contain any nodes that
     /// you trust.
     pub boot_nodes: Vec<PeerInfo>,
+    /// Boot nodes given by hostnames. They are added to the store once resolved.
+    pub boot_node_hosts: Vec<BootNodeHost>,
     /// Nodes will not accept or try to establish connection to such peers.
     pub blacklist: blacklist::Blacklist,
     /// If true - connect only to the bootnodes.
     pub connect_only_to_boot_nodes: bool,
+    /// Peers whose addresses are not shared with other peers.
+    pub private_peers: Vec<PeerId>,
     /// The maximum number of peers to store. If capacity is exceeded, the peers
     /// with the earliest last_seen value will be evicted.
     pub peer_states_cache_size: u32,
@@ -85,6 +113,15 @@ struct Inner {
     // It can happens that some peers don't have known address, so
     // they will not be present in this list, otherwise they will be present.
     addr_peers: HashMap<SocketAddr, VerifiedPeer>,
+    // Reputation of peers which committed offenses recently. Peers are
+    // removed once their reputation recovers.
+    reputations: HashMap<PeerId, reputation::Reputation>,
+    // Storage for the known peers and bans, so that they survive restarts.
+    store: store::Store,
+    // Known peers as they were last written to the store.
+    saved_peers: HashMap<PeerId, KnownPeerState>,
+    // Time at which the known peers were last written to the store.
+    last_saved: Option<time::Instant>,
 }
 
 impl Inner {
@@ -157,6 +194,70 @@ impl Inner {
         Ok(())
     }
 
+    /// Restores peers known before the node restarted together with their
+    /// connection history. Boot nodes keep the addresses from the config.
+    fn load_known_peers(&mut self, clock: &time::Clock) -> anyhow::Result<()> {
+        let mut known_peers = self.store.get_known_peers()?;
+        self.saved_peers =
+            known_peers.iter().map(|state| (state.peer_info.id.clone(), state.clone())).collect();
+        // The most recently seen peers are added last, so that they are the
+        // last ones to be evicted from the cache.
+        known_peers.sort_by_key(|state| state.last_seen);
+        for known_peer in known_peers {
+            let peer_id = known_peer.peer_info.id.clone();
+            self.add_peer(clock, known_peer.peer_info, TrustLevel::Indirect);
+            if let Some(peer_state) = self.peer_states.peek_mut(&peer_id) {
+                if known_peer.status != KnownPeerStatus::Unknown {
+                    peer_state.status = known_peer.status;
+                }
+                peer_state.first_seen = known_peer.first_seen;
+                peer_state.last_seen = known_peer.last_seen;
+                peer_state.last_outbound_attempt = known_peer.last_outbound_attempt;
+                peer_state.failed_attempts = known_peer.failed_attempts;
+            }
+        }
+        Ok(())
+    }
+
+    /// Persists the known peers so that they survive restarts. Only the
+    /// peers which changed since the last write are written, and the ones
+    /// which were removed from the cache are deleted from the store.
+    fn save(&mut self) {
+        let known_peers: HashMap<_, _> =
+            self.peer_states.iter().map(|(id, state)| (id.clone(), state.clone())).collect();
+        let changed: Vec<_> = known_peers
+            .values()
+            .filter(|state| self.saved_peers.get(&state.peer_info.id) != Some(*state))
+            .cloned()
+            .collect();
+        let removed: Vec<_> =
+            self.saved_peers.keys().filter(|id| !known_peers.contains_key(*id)).cloned().collect();
+        if let Err(err) = self.store.update_known_peers(&changed, &removed) {
+            tracing::error!(target: "network", ?err, "Failed to save known peers");
+            return;
+        }
+        self.saved_peers = known_peers;
+    }
+
+    /// Restores bans which were issued before the node restarted and haven't
+    /// expired yet. Expired bans are removed from the storage.
+    fn load_bans(&mut self, clock: &time::Clock) -> anyhow::Result<()> {
+        let now = clock.now_utc();
+        for (peer_id, ban_reason, ban_time) in self.store.get_peer_bans()? {
+            if now >= ban_time + self.config.ban_window {
+                self.store.delete_peer_ban(&peer_id)?;
+                continue;
+            }
+            // Address of the peer is learned again if it connects after the ban.
+            let peer_info = PeerInfo { id: peer_id.clone(), addr: None, account_id: None };
+            self.add_peer(clock, peer_info, TrustLevel::Indirect);
+            if let Some(peer_state) = self.peer_states.peek_mut(&peer_id) {
+                peer_state.status = KnownPeerStatus::Banned(ban_reason, ban_time);
+            }
+        }
+        Ok(())
+    }
+
     /// Deletes peers from the internal cache
     fn delete_peers(&mut self, peer_ids: &[PeerId]) {
         for peer_id in peer_ids {
@@ -168,6 +269,21 @@ impl Inner {
         }
     }
 
+    /// Find a random peer matching the filter among the ones with the fewest
+    /// consecutive failed connection attempts.
+    fn find_reliable_peer<F>(&self, filter: F) -> Option<PeerInfo>
+    where
+        F: FnMut(&&KnownPeerState) -> bool,
+    {
+        let candidates: Vec<_> = self.peer_states.iter().map(|(_, v)| v).filter(filter).collect();
+        let min_failed_attempts = candidates.iter().map(|kps| kps.failed_attempts).min()?;
+        candidates
+            .into_iter()
+            .filter(|kps| kps.failed_attempts == min_failed_attempts)
+            .choose(&mut thread_rng())
+            .map(|kps| kps.peer_info.clone())
+    }
+
     /// Find a random subset of peers based on filter.
     fn find_peers<F>(&self, filter: F, count: usize) -> Vec<PeerInfo>
     where
@@ -224,7 +340,8 @@ impl Inner {
         }
     }
 
-    /// Removes peers that are not responding for expiration period.
+    /// Removes peers that are not responding for expiration period and
+    /// peers which we repeatedly failed to connect to.
     fn remove_expired(&mut self, now: time::Utc) {
         let mut to_remove = vec![];
         for (peer_id, peer_status) in self.peer_states.iter() {
@@ -233,12 +350,19 @@ impl Inner {
             {
                 tracing::debug!(target: "network", "Removing peer: last seen {:?} ago", now-peer_status.last_seen);
                 to_remove.push(peer_id.clone());
+            } else if peer_status.status == KnownPeerStatus::Unknown
+                && peer_status.failed_attempts >= MAX_FAILED_ATTEMPTS
+                && !self.boot_nodes.contains(peer_id)
+            {
+                tracing::debug!(target: "network", ?peer_id, failed_attempts = peer_status.failed_attempts, "Removing unreachable peer");
+                to_remove.push(peer_id.clone());
             }
         }
         self.delete_peers(&to_remove);
     }
 
-    fn unban(&mut self, now: time::Utc) {
+    /// Unbans the peers whose ban has expired and returns them.
+    fn unban(&mut self, now: time::Utc) -> Vec<PeerId> {
         let mut to_unban = vec![];
         for (peer_id, peer_state) in &self.peer_states {
             if let KnownPeerStatus::Banned(_, ban_time) = peer_state.status {
@@ -249,11 +373,14 @@ impl Inner {
                 to_unban.push(peer_id.clone());
             }
         }
-        for peer_id in &to_unban {
-            if let Err(err) = self.peer_unban(&peer_id) {
+        to_unban.retain(|peer_id| match self.peer_unban(peer_id) {
+            Ok(()) => true,
+            Err(err) => {
                 tracing::error!(target: "network", ?peer_id, ?err, "Failed to unban a peer");
+                false
             }
-        }
+        });
+        to_unban
     }
 
     /// Update the 'last_seen' time for all the peers that we're currently connected to.
@@ -276,20 +403,50 @@ impl Inner {
     /// * it unbans a peer if config.ban_window has passed
     /// * it updates KnownPeerStatus.last_seen of the connected peers
     /// * it removes peers which were not seen for config.peer_expiration_duration
-    /// This function should be called periodically.
-    pub fn update(&mut self, clock: &time::Clock) {
+    /// * it forgets offenses of peers whose reputation has fully recovered
+    /// * it persists the known peers, at most once per SAVE_KNOWN_PEERS_INTERVAL
+    /// * it exports the number of peers by status
+    /// This function should be called periodically. Returns the unbanned
+    /// peers, whose bans are to be deleted from the storage.
+    pub fn update(&mut self, clock: &time::Clock) -> Vec<PeerId> {
         let now = clock.now_utc();
-        self.unban(now);
+        let unbanned = self.unban(now);
         self.update_last_seen(now);
         self.remove_expired(now);
+        self.reputations.retain(|_, reputation| reputation.score(now) < 0.);
+        if self.last_saved.map_or(true, |last| clock.now() >= last + SAVE_KNOWN_PEERS_INTERVAL) {
+            self.save();
+            self.last_saved = Some(clock.now());
+        }
+        self.update_metrics();
+        unbanned
+    }
+
+    fn update_metrics(&self) {
+        let mut counts: HashMap<&'static str, i64> =
+            ["Unknown", "NotConnected", "Connected", "Banned"]
+                .into_iter()
+                .map(|status| (status, 0))
+                .collect();
+        for (_, peer_state) in self.peer_states.iter() {
+            *counts.entry((&peer_state.status).into()).or_default() += 1;
+        }
+        for (status, count) in counts {
+            metrics::PEER_STORE_PEERS.with_label_values(&[status]).set(count);
+        }
     }
 }
 
 pub(crate) struct PeerStore(Mutex<Inner>);
 
 impl PeerStore {
-    pub fn new(clock: &time::Clock, config: Config) -> anyhow::Result<Self> {
-        let boot_nodes: HashSet<_> = config.boot_nodes.iter().map(|p| p.id.clone()).collect();
+    pub fn new(clock: &time::Clock, config: Config, store: store::Store) -> anyhow::Result<Self> {
+        let boot_nodes: HashSet<_> = config
+            .boot_nodes
+            .iter()
+            .map(|p| p.id.clone())
+            .chain(config.boot_node_hosts.iter().map(|b| b.id.clone()))
+            .collect();
         // A mapping from `PeerId` to `KnownPeerState`.
         let mut peerid_2_state =
             LruCache::new(NonZeroUsize::new(config.peer_states_cache_size as usize).unwrap());
@@ -336,8 +493,18 @@ impl PeerStore {
             }
         }
 
-        let inner =
-            Inner { config, boot_nodes, peer_states: peerid_2_state, addr_peers: addr_2_peer };
+        let mut inner = Inner {
+            config,
+            boot_nodes,
+            peer_states: peerid_2_state,
+            addr_peers: addr_2_peer,
+            reputations: HashMap::default(),
+            store,
+            saved_peers: HashMap::default(),
+            last_saved: None,
+        };
+        inner.load_known_peers(clock).context("failed to load known peers")?;
+        inner.load_bans(clock).context("failed to load banned peers")?;
         Ok(PeerStore(Mutex::new(inner)))
     }
 
@@ -345,6 +512,10 @@ impl PeerStore {
         self.0.lock().config.blacklist.contains(*addr)
     }
 
+    pub fn is_peer_blacklisted(&self, peer_id: &PeerId) -> bool {
+        self.0.lock().config.blacklist.contains_peer(peer_id)
+    }
+
     pub fn len(&self) -> usize {
         self.0.lock().peer_states.len()
     }
@@ -358,7 +529,24 @@ impl PeerStore {
     }
 
     pub fn update(&self, clock: &time::Clock) {
-        self.0.lock().update(clock)
+        // Bans are written to the storage without holding the lock, so that
+        // the writes don't block the other users of the peer store.
+        let (unbanned, mut store) = {
+            let mut inner = self.0.lock();
+            (inner.update(clock), inner.store.clone())
+        };
+        for peer_id in unbanned {
+            if let Err(err) = store.delete_peer_ban(&peer_id) {
+                tracing::error!(target: "network", ?peer_id, ?err, "Failed to delete a peer ban");
+            }
+        }
+    }
+
+    /// Writes the known peers which changed since the last write to the
+    /// storage. Should be called on shutdown, so that the latest state of
+    /// the peers survives the restart.
+    pub fn flush(&self) {
+        self.0.lock().save();
     }
 
     #[allow(dead_code)]
@@ -373,6 +561,7 @@ impl PeerStore {
         let entry = inner.peer_states.get_mut(&peer_info.id).unwrap();
         entry.last_seen = clock.now_utc();
         entry.status = KnownPeerStatus::Connected;
+        entry.failed_attempts = 0;
     }
 
     pub fn peer_disconnected(&self, clock: &time::Clock, peer_id: &PeerId) -> anyhow::Result<()> {
@@ -399,6 +588,9 @@ impl PeerStore {
             if result.is_err() {
                 // Marks the peer status as Unknown (as we failed to connect to it).
                 peer_state.status = KnownPeerStatus::Unknown;
+                peer_state.failed_attempts = peer_state.failed_attempts.saturating_add(1);
+            } else {
+                peer_state.failed_attempts = 0;
             }
             peer_state.last_outbound_attempt =
                 Some((clock.now_utc(), result.map_err(|err| err.to_string())));
@@ -410,6 +602,18 @@ impl PeerStore {
         Ok(())
     }
 
+    /// Records the reason the peer gave for closing the connection.
+    pub fn peer_disconnect_reason(
+        &self,
+        clock: &time::Clock,
+        peer_id: &PeerId,
+        reason: DisconnectReason,
+    ) {
+        if let Some(peer_state) = self.0.lock().peer_states.get_mut(peer_id) {
+            peer_state.last_disconnect = Some((clock.now_utc(), reason));
+        }
+    }
+
     pub fn peer_ban(
         &self,
         clock: &time::Clock,
@@ -417,15 +621,51 @@ impl PeerStore {
         ban_reason: ReasonForBan,
     ) -> anyhow::Result<()> {
         tracing::warn!(target: "network", "Banning peer {} for {:?}", peer_id, ban_reason);
+        let now = clock.now_utc();
+        let mut store = {
+            let mut inner = self.0.lock();
+            if let Some(peer_state) = inner.peer_states.get_mut(peer_id) {
+                peer_state.last_seen = now;
+                peer_state.status = KnownPeerStatus::Banned(ban_reason, now);
+            } else {
+                bail!("Peer {} is missing in the peer store", peer_id);
+            }
+            inner.reputations.remove(peer_id);
+            inner.store.clone()
+        };
+        store.set_peer_ban(peer_id, ban_reason, now)?;
+        Ok(())
+    }
+
+    /// Lowers reputation of the peer for the offense. Returns reason to ban
+    /// the peer for if its reputation fell below the threshold; the caller
+    /// is expected to disconnect and ban the peer then.
+    pub fn peer_offense(
+        &self,
+        clock: &time::Clock,
+        peer_id: &PeerId,
+        offense: PeerOffense,
+    ) -> Option<ReasonForBan> {
+        metrics::PEER_OFFENSES_TOTAL.with_label_values(&[offense.into()]).inc();
         let mut inner = self.0.lock();
-        if let Some(peer_state) = inner.peer_states.get_mut(peer_id) {
-            let now = clock.now_utc();
-            peer_state.last_seen = now;
-            peer_state.status = KnownPeerStatus::Banned(ban_reason, now);
-        } else {
-            bail!("Peer {} is missing in the peer store", peer_id);
+        if inner.peer_states.peek(peer_id).is_some_and(|s| s.status.is_banned()) {
+            return None;
         }
-        Ok(())
+        let now = clock.now_utc();
+        let ban_reason = inner
+            .reputations
+            .entry(peer_id.clone())
+            .or_insert_with(|| reputation::Reputation::new(now))
+            .punish(now, offense);
+        tracing::debug!(target: "network", ?peer_id, ?offense, ?ban_reason, "peer offense");
+        ban_reason
+    }
+
+    /// Returns current reputation of the peer. It is zero for peers without
+    /// recent offenses and negative otherwise.
+    pub fn peer_reputation(&self, clock: &time::Clock, peer_id: &PeerId) -> f64 {
+        let inner = self.0.lock();
+        inner.reputations.get(peer_id).map_or(0., |r| r.score(clock.now_utc()))
     }
 
     /// Return unconnected or peers with unknown status that we can try to connect to.
@@ -455,25 +695,26 @@ impl PeerStore {
             };
             // otherwise, pick a peer from the wider pool below.
         }
+        // Peers we failed to connect to the least number of times go first.
+        inner.find_reliable_peer(|p| {
+            (p.status == KnownPeerStatus::NotConnected || p.status == KnownPeerStatus::Unknown)
+                && !ignore_fn(p)
+                && p.peer_info.addr.is_some()
+                // If we're connecting only to the boot nodes - filter out the nodes that are not boot nodes.
+                && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
+        })
+    }
+
+    /// Return healthy known peers up to given amount. Private peers are never returned.
+    pub fn healthy_peers(&self, max_count: usize) -> Vec<PeerInfo> {
+        let inner = self.0.lock();
         inner.find_peers(
             |p| {
-                (p.status == KnownPeerStatus::NotConnected || p.status == KnownPeerStatus::Unknown)
-                    && !ignore_fn(p)
-                    && p.peer_info.addr.is_some()
-                    // If we're connecting only to the boot nodes - filter out the nodes that are not boot nodes.
-                    && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
+                matches!(p.status, KnownPeerStatus::Banned(_, _)).not()
+                    && !inner.config.private_peers.contains(&p.peer_info.id)
             },
-            1,
+            max_count,
         )
-        .get(0)
-        .cloned()
-    }
-
-    /// Return healthy known peers up to given amount.
-    pub fn healthy_peers(&self, max_count: usize) -> Vec<PeerInfo> {
-        self.0
-            .lock()
-            .find_peers(|p| matches!(p.status, KnownPeerStatus::Banned(_, _)).not(), max_count)
     }
 
     /// Adds peers we’ve learned about from other peers.
@@ -490,7 +731,8 @@ impl PeerStore {
         for peer_info in peers {
             total += 1;
             let is_blacklisted =
-                peer_info.addr.is_some_and(|addr| inner.config.blacklist.contains(addr));
+                peer_info.addr.is_some_and(|addr| inner.config.blacklist.contains(addr))
+                    || inner.config.blacklist.contains_peer(&peer_info.id);
             if is_blacklisted {
                 blacklisted += 1;
             } else {
@@ -514,6 +756,19 @@ impl PeerStore {
         self.0.lock().add_peer(clock, peer_info, TrustLevel::Direct)
     }
 
+    /// Adds a boot node with a freshly resolved address.
+    ///
+    /// Like the boot nodes with IP addresses given in the config, it is
+    /// trusted, so the address replaces any address known for the peer.
+    pub fn add_boot_node(&self, clock: &time::Clock, peer_info: PeerInfo) {
+        let mut inner = self.0.lock();
+        if !inner.boot_nodes.contains(&peer_info.id) {
+            tracing::error!(target: "network", id = ?peer_info.id, "Not a boot node");
+            return;
+        }
+        inner.add_signed_peer(clock, peer_info)
+    }
+
     pub fn load(&self) -> HashMap<PeerId, KnownPeerState> {
         self.0.lock().peer_states.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
     }
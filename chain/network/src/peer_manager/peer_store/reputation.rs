@@ -0,0 +1,70 @@
+use crate::types::{PeerOffense, ReasonForBan};
+use near_async::time;
+
+/// Reputation at or below which a peer gets banned.
+const BAN_THRESHOLD: f64 = -100.;
+
+/// Reputation regained by a peer every hour without offenses.
+const RECOVERY_PER_HOUR: f64 = 20.;
+
+impl PeerOffense {
+    /// How much the offense lowers reputation of the peer.
+    ///
+    /// Invalid blocks and protocol violations can hardly be caused by an
+    /// honest peer so a couple of them is enough to get banned.  Invalid
+    /// transactions and timeouts happen in normal operation (e.g. a nonce
+    /// race or a congested link) and only a steady stream of them does.
+    /// Exceeding rate limits is reported at most once per a few seconds, so a
+    /// peer gets banned once it keeps flooding us for minutes rather than
+    /// after a single burst.
+    fn penalty(self) -> f64 {
+        match self {
+            PeerOffense::InvalidBlock => 50.,
+            PeerOffense::InvalidTransaction => 2.,
+            PeerOffense::Timeout => 5.,
+            PeerOffense::ProtocolViolation => 50.,
+            PeerOffense::RateLimitExceeded => 5.,
+        }
+    }
+
+    /// Reason reported for banning a peer whose last offense was this one.
+    fn ban_reason(self) -> ReasonForBan {
+        match self {
+            PeerOffense::InvalidBlock => ReasonForBan::BadBlock,
+            PeerOffense::InvalidTransaction
+            | PeerOffense::Timeout
+            | PeerOffense::ProtocolViolation
+            | PeerOffense::RateLimitExceeded => ReasonForBan::Abusive,
+        }
+    }
+}
+
+/// Reputation of a peer which has committed offenses recently.
+///
+/// Peers start with reputation of zero which is lowered by each offense and
+/// recovers linearly with time back to zero.
+#[derive(Debug, Clone)]
+pub(super) struct Reputation {
+    score: f64,
+    updated: time::Utc,
+}
+
+impl Reputation {
+    pub fn new(now: time::Utc) -> Self {
+        Self { score: 0., updated: now }
+    }
+
+    /// Returns the reputation at given time.
+    pub fn score(&self, now: time::Utc) -> f64 {
+        let hours = (now - self.updated).as_seconds_f64() / 3600.;
+        (self.score + hours.max(0.) * RECOVERY_PER_HOUR).min(0.)
+    }
+
+    /// Lowers the reputation for the offense.  Returns reason to ban the peer
+    /// for if the reputation fell to the ban threshold.
+    pub fn punish(&mut self, now: time::Utc, offense: PeerOffense) -> Option<ReasonForBan> {
+        self.score = self.score(now) - offense.penalty();
+        self.updated = now;
+        (self.score <= BAN_THRESHOLD).then(|| offense.ban_reason())
+    }
+}
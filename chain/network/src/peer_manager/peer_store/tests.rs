@@ -25,6 +25,10 @@ fn gen_peer_info(port: u16) -> PeerInfo {
     }
 }
 
+fn make_store() -> store::Store {
+    store::Store::from(near_store::db::TestDB::new())
+}
+
 fn make_config(
     boot_nodes: &[PeerInfo],
     blacklist: blacklist::Blacklist,
@@ -32,9 +36,11 @@ fn make_config(
 ) -> Config {
     Config {
         boot_nodes: boot_nodes.iter().cloned().collect(),
+        boot_node_hosts: vec![],
         blacklist,
         peer_states_cache_size: 1000,
         connect_only_to_boot_nodes,
+        private_peers: vec![],
         ban_window: time::Duration::seconds(1),
         peer_expiration_duration: time::Duration::days(1000),
     }
@@ -47,14 +53,197 @@ fn ban_store() {
     let peer_info_to_ban = gen_peer_info(1);
     let boot_nodes = vec![peer_info_a, peer_info_to_ban.clone()];
 
-    let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-            .unwrap();
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        make_store(),
+    )
+    .unwrap();
     assert_eq!(peer_store.healthy_peers(3).len(), 2);
     peer_store.peer_ban(&clock.clock(), &peer_info_to_ban.id, ReasonForBan::Abusive).unwrap();
     assert_eq!(peer_store.healthy_peers(3).len(), 1);
 }
 
+#[test]
+fn private_peers_are_not_shared() {
+    let clock = time::FakeClock::default();
+    let peer_info_a = gen_peer_info(0);
+    let private_peer = gen_peer_info(1);
+    let mut config = make_config(&[], Blacklist::default(), false);
+    config.private_peers = vec![private_peer.id.clone()];
+
+    let peer_store = PeerStore::new(&clock.clock(), config, make_store()).unwrap();
+    peer_store.add_direct_peer(&clock.clock(), peer_info_a.clone());
+    peer_store.add_direct_peer(&clock.clock(), private_peer);
+    assert_eq!(peer_store.healthy_peers(3), vec![peer_info_a]);
+}
+
+#[test]
+fn ban_survives_restart() {
+    let clock = time::FakeClock::default();
+    let store = make_store();
+    let peer_info = gen_peer_info(0);
+    let config = make_config(&[], Blacklist::default(), false);
+
+    let peer_store = PeerStore::new(&clock.clock(), config.clone(), store.clone()).unwrap();
+    peer_store.add_direct_peer(&clock.clock(), peer_info.clone());
+    peer_store.peer_ban(&clock.clock(), &peer_info.id, ReasonForBan::Abusive).unwrap();
+    drop(peer_store);
+
+    let peer_store = PeerStore::new(&clock.clock(), config.clone(), store.clone()).unwrap();
+    assert!(peer_store.is_banned(&peer_info.id));
+
+    // Expired bans are neither restored nor kept in the storage.
+    clock.advance(config.ban_window);
+    let peer_store = PeerStore::new(&clock.clock(), config, store.clone()).unwrap();
+    assert!(!peer_store.is_banned(&peer_info.id));
+    assert_eq!(store.get_peer_bans().unwrap(), vec![]);
+}
+
+#[test]
+fn known_peers_survive_restart() {
+    let clock = time::FakeClock::default();
+    let store = make_store();
+    let config = make_config(&[], Blacklist::default(), false);
+    let peer_a = gen_peer_info(0);
+    let peer_b = gen_peer_info(1);
+
+    let peer_store = PeerStore::new(&clock.clock(), config.clone(), store.clone()).unwrap();
+    peer_store.add_direct_peer(&clock.clock(), peer_a.clone());
+    peer_store.add_direct_peer(&clock.clock(), peer_b.clone());
+    peer_store.peer_connected(&clock.clock(), &peer_a);
+    peer_store.peer_disconnected(&clock.clock(), &peer_a.id).unwrap();
+    peer_store
+        .peer_connection_attempt(&clock.clock(), &peer_b.id, Err(anyhow::anyhow!("refused")))
+        .unwrap();
+    peer_store.update(&clock.clock());
+    drop(peer_store);
+
+    let peer_store = PeerStore::new(&clock.clock(), config, store).unwrap();
+    let state_a = peer_store.get_peer_state(&peer_a.id).unwrap();
+    assert_eq!(state_a.status, KnownPeerStatus::NotConnected);
+    assert_eq!(state_a.peer_info, peer_a);
+    let state_b = peer_store.get_peer_state(&peer_b.id).unwrap();
+    assert_eq!(state_b.status, KnownPeerStatus::Unknown);
+    assert_eq!(state_b.failed_attempts, 1);
+    // The peer which didn't fail is tried first.
+    assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_a));
+
+    for _ in 1..MAX_FAILED_ATTEMPTS {
+        peer_store
+            .peer_connection_attempt(&clock.clock(), &peer_b.id, Err(anyhow::anyhow!("refused")))
+            .unwrap();
+    }
+    peer_store.update(&clock.clock());
+    assert!(peer_store.get_peer_state(&peer_b.id).is_none());
+}
+
+#[test]
+fn resolved_boot_node() {
+    let clock = time::FakeClock::default();
+    let boot_node = gen_peer_info(0);
+    let mut config = make_config(&[], Blacklist::default(), true);
+    config.boot_node_hosts = vec![BootNodeHost {
+        id: boot_node.id.clone(),
+        host: "boot.invalid:24567".to_string(),
+        account_id: None,
+    }];
+    let peer_store = PeerStore::new(&clock.clock(), config, make_store()).unwrap();
+    assert_eq!(peer_store.unconnected_peer(|_| false, false), None);
+
+    peer_store.add_boot_node(&clock.clock(), boot_node.clone());
+    assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(boot_node.clone()));
+
+    // Re-resolved address replaces the old one.
+    let moved = get_peer_info(boot_node.id.clone(), Some(get_addr(1)));
+    peer_store.add_boot_node(&clock.clock(), moved.clone());
+    assert_eq!(peer_store.get_peer_state(&boot_node.id).unwrap().peer_info, moved);
+
+    // Peers which are not boot nodes are ignored.
+    let other = gen_peer_info(2);
+    peer_store.add_boot_node(&clock.clock(), other.clone());
+    assert!(peer_store.get_peer_state(&other.id).is_none());
+}
+
+#[test]
+fn known_peers_saved_periodically() {
+    let clock = time::FakeClock::default();
+    let store = make_store();
+    let config = make_config(&[], Blacklist::default(), false);
+    let peer_store = PeerStore::new(&clock.clock(), config, store.clone()).unwrap();
+    peer_store.update(&clock.clock());
+
+    peer_store.add_direct_peer(&clock.clock(), gen_peer_info(0));
+    peer_store.update(&clock.clock());
+    assert_eq!(store.get_known_peers().unwrap().len(), 0);
+    clock.advance(SAVE_KNOWN_PEERS_INTERVAL);
+    peer_store.update(&clock.clock());
+    assert_eq!(store.get_known_peers().unwrap().len(), 1);
+
+    // The latest state is saved on flush.
+    let peer = gen_peer_info(1);
+    peer_store.add_direct_peer(&clock.clock(), peer.clone());
+    peer_store.flush();
+    assert_eq!(store.get_known_peers().unwrap().len(), 2);
+
+    // Rows of the removed peers are deleted.
+    peer_store.0.lock().delete_peers(&[peer.id]);
+    peer_store.flush();
+    assert_eq!(store.get_known_peers().unwrap().len(), 1);
+}
+
+#[test]
+fn ban_for_offenses() {
+    let clock = time::FakeClock::default();
+    let peer_info = gen_peer_info(0);
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], Blacklist::default(), false), make_store())
+            .unwrap();
+    peer_store.add_direct_peer(&clock.clock(), peer_info.clone());
+
+    let offense = PeerOffense::InvalidBlock;
+    assert_eq!(peer_store.peer_reputation(&clock.clock(), &peer_info.id), 0.);
+    assert_eq!(peer_store.peer_offense(&clock.clock(), &peer_info.id, offense), None);
+    assert_eq!(peer_store.peer_reputation(&clock.clock(), &peer_info.id), -50.);
+    // Reputation recovers with time.
+    clock.advance(time::Duration::hours(10));
+    peer_store.update(&clock.clock());
+    assert_eq!(peer_store.peer_reputation(&clock.clock(), &peer_info.id), 0.);
+    assert_eq!(peer_store.peer_offense(&clock.clock(), &peer_info.id, offense), None);
+    assert_eq!(
+        peer_store.peer_offense(&clock.clock(), &peer_info.id, offense),
+        Some(ReasonForBan::BadBlock)
+    );
+
+    peer_store.peer_ban(&clock.clock(), &peer_info.id, ReasonForBan::BadBlock).unwrap();
+    assert_eq!(peer_store.peer_offense(&clock.clock(), &peer_info.id, offense), None);
+}
+
+#[test]
+fn reconnect_delayed_after_disconnect() {
+    let clock = time::FakeClock::default();
+    let peer_info = gen_peer_info(0);
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], Blacklist::default(), false), make_store())
+            .unwrap();
+    peer_store.add_direct_peer(&clock.clock(), peer_info.clone());
+    let delayed =
+        || peer_store.get_peer_state(&peer_info.id).unwrap().reconnect_delayed(clock.now_utc());
+    assert!(!delayed());
+
+    peer_store.peer_disconnect_reason(&clock.clock(), &peer_info.id, DisconnectReason::Unknown);
+    assert!(!delayed());
+
+    peer_store.peer_disconnect_reason(
+        &clock.clock(),
+        &peer_info.id,
+        DisconnectReason::TooManyPeers,
+    );
+    assert!(delayed());
+    clock.advance(time::Duration::minutes(5));
+    assert!(!delayed());
+}
+
 #[test]
 fn test_unconnected_peer() {
     let clock = time::FakeClock::default();
@@ -62,9 +251,12 @@ fn test_unconnected_peer() {
     let peer_info_to_ban = gen_peer_info(1);
     let boot_nodes = vec![peer_info_a, peer_info_to_ban];
 
-    let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-            .unwrap();
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        make_store(),
+    )
+    .unwrap();
 
     assert!(peer_store.unconnected_peer(|_| false, false).is_some());
     assert!(peer_store.unconnected_peer(|_| true, false).is_none());
@@ -85,9 +277,12 @@ fn test_unknown_vs_not_connected() {
         nodes.map(|peer| peer_store.get_peer_state(&peer.id).map(|known_state| known_state.status))
     };
 
-    let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-            .unwrap();
+    let peer_store = PeerStore::new(
+        &clock.clock(),
+        make_config(&boot_nodes, Blacklist::default(), false),
+        make_store(),
+    )
+    .unwrap();
 
     // Check the status of the in-memory store.
     // Boot node should be marked as not-connected, as we've verified it.
@@ -159,9 +354,12 @@ fn test_unconnected_peer_only_boot_nodes() {
     // 1 non-boot (peer_in_store) node peer that is in the store.
     // we should connect to peer_in_store
     {
-        let peer_store =
-            PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
-                .unwrap();
+        let peer_store = PeerStore::new(
+            &clock.clock(),
+            make_config(&boot_nodes, Blacklist::default(), false),
+            make_store(),
+        )
+        .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store.clone());
         peer_store.peer_connected(&clock.clock(), &peer_info_a);
         assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_in_store.clone()));
@@ -171,9 +369,12 @@ fn test_unconnected_peer_only_boot_nodes() {
     // 1 non-boot (peer_in_store) node peer that is in the store.
     // connect to only boot nodes is enabled - we should not find any peer to connect to.
     {
-        let peer_store =
-            PeerStore::new(&clock.clock(), make_config(&boot_nodes, Default::default(), true))
-                .unwrap();
+        let peer_store = PeerStore::new(
+            &clock.clock(),
+            make_config(&boot_nodes, Default::default(), true),
+            make_store(),
+        )
+        .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store);
         peer_store.peer_connected(&clock.clock(), &peer_info_a);
         assert_eq!(peer_store.unconnected_peer(|_| false, false), None);
@@ -185,6 +386,7 @@ fn test_unconnected_peer_only_boot_nodes() {
         let peer_store = PeerStore::new(
             &clock.clock(),
             make_config(&boot_nodes, Default::default(), connect_to_boot_nodes),
+            make_store(),
         )
         .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_info_a.clone());
@@ -234,7 +436,8 @@ fn check_integrity(peer_store: &PeerStore) -> bool {
 fn handle_peer_id_change() {
     let clock = time::FakeClock::default();
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     let peers_id = (0..2).map(|ix| get_peer_id(format!("node{}", ix))).collect::<Vec<_>>();
     let addr = get_addr(0);
@@ -258,7 +461,8 @@ fn handle_peer_id_change() {
 fn dont_handle_address_change() {
     let clock = time::FakeClock::default();
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     let peers_id = (0..1).map(|ix| get_peer_id(format!("node{}", ix))).collect::<Vec<_>>();
     let addrs = (0..2).map(get_addr).collect::<Vec<_>>();
@@ -277,7 +481,8 @@ fn dont_handle_address_change() {
 fn check_add_peers_overriding() {
     let clock = time::FakeClock::default();
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     // Five peers: A, B, C, D, X, T
     let peers_id = (0..6).map(|ix| get_peer_id(format!("node{}", ix))).collect::<Vec<_>>();
@@ -369,7 +574,8 @@ fn check_ignore_blacklisted_peers() {
     let blacklist: blacklist::Blacklist =
         ["127.0.0.1:1"].iter().map(|e| e.parse().unwrap()).collect();
 
-    let peer_store = PeerStore::new(&clock.clock(), make_config(&[], blacklist, false)).unwrap();
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], blacklist, false), make_store()).unwrap();
 
     peer_store.add_indirect_peers(
         &clock.clock(),
@@ -414,7 +620,8 @@ fn test_delete_peers() {
     let peer_addresses = peer_infos.iter().map(|info| info.addr.unwrap()).collect::<Vec<_>>();
 
     let peer_store =
-        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false)).unwrap();
+        PeerStore::new(&clock.clock(), make_config(&[], Default::default(), false), make_store())
+            .unwrap();
 
     peer_store.add_indirect_peers(&clock.clock(), peer_infos.into_iter());
     assert_peers_in_cache(&peer_store, &peer_ids, &peer_addresses);
@@ -428,7 +635,7 @@ fn test_lru_eviction() {
     let clock = time::FakeClock::default();
     let mut config = make_config(&[], Default::default(), false);
     config.peer_states_cache_size = 10;
-    let peer_store = PeerStore::new(&clock.clock(), config).unwrap();
+    let peer_store = PeerStore::new(&clock.clock(), config, make_store()).unwrap();
 
     let (peer_ids, peer_infos): (Vec<_>, Vec<_>) = (0..15)
         .map(|i| {
@@ -459,7 +666,7 @@ fn test_lru_ignore_duplicate_peers() {
     let clock = time::FakeClock::default();
     let mut config = make_config(&[], Default::default(), false);
     config.peer_states_cache_size = 10;
-    let peer_store = PeerStore::new(&clock.clock(), config).unwrap();
+    let peer_store = PeerStore::new(&clock.clock(), config, make_store()).unwrap();
 
     let (peer_ids, peer_infos): (Vec<_>, Vec<_>) = (0..15)
         .map(|i| {
@@ -14,7 +14,7 @@ use crate::network_protocol::{
     EdgeState, Encoding, PeerInfo, PeerMessage, SignedAccountData, SyncAccountsData,
 };
 use crate::peer;
-use crate::peer::peer_actor::ClosingReason;
+use crate::peer::peer_actor::{ClosingReason, PeerActor};
 use crate::peer_manager::network_state::NetworkState;
 use crate::peer_manager::peer_manager_actor::Event as PME;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
@@ -119,13 +119,15 @@ pub(crate) fn make_chain_info(
 
 pub(crate) struct RawConnection {
     pub events: broadcast::Receiver<Event>,
+    /// Id of the stream, as reported by PeerManager.
+    pub stream_id: tcp::StreamId,
     pub stream: tcp::Stream,
     pub cfg: peer::testonly::PeerConfig,
 }
 
 impl RawConnection {
     pub async fn handshake(mut self, clock: &time::Clock) -> peer::testonly::PeerHandle {
-        let stream_id = self.stream.id();
+        let stream_id = self.stream_id;
         let mut peer =
             peer::testonly::PeerHandle::start_endpoint(clock.clone(), self.cfg, self.stream).await;
 
@@ -149,7 +151,7 @@ impl RawConnection {
 
     // Try to perform a handshake. PeerManager is expected to reject the handshake.
     pub async fn manager_fail_handshake(mut self, clock: &time::Clock) -> ClosingReason {
-        let stream_id = self.stream.id();
+        let stream_id = self.stream_id;
         let peer =
             peer::testonly::PeerHandle::start_endpoint(clock.clone(), self.cfg, self.stream).await;
         let reason = self
@@ -248,6 +250,7 @@ impl ActorHandler {
         let stream_id = stream.id();
         let conn = RawConnection {
             events,
+            stream_id,
             stream,
             cfg: peer::testonly::PeerConfig {
                 network: network_cfg,
@@ -272,6 +275,53 @@ impl ActorHandler {
         conn
     }
 
+    /// Establishes an inbound connection, which PeerManager observes as coming from `ip`.
+    /// Allows to test the limits depending on the peer address, which is always
+    /// a loopback one for the connections established by `start_inbound`.
+    pub async fn start_inbound_from(
+        &self,
+        clock: &time::Clock,
+        chain: Arc<data::Chain>,
+        network_cfg: config::NetworkConfig,
+        ip: std::net::IpAddr,
+    ) -> RawConnection {
+        let (outbound_stream, mut inbound_stream) =
+            tcp::Stream::loopback(self.cfg.node_id(), tcp::Tier::T2).await;
+        inbound_stream.peer_addr.set_ip(ip);
+        let stream_id = inbound_stream.id();
+        let events = self.events.from_now();
+        let clock = clock.clone();
+        self.with_state(move |s| async move {
+            PeerActor::spawn(clock, inbound_stream, None, s).unwrap();
+        })
+        .await;
+        let conn = RawConnection {
+            events,
+            stream_id,
+            stream: outbound_stream,
+            cfg: peer::testonly::PeerConfig {
+                network: network_cfg,
+                chain,
+                force_encoding: Some(Encoding::Proto),
+            },
+        };
+        // Wait until the handshake started or connection is closed.
+        // The Handshake is not performed yet.
+        conn.events
+            .clone()
+            .recv_until(|ev| match ev {
+                Event::PeerManager(PME::HandshakeStarted(ev)) if ev.stream_id == stream_id => {
+                    Some(())
+                }
+                Event::PeerManager(PME::ConnectionClosed(ev)) if ev.stream_id == stream_id => {
+                    Some(())
+                }
+                _ => None,
+            })
+            .await;
+        conn
+    }
+
     pub async fn start_outbound(
         &self,
         chain: Arc<data::Chain>,
@@ -287,6 +337,7 @@ impl ActorHandler {
         );
         let conn = RawConnection {
             events,
+            stream_id,
             stream: inbound_stream,
             cfg: peer::testonly::PeerConfig {
                 network: network_cfg,
@@ -510,12 +561,15 @@ impl ActorHandler {
         }
     }
 
-    pub async fn wait_for_account_owner(&self, account: &AccountId) -> PeerId {
+    pub async fn wait_for_account_owner(&self, clock: &time::Clock, account: &AccountId) -> PeerId {
         let mut events = self.events.from_now();
         loop {
             let account = account.clone();
+            let clock = clock.clone();
             let got = self
-                .with_state(|s| async move { s.account_announcements.get_account_owner(&account) })
+                .with_state(|s| async move {
+                    s.account_announcements.get_account_owner(&clock, &account)
+                })
                 .await;
             if let Some(got) = got {
                 return got;
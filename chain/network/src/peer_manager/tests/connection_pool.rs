@@ -1,4 +1,5 @@
-use crate::config::SocketOptions;
+use crate::blacklist;
+use crate::config::{InboundSubnetLimit, SocketOptions};
 use crate::network_protocol::testonly as data;
 use crate::network_protocol::PeerMessage;
 use crate::network_protocol::{Encoding, Handshake, OwnedAccount, PartialEdgeInfo};
@@ -12,10 +13,12 @@ use crate::private_actix::RegisterPeerError;
 use crate::tcp;
 use crate::testonly::make_rng;
 use crate::testonly::stream::Stream;
-use crate::types::Edge;
+use crate::types::{Edge, PeerOffense};
 use near_async::time;
 use near_o11y::testonly::init_test_logger;
+use near_primitives::network::PeerId;
 use near_primitives::version::PROTOCOL_VERSION;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 #[tokio::test]
@@ -107,6 +110,9 @@ async fn loop_connection() {
                 &pm.cfg.node_key,
             ),
             owned_account: None,
+            supports_compression: false,
+            supports_block_announcements: false,
+            sender_advertised_addr: None,
         }))
         .await;
     let reason = events
@@ -177,6 +183,9 @@ async fn owned_account_mismatch() {
                 }
                 .sign(&signer),
             ),
+            supports_compression: false,
+            supports_block_announcements: false,
+            sender_advertised_addr: None,
         }))
         .await;
     let reason = events
@@ -299,6 +308,9 @@ async fn invalid_edge() {
                     }
                     .sign(&signer),
                 ),
+                supports_compression: false,
+                supports_block_announcements: false,
+                sender_advertised_addr: None,
             };
             let handshake = match tier {
                 tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
@@ -326,3 +338,279 @@ async fn invalid_edge() {
         }
     }
 }
+
+#[tokio::test]
+async fn advertised_addr() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let blacklisted_addr: SocketAddr = "127.0.0.1:1".parse().unwrap();
+    let mut pm_cfg = chain.make_config(rng);
+    pm_cfg.peer_store.blacklist =
+        [blacklist::Entry::from_addr(blacklisted_addr)].into_iter().collect();
+    let pm = peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        pm_cfg,
+        chain.clone(),
+    )
+    .await;
+
+    let testcases = [
+        // An address on another IP is ignored in favor of the observed one.
+        ("spoofed", Some("1.2.3.4:24567".parse().unwrap()), None),
+        // An address on the observed IP is accepted.
+        ("same ip", Some("127.0.0.1:24567".parse().unwrap()), Some("127.0.0.1:24567")),
+        // An address on the observed IP still has to pass the blacklist.
+        ("blacklisted", Some(blacklisted_addr), None),
+    ];
+    for (name, advertised_addr, want_addr) in testcases {
+        tracing::info!(target:"test", "{name}");
+        let cfg = chain.make_config(rng);
+        let stream =
+            tcp::Stream::connect(&pm.peer_info(), tcp::Tier::T2, &SocketOptions::default())
+                .await
+                .unwrap();
+        let stream_id = stream.id();
+        let local_addr = stream.local_addr;
+        let mut events = pm.events.from_now();
+        let mut stream = Stream::new(Some(Encoding::Proto), stream);
+        stream
+            .write(&PeerMessage::Tier2Handshake(Handshake {
+                protocol_version: PROTOCOL_VERSION,
+                oldest_supported_version: PROTOCOL_VERSION,
+                sender_peer_id: cfg.node_id(),
+                target_peer_id: pm.cfg.node_id(),
+                sender_listen_port: Some(local_addr.port()),
+                sender_chain_info: chain.get_peer_chain_info(),
+                partial_edge_info: PartialEdgeInfo::new(
+                    &cfg.node_id(),
+                    &pm.cfg.node_id(),
+                    Edge::create_fresh_nonce(&clock.clock()),
+                    &cfg.node_key,
+                ),
+                owned_account: None,
+                supports_compression: false,
+                supports_block_announcements: false,
+                sender_advertised_addr: advertised_addr,
+            }))
+            .await;
+        let accepted = events
+            .recv_until(|ev| match ev {
+                Event::PeerManager(PME::ConnectionClosed(ev)) if ev.stream_id == stream_id => {
+                    assert_eq!(
+                        ClosingReason::RejectedByPeerManager(RegisterPeerError::Blacklisted),
+                        ev.reason
+                    );
+                    Some(false)
+                }
+                Event::PeerManager(PME::HandshakeCompleted(ev)) if ev.stream_id == stream_id => {
+                    Some(true)
+                }
+                _ => None,
+            })
+            .await;
+        if name == "blacklisted" {
+            assert!(!accepted);
+            continue;
+        }
+        assert!(accepted);
+        let want_addr = want_addr.map_or(local_addr, |addr| addr.parse().unwrap());
+        let peer_id = cfg.node_id();
+        let got_addr = pm
+            .with_state(
+                |s| async move { s.tier2.load().ready.get(&peer_id).unwrap().peer_info.addr },
+            )
+            .await;
+        assert_eq!(Some(want_addr), got_addr);
+    }
+}
+
+#[tokio::test]
+async fn max_num_inbound_peers_limit() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut pm_cfg = chain.make_config(rng);
+    pm_cfg.max_num_inbound_peers = 1;
+    let pm = peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        pm_cfg,
+        chain.clone(),
+    )
+    .await;
+
+    tracing::info!(target:"test", "the first inbound peer is accepted");
+    let conn = pm.start_inbound(chain.clone(), chain.make_config(rng)).await;
+    let _inbound = conn.handshake(&clock.clock()).await;
+
+    tracing::info!(target:"test", "the second one exceeds the limit");
+    let conn = pm.start_inbound(chain.clone(), chain.make_config(rng)).await;
+    assert_eq!(
+        ClosingReason::RejectedByPeerManager(RegisterPeerError::ConnectionLimitExceeded),
+        conn.manager_fail_handshake(&clock.clock()).await
+    );
+
+    tracing::info!(target:"test", "outbound peers don't count against the limit");
+    let conn = pm.start_outbound(chain.clone(), chain.make_config(rng), tcp::Tier::T2).await;
+    let _outbound = conn.handshake(&clock.clock()).await;
+}
+
+#[tokio::test]
+async fn max_num_outbound_peers_limit() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut pm_cfg = chain.make_config(rng);
+    pm_cfg.max_num_outbound_peers = 1;
+    let pm = peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        pm_cfg,
+        chain.clone(),
+    )
+    .await;
+
+    tracing::info!(target:"test", "the first outbound peer is accepted");
+    let conn = pm.start_outbound(chain.clone(), chain.make_config(rng), tcp::Tier::T2).await;
+    let _outbound = conn.handshake(&clock.clock()).await;
+
+    tracing::info!(target:"test", "the second one exceeds the limit");
+    let conn = pm.start_outbound(chain.clone(), chain.make_config(rng), tcp::Tier::T2).await;
+    assert_eq!(
+        ClosingReason::RejectedByPeerManager(RegisterPeerError::ConnectionLimitExceeded),
+        conn.manager_fail_handshake(&clock.clock()).await
+    );
+
+    tracing::info!(target:"test", "inbound peers don't count against the limit");
+    let conn = pm.start_inbound(chain.clone(), chain.make_config(rng)).await;
+    let _inbound = conn.handshake(&clock.clock()).await;
+}
+
+#[tokio::test]
+async fn max_inbound_peers_per_subnet_limit() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut pm_cfg = chain.make_config(rng);
+    pm_cfg.inbound_subnet_limit =
+        Some(InboundSubnetLimit { max_peers: 1, ipv4_prefix_len: 24, ipv6_prefix_len: 64 });
+    let pm = peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        pm_cfg,
+        chain.clone(),
+    )
+    .await;
+    let clock = clock.clock();
+    let mut peers = vec![];
+
+    tracing::info!(target:"test", "the first peer from a subnet is accepted");
+    for ip in ["8.8.8.1", "2001:db8::1"] {
+        let cfg = chain.make_config(rng);
+        let conn = pm.start_inbound_from(&clock, chain.clone(), cfg, ip.parse().unwrap()).await;
+        peers.push(conn.handshake(&clock).await);
+    }
+
+    tracing::info!(target:"test", "the next peers from the same subnets exceed the limit");
+    for ip in ["8.8.8.2", "::ffff:8.8.8.3", "2001:db8::ffff:2"] {
+        let cfg = chain.make_config(rng);
+        let conn = pm.start_inbound_from(&clock, chain.clone(), cfg, ip.parse().unwrap()).await;
+        assert_eq!(
+            ClosingReason::RejectedByPeerManager(RegisterPeerError::SubnetLimitExceeded),
+            conn.manager_fail_handshake(&clock).await
+        );
+    }
+
+    tracing::info!(target:"test", "peers from other subnets are accepted");
+    for ip in ["8.8.9.1", "2001:db8:0:1::1"] {
+        let cfg = chain.make_config(rng);
+        let conn = pm.start_inbound_from(&clock, chain.clone(), cfg, ip.parse().unwrap()).await;
+        peers.push(conn.handshake(&clock).await);
+    }
+
+    tracing::info!(target:"test", "peers from local networks are not subject to the limit");
+    for ip in ["10.0.0.1", "10.0.0.2", "fd00::1", "fd00::2", "fe80::1", "fe80::2"] {
+        let cfg = chain.make_config(rng);
+        let conn = pm.start_inbound_from(&clock, chain.clone(), cfg, ip.parse().unwrap()).await;
+        peers.push(conn.handshake(&clock).await);
+    }
+}
+
+// Once there are more than ideal_connections_hi peers, the inbound peer outside of the safe set
+// with the lowest reputation is evicted, even if an outbound peer has a lower one.
+#[tokio::test]
+async fn eviction_order() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut pm_cfg = chain.make_config(rng);
+    pm_cfg.max_num_peers = 4;
+    pm_cfg.max_num_inbound_peers = 4;
+    pm_cfg.max_num_outbound_peers = 4;
+    pm_cfg.ideal_connections_lo = 3;
+    pm_cfg.ideal_connections_hi = 3;
+    pm_cfg.minimum_outbound_peers = 0;
+    pm_cfg.safe_set_size = 1;
+    let pm = peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        pm_cfg,
+        chain.clone(),
+    )
+    .await;
+    let report = |peer_id: PeerId, offenses: Vec<PeerOffense>| {
+        let clock = clock.clock();
+        pm.with_state(move |s| async move {
+            for offense in offenses {
+                s.report_offense(&clock, &peer_id, offense);
+            }
+        })
+    };
+
+    tracing::info!(target:"test", "connect the earliest peer, which makes up the safe set");
+    let conn = pm.start_inbound(chain.clone(), chain.make_config(rng)).await;
+    let _safe = conn.handshake(&clock.clock()).await;
+
+    tracing::info!(target:"test", "connect an outbound peer with the lowest reputation");
+    let conn = pm.start_outbound(chain.clone(), chain.make_config(rng), tcp::Tier::T2).await;
+    let outbound = conn.handshake(&clock.clock()).await;
+    report(outbound.cfg.id(), vec![PeerOffense::InvalidBlock, PeerOffense::Timeout]).await;
+
+    tracing::info!(target:"test", "connect an inbound peer with a lowered reputation");
+    let conn = pm.start_inbound(chain.clone(), chain.make_config(rng)).await;
+    let victim_stream_id = conn.stream.id();
+    let victim = conn.handshake(&clock.clock()).await;
+    report(victim.cfg.id(), vec![PeerOffense::InvalidTransaction]).await;
+
+    tracing::info!(target:"test", "connect an inbound peer over ideal_connections_hi");
+    let mut events = pm.events.from_now();
+    let conn = pm.start_inbound(chain.clone(), chain.make_config(rng)).await;
+    let _last = conn.handshake(&clock.clock()).await;
+    let stream_id = events
+        .recv_until(|ev| match ev {
+            Event::PeerManager(PME::ConnectionClosed(ev)) => {
+                assert_eq!(ClosingReason::PeerManagerRequest, ev.reason);
+                Some(ev.stream_id)
+            }
+            _ => None,
+        })
+        .await;
+    assert_eq!(victim_stream_id, stream_id);
+}
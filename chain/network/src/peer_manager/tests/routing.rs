@@ -775,6 +775,71 @@ async fn blacklist_all() {
     pm1.wait_for_routing_table(&[]).await;
 }
 
+// test node 0 blacklisting the peer id of node 1
+#[tokio::test]
+async fn blacklist_peer_id() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    tracing::info!(target:"test", "start two nodes with 0 blacklisting the peer id of 1");
+    let mut cfgs = make_configs(&chain, rng, 2, 2, true);
+    cfgs[0].peer_store.blacklist =
+        [blacklist::Entry::PeerId(cfgs[1].node_id())].into_iter().collect();
+
+    let pm0 = start_pm(clock.clock(), TestDB::new(), cfgs[0].clone(), chain.clone()).await;
+    let pm1 = start_pm(clock.clock(), TestDB::new(), cfgs[1].clone(), chain.clone()).await;
+
+    let id0 = pm0.cfg.node_id();
+    let id1 = pm1.cfg.node_id();
+
+    tracing::info!(target:"test", "wait for the connection to be attempted and rejected");
+    wait_for_connection_closed(
+        &mut pm0.events.clone(),
+        ClosingReason::RejectedByPeerManager(RegisterPeerError::Blacklisted),
+    )
+    .await;
+
+    tracing::info!(target:"test", "wait for {id0} routing table");
+    pm0.wait_for_routing_table(&[]).await;
+    tracing::info!(target:"test", "wait for {id1} routing table");
+    pm1.wait_for_routing_table(&[]).await;
+}
+
+// test node 0 accepting connections only from the whitelisted nodes, with node 1 not whitelisted
+#[tokio::test]
+async fn not_whitelisted() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    tracing::info!(target:"test", "start two nodes with 0 accepting only whitelisted nodes");
+    let mut cfgs = make_configs(&chain, rng, 2, 2, true);
+    cfgs[0].whitelist_only = true;
+
+    let pm0 = start_pm(clock.clock(), TestDB::new(), cfgs[0].clone(), chain.clone()).await;
+    let pm1 = start_pm(clock.clock(), TestDB::new(), cfgs[1].clone(), chain.clone()).await;
+
+    let id0 = pm0.cfg.node_id();
+    let id1 = pm1.cfg.node_id();
+
+    tracing::info!(target:"test", "wait for the connection to be attempted and rejected");
+    wait_for_connection_closed(
+        &mut pm0.events.clone(),
+        ClosingReason::RejectedByPeerManager(RegisterPeerError::NotWhitelisted),
+    )
+    .await;
+
+    tracing::info!(target:"test", "wait for {id0} routing table");
+    pm0.wait_for_routing_table(&[]).await;
+    tracing::info!(target:"test", "wait for {id1} routing table");
+    pm1.wait_for_routing_table(&[]).await;
+}
+
 // Spawn 3 nodes with max peers configured to 2, then allow them to connect to each other in a triangle.
 // Spawn a fourth node and see it fail to connect since the first three are at max capacity.
 #[tokio::test]
@@ -789,6 +854,8 @@ async fn max_num_peers_limit() {
     let mut cfgs = make_configs(&chain, rng, 4, 4, false);
     for config in cfgs.iter_mut() {
         config.max_num_peers = 2;
+        config.max_num_inbound_peers = 2;
+        config.max_num_outbound_peers = 2;
         config.ideal_connections_lo = 2;
         config.ideal_connections_hi = 2;
     }
@@ -1148,7 +1215,7 @@ async fn do_not_block_announce_account_broadcast() {
     let pm1 = start_pm(clock.clock(), db1.clone(), chain.make_config(rng), chain.clone()).await;
     pm1.connect_to(&pm0.peer_info(), tcp::Tier::T2).await;
     pm1.announce_account(aa.clone()).await;
-    assert_eq!(&aa.peer_id, &pm0.wait_for_account_owner(&aa.account_id).await);
+    assert_eq!(&aa.peer_id, &pm0.wait_for_account_owner(&clock.clock(), &aa.account_id).await);
     drop(pm0);
     drop(pm1);
 
@@ -1161,7 +1228,7 @@ async fn do_not_block_announce_account_broadcast() {
     pm1.connect_to(&pm0.peer_info(), tcp::Tier::T2).await;
     pm2.connect_to(&pm0.peer_info(), tcp::Tier::T2).await;
     pm1.announce_account(aa.clone()).await;
-    assert_eq!(&aa.peer_id, &pm2.wait_for_account_owner(&aa.account_id).await);
+    assert_eq!(&aa.peer_id, &pm2.wait_for_account_owner(&clock.clock(), &aa.account_id).await);
 }
 
 /// Check that two archival nodes keep connected after network rebalance. Nodes 0 and 1 are archival nodes, others aren't.
@@ -1180,6 +1247,8 @@ async fn archival_node() {
     let mut cfgs = make_configs(&chain, rng, 5, 5, false);
     for config in cfgs.iter_mut() {
         config.max_num_peers = 3;
+        config.max_num_inbound_peers = 3;
+        config.max_num_outbound_peers = 3;
         config.ideal_connections_lo = 2;
         config.ideal_connections_hi = 2;
         config.safe_set_size = 1;
@@ -1,5 +1,8 @@
 use crate::broadcast;
+use crate::client::ClientSenderForNetworkInput;
 use crate::network_protocol::testonly as data;
+use crate::network_protocol::{BlockAnnouncement, PeerMessage};
+use crate::peer;
 use crate::peer_manager::connection_store::STORED_CONNECTIONS_MIN_DURATION;
 use crate::peer_manager::network_state::RECONNECT_ATTEMPT_INTERVAL;
 use crate::peer_manager::peer_manager_actor::Event as PME;
@@ -11,8 +14,11 @@ use crate::tcp;
 use crate::testonly::make_rng;
 use crate::testonly::AsSet;
 use near_async::time;
+use near_crypto::{KeyType, Signature};
 use near_o11y::testonly::init_test_logger;
+use near_primitives::hash::CryptoHash;
 use near_primitives::network::PeerId;
+use near_primitives::transaction::SignedTransaction;
 use near_store::db::TestDB;
 use std::sync::Arc;
 
@@ -39,6 +45,23 @@ async fn wait_for_connection_closed(events: &mut broadcast::Receiver<Event>) {
         .await
 }
 
+/// Waits until the peer is asked for the block with the given hash.
+async fn wait_for_block_request(
+    events: &mut broadcast::Receiver<peer::testonly::Event>,
+    hash: CryptoHash,
+) {
+    events
+        .recv_until(|ev| match ev {
+            peer::testonly::Event::Client(ClientSenderForNetworkInput::_block_request(req))
+                if req.0 == hash =>
+            {
+                Some(())
+            }
+            _ => None,
+        })
+        .await
+}
+
 #[tokio::test]
 async fn test_store_outbound_connection() {
     init_test_logger();
@@ -295,3 +318,104 @@ async fn test_reconnect_after_restart_outbound_side_multi() {
     pm0.wait_for_direct_connection(id3.clone()).await;
     pm0.wait_for_direct_connection(id4.clone()).await;
 }
+
+#[tokio::test]
+async fn duplicate_transactions_are_dropped() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let pm = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let peer1 = pm
+        .start_inbound(chain.clone(), chain.make_config(rng))
+        .await
+        .handshake(&clock.clock())
+        .await;
+    let peer2 = pm
+        .start_inbound(chain.clone(), chain.make_config(rng))
+        .await
+        .handshake(&clock.clock())
+        .await;
+
+    tracing::info!(target:"test", "send a copy of a transaction with a forged signature, then the transaction from both peers, then a new one");
+    let mut events = pm.events.from_now();
+    let mut client_events = events.clone();
+    let tx = data::make_signed_transaction(rng);
+    let forged_tx =
+        SignedTransaction::new(Signature::empty(KeyType::ED25519), tx.transaction.clone());
+    let new_tx = data::make_signed_transaction(rng);
+    for (peer, tx) in [(&peer1, &forged_tx), (&peer2, &tx), (&peer1, &tx)] {
+        peer.send(PeerMessage::Transaction(tx.clone())).await;
+        events
+            .recv_until(|ev| match ev {
+                Event::PeerManager(PME::MessageProcessed(_, PeerMessage::Transaction(_))) => {
+                    Some(())
+                }
+                _ => None,
+            })
+            .await;
+    }
+    peer1.send(PeerMessage::Transaction(new_tx.clone())).await;
+
+    tracing::info!(target:"test", "check that the client got the forged copy and the transaction once");
+    let mut got = vec![];
+    while got.last() != Some(&new_tx) {
+        got.push(
+            client_events
+                .recv_until(|ev| match ev {
+                    Event::Client(ClientSenderForNetworkInput::_transaction(req)) => {
+                        Some(req.transaction)
+                    }
+                    _ => None,
+                })
+                .await,
+        );
+    }
+    assert_eq!(got, vec![forged_tx, tx, new_tx]);
+}
+
+#[tokio::test]
+async fn announced_block_is_fetched_from_other_announcers() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut pm_cfg = chain.make_config(rng);
+    pm_cfg.block_announcements = true;
+    let block_fetch_timeout = pm_cfg.block_fetch_timeout;
+    let pm = start_pm(clock.clock(), TestDB::new(), pm_cfg, chain.clone()).await;
+    let mut peers = vec![];
+    for _ in 0..2 {
+        let mut cfg = chain.make_config(rng);
+        cfg.block_announcements = true;
+        peers.push(pm.start_inbound(chain.clone(), cfg).await.handshake(&clock.clock()).await);
+    }
+    let announcement = BlockAnnouncement::new(chain.blocks.last().unwrap());
+    let hash = announcement.hash;
+    let mut events0 = peers[0].events.from_now();
+    let mut events1 = peers[1].events.from_now();
+
+    tracing::info!(target:"test", "the block is requested from the first peer which announces it");
+    peers[0].send(PeerMessage::BlockAnnouncement(announcement.clone())).await;
+    wait_for_block_request(&mut events0, hash).await;
+
+    tracing::info!(target:"test", "the second peer which announces it is kept as a fallback");
+    let mut events = pm.events.from_now();
+    peers[1].send(PeerMessage::BlockAnnouncement(announcement.clone())).await;
+    events
+        .recv_until(|ev| match ev {
+            Event::PeerManager(PME::MessageProcessed(_, PeerMessage::BlockAnnouncement(_))) => {
+                Some(())
+            }
+            _ => None,
+        })
+        .await;
+
+    tracing::info!(target:"test", "the first peer doesn't respond, so the block is requested from the second one");
+    clock.advance(block_fetch_timeout);
+    wait_for_block_request(&mut events1, hash).await;
+}
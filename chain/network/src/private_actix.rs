@@ -8,9 +8,11 @@ use std::sync::Arc;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum RegisterPeerError {
     Blacklisted,
+    NotWhitelisted,
     Banned,
     PoolError(connection::PoolError),
     ConnectionLimitExceeded,
+    SubnetLimitExceeded,
     NotTier1Peer,
     Tier1InboundDisabled,
     InvalidEdge,
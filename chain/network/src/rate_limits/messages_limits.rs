@@ -4,17 +4,22 @@
 use std::collections::HashMap;
 
 use enum_map::{enum_map, EnumMap};
-use near_async::time::Instant;
+use near_async::time::{Duration, Instant};
 
 use crate::network_protocol::{PeerMessage, RoutedMessageBody};
 
 use super::token_bucket::{TokenBucket, TokenBucketError};
 
+/// Minimal interval between two reports of a peer exceeding its rate limits.
+const EXCEEDED_REPORT_INTERVAL: Duration = Duration::seconds(10);
+
 /// Object responsible to manage the rate limits of all network messages
 /// for a single connection/peer.
 #[derive(Default)]
 pub struct RateLimits {
     buckets: EnumMap<RateLimitedPeerMessageKey, Option<TokenBucket>>,
+    /// Time at which the peer was last reported for exceeding the limits.
+    last_exceeded_report: Option<Instant>,
 }
 
 impl RateLimits {
@@ -37,7 +42,7 @@ impl RateLimits {
                 }
             }
         }
-        Self { buckets }
+        Self { buckets, last_exceeded_report: None }
     }
 
     /// Checks if the given message is under the rate limits.
@@ -57,6 +62,19 @@ impl RateLimits {
         }
         true
     }
+
+    /// Checks if the peer should be reported for exceeding its rate limits.
+    /// A peer is reported at most once per `EXCEEDED_REPORT_INTERVAL`, no
+    /// matter how many of its messages have been rate limited meanwhile.
+    pub fn should_report_exceeded(&mut self, now: Instant) -> bool {
+        if let Some(last) = self.last_exceeded_report {
+            if now < last + EXCEEDED_REPORT_INTERVAL {
+                return false;
+            }
+        }
+        self.last_exceeded_report = Some(now);
+        true
+    }
 }
 
 /// Rate limit configuration for a single network message.
@@ -152,6 +170,7 @@ pub enum RateLimitedPeerMessageKey {
     BlockHeaders,
     BlockRequest,
     Block,
+    BlockAnnouncement,
     Transaction,
     SyncSnapshotHosts,
     StateRequestHeader,
@@ -191,6 +210,7 @@ fn get_key_and_token_cost(message: &PeerMessage) -> Option<(RateLimitedPeerMessa
         PeerMessage::BlockHeaders(_) => Some((BlockHeaders, 1)),
         PeerMessage::BlockRequest(_) => Some((BlockRequest, 1)),
         PeerMessage::Block(_) => Some((Block, 1)),
+        PeerMessage::BlockAnnouncement(_) => Some((BlockAnnouncement, 1)),
         PeerMessage::Transaction(_) => Some((Transaction, 1)),
         PeerMessage::Routed(msg) => match msg.body {
             RoutedMessageBody::BlockApproval(_) => Some((BlockApproval, 1)),
@@ -253,14 +273,16 @@ mod tests {
     use near_async::time::Duration;
     use near_primitives::hash::CryptoHash;
 
-    use crate::network_protocol::{Disconnect, PeerMessage};
+    use crate::network_protocol::{Disconnect, DisconnectReason, PeerMessage};
 
     use super::*;
 
     #[test]
     fn is_allowed() {
-        let disconnect =
-            PeerMessage::Disconnect(Disconnect { remove_from_connection_store: false });
+        let disconnect = PeerMessage::Disconnect(Disconnect {
+            remove_from_connection_store: false,
+            reason: DisconnectReason::Unknown,
+        });
         let block_request = PeerMessage::BlockRequest(CryptoHash::default());
         let now = Instant::now();
 
@@ -293,6 +315,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_report_exceeded() {
+        let now = Instant::now();
+        let mut limits = RateLimits::default();
+        assert!(limits.should_report_exceeded(now));
+        assert!(!limits.should_report_exceeded(now));
+        assert!(!limits.should_report_exceeded(now + Duration::seconds(9)));
+        assert!(limits.should_report_exceeded(now + EXCEEDED_REPORT_INTERVAL));
+        assert!(!limits.should_report_exceeded(now + EXCEEDED_REPORT_INTERVAL));
+    }
+
     #[test]
     fn configuration() {
         use RateLimitedPeerMessageKey::*;
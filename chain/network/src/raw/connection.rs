@@ -218,6 +218,9 @@ fn new_handshake(
         },
         partial_edge_info: PartialEdgeInfo::new(my_peer_id, target_peer_id, nonce, secret_key),
         owned_account: None,
+        supports_compression: false,
+        supports_block_announcements: false,
+        sender_advertised_addr: None,
     })
 }
 
@@ -1,11 +1,11 @@
 use crate::network_protocol::Encoding;
-use crate::network_protocol::{RoutedMessageBody, RoutedMessageV2};
+use crate::network_protocol::{PeerMessage, RoutedMessageBody, RoutedMessageV2};
 use crate::tcp;
 use crate::types::PeerType;
 use near_async::time;
 use near_o11y::metrics::prometheus;
 use near_o11y::metrics::{
-    exponential_buckets, try_create_histogram, try_create_histogram_vec,
+    exponential_buckets, try_create_gauge, try_create_histogram, try_create_histogram_vec,
     try_create_histogram_with_buckets, try_create_int_counter, try_create_int_counter_vec,
     try_create_int_gauge, try_create_int_gauge_vec, Histogram, HistogramVec, IntCounter,
     IntCounterVec, IntGauge, IntGaugeVec, MetricVec, MetricVecBuilder,
@@ -203,6 +203,30 @@ pub(crate) static PEER_MESSAGE_RATE_LIMITED_BY_TYPE_TOTAL: LazyLock<IntCounterVe
         )
         .unwrap()
     });
+pub(crate) static PEER_OFFENSES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "near_peer_offenses_total",
+        "Number of offenses lowering reputation of peers by offense type",
+        &["offense"],
+    )
+    .unwrap()
+});
+pub(crate) static PEER_HANDSHAKE_FAILURES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "near_peer_handshake_failures_total",
+        "Number of connections closed before completing the handshake by closing reason",
+        &["reason"],
+    )
+    .unwrap()
+});
+pub(crate) static PEER_STORE_PEERS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "near_peer_store_peers",
+        "Number of peers known to the peer store by status",
+        &["status"],
+    )
+    .unwrap()
+});
 pub(crate) static SYNC_ACCOUNTS_DATA: LazyLock<IntCounterVec> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "near_sync_accounts_data",
@@ -333,6 +357,14 @@ pub(crate) static BROADCAST_MESSAGES: LazyLock<IntCounterVec> = LazyLock::new(||
     try_create_int_counter_vec("near_broadcast_msg", "Broadcasted messages", &["type"]).unwrap()
 });
 
+pub(crate) static GOSSIP_FANOUT_RATIO: LazyLock<prometheus::Gauge> = LazyLock::new(|| {
+    try_create_gauge(
+        "near_gossip_fanout_ratio",
+        "Fraction of peers the non-critical gossip is forwarded to",
+    )
+    .unwrap()
+});
+
 static NETWORK_ROUTED_MSG_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
     try_create_histogram_vec(
         "near_network_routed_msg_latency",
@@ -455,6 +487,9 @@ pub(crate) enum MessageDropped {
     MaxCapacityExceeded,
     TransactionsPerBlockExceeded,
     Duplicate,
+    TtlExpired,
+    BandwidthLimitExceeded,
+    LoadShedding,
 }
 
 impl MessageDropped {
@@ -462,6 +497,10 @@ impl MessageDropped {
         self.inc_msg_type(msg.into())
     }
 
+    pub fn inc_peer_msg(self, msg: &PeerMessage) {
+        self.inc_msg_type(msg.msg_variant())
+    }
+
     pub fn inc_unknown_msg(self) {
         self.inc_msg_type("unknown")
     }
@@ -1,8 +1,9 @@
 /// Store module defines atomic DB operations on top of schema module.
 /// All transactions should be implemented within this module,
 /// in particular schema::StoreUpdate is not exported.
-use crate::types::ConnectionInfo;
-use near_primitives::network::AnnounceAccount;
+use crate::types::{ConnectionInfo, KnownPeerState, ReasonForBan};
+use near_async::time;
+use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::types::AccountId;
 use std::sync::Arc;
 
@@ -78,6 +79,70 @@ impl Store {
     }
 }
 
+// PeerStore storage.
+impl Store {
+    /// Writes the given known peers and deletes the removed ones, one row per peer.
+    #[tracing::instrument(
+        target = "network::store",
+        level = "trace",
+        "Store::update_known_peers",
+        skip_all
+    )]
+    pub fn update_known_peers(
+        &mut self,
+        known_peers: &[KnownPeerState],
+        removed: &[PeerId],
+    ) -> Result<(), Error> {
+        let mut update = self.0.new_update();
+        for known_peer in known_peers {
+            update.set::<schema::KnownPeers>(&known_peer.peer_info.id, known_peer);
+        }
+        for peer_id in removed {
+            update.delete::<schema::KnownPeers>(peer_id);
+        }
+        self.0.commit(update).map_err(Error)
+    }
+
+    pub fn get_known_peers(&self) -> Result<Vec<KnownPeerState>, Error> {
+        self.0.iter::<schema::KnownPeers>().map(|item| Ok(item.map_err(Error)?.1)).collect()
+    }
+
+    #[tracing::instrument(
+        target = "network::store",
+        level = "trace",
+        "Store::set_peer_ban",
+        skip_all,
+        fields(%peer_id)
+    )]
+    pub fn set_peer_ban(
+        &mut self,
+        peer_id: &PeerId,
+        reason: ReasonForBan,
+        time_banned: time::Utc,
+    ) -> Result<(), Error> {
+        let mut update = self.0.new_update();
+        update.set::<schema::BannedPeers>(peer_id, &(reason, time_banned));
+        self.0.commit(update).map_err(Error)
+    }
+
+    pub fn delete_peer_ban(&mut self, peer_id: &PeerId) -> Result<(), Error> {
+        let mut update = self.0.new_update();
+        update.delete::<schema::BannedPeers>(peer_id);
+        self.0.commit(update).map_err(Error)
+    }
+
+    /// Returns all the persisted bans, including the expired ones.
+    pub fn get_peer_bans(&self) -> Result<Vec<(PeerId, ReasonForBan, time::Utc)>, Error> {
+        self.0
+            .iter::<schema::BannedPeers>()
+            .map(|item| {
+                let (peer_id, (reason, time_banned)) = item.map_err(Error)?;
+                Ok((peer_id, reason, time_banned))
+            })
+            .collect()
+    }
+}
+
 impl From<Arc<dyn near_store::db::Database>> for Store {
     fn from(store: Arc<dyn near_store::db::Database>) -> Self {
         Self(schema::Store::from(store))
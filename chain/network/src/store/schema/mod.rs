@@ -58,6 +58,78 @@ impl BorshRepr for ConnectionInfoRepr {
     }
 }
 
+/// A Borsh representation of the primitives::KnownPeerState.
+#[derive(BorshSerialize, BorshDeserialize, ProtocolSchema)]
+pub(super) struct KnownPeerStateRepr {
+    peer_info: primitives::PeerInfo,
+    /// Whether the peer is known to exist, i.e. its status isn't Unknown.
+    /// Connections and bans aren't restored from this column.
+    verified: bool,
+    /// UNIX timestamps in nanos.
+    first_seen: u64,
+    last_seen: u64,
+    /// UNIX timestamp in nanos and the error, if the attempt failed.
+    last_outbound_attempt: Option<(u64, Option<String>)>,
+    failed_attempts: u32,
+}
+
+impl BorshRepr for KnownPeerStateRepr {
+    type T = primitives::KnownPeerState;
+    fn to_repr(s: &primitives::KnownPeerState) -> Self {
+        Self {
+            peer_info: s.peer_info.clone(),
+            verified: s.status != primitives::KnownPeerStatus::Unknown,
+            first_seen: s.first_seen.unix_timestamp_nanos() as u64,
+            last_seen: s.last_seen.unix_timestamp_nanos() as u64,
+            last_outbound_attempt: s.last_outbound_attempt.as_ref().map(|(time, result)| {
+                (time.unix_timestamp_nanos() as u64, result.as_ref().err().cloned())
+            }),
+            failed_attempts: s.failed_attempts,
+        }
+    }
+
+    fn from_repr(s: Self) -> Result<primitives::KnownPeerState, Error> {
+        let utc = |nanos: u64| time::Utc::from_unix_timestamp_nanos(nanos as i128);
+        let last_outbound_attempt = match s.last_outbound_attempt {
+            Some((time, err)) => Some((utc(time).map_err(invalid_data)?, err.map_or(Ok(()), Err))),
+            None => None,
+        };
+        Ok(primitives::KnownPeerState {
+            peer_info: s.peer_info,
+            status: match s.verified {
+                true => primitives::KnownPeerStatus::NotConnected,
+                false => primitives::KnownPeerStatus::Unknown,
+            },
+            first_seen: utc(s.first_seen).map_err(invalid_data)?,
+            last_seen: utc(s.last_seen).map_err(invalid_data)?,
+            last_outbound_attempt,
+            failed_attempts: s.failed_attempts,
+            last_disconnect: None,
+        })
+    }
+}
+
+/// A Borsh representation of a ban of a peer.
+#[derive(BorshSerialize, BorshDeserialize, ProtocolSchema)]
+pub(super) struct PeerBanRepr {
+    reason: primitives::ReasonForBan,
+    /// UNIX timestamp in nanos.
+    time_banned: u64,
+}
+
+impl BorshRepr for PeerBanRepr {
+    type T = (primitives::ReasonForBan, time::Utc);
+    fn to_repr(s: &Self::T) -> Self {
+        Self { reason: s.0, time_banned: s.1.unix_timestamp_nanos() as u64 }
+    }
+
+    fn from_repr(s: Self) -> Result<Self::T, Error> {
+        let time_banned =
+            time::Utc::from_unix_timestamp_nanos(s.time_banned as i128).map_err(invalid_data)?;
+        Ok((s.reason, time_banned))
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, ProtocolSchema)]
 pub(super) struct EdgeRepr {
     key: (PeerId, PeerId),
@@ -102,6 +174,20 @@ impl Column for RecentOutboundConnections {
     type Value = Vec<ConnectionInfoRepr>;
 }
 
+pub(super) struct KnownPeers;
+impl Column for KnownPeers {
+    const COL: DBCol = DBCol::KnownPeers;
+    type Key = Borsh<PeerId>;
+    type Value = KnownPeerStateRepr;
+}
+
+pub(super) struct BannedPeers;
+impl Column for BannedPeers {
+    const COL: DBCol = DBCol::BannedPeers;
+    type Key = Borsh<PeerId>;
+    type Value = PeerBanRepr;
+}
+
 ////////////////////////////////////////////////////
 // Storage
 
@@ -228,6 +314,17 @@ impl Store {
             None => None,
         })
     }
+
+    pub fn iter<C: Column>(
+        &self,
+    ) -> impl Iterator<Item = Result<(<C::Key as Format>::T, <C::Value as Format>::T), Error>> + '_
+    {
+        debug_assert!(!C::COL.is_rc());
+        self.0.iter(C::COL).map(|item| {
+            let (k, v) = item?;
+            Ok((C::Key::decode(&k)?, C::Value::decode(&v)?))
+        })
+    }
 }
 
 impl From<Arc<dyn near_store::db::Database>> for Store {
@@ -240,7 +337,7 @@ impl StoreUpdate {
     pub fn set<C: Column>(&mut self, k: &<C::Key as Format>::T, v: &<C::Value as Format>::T) {
         self.0.set(C::COL, to_vec::<C::Key>(k), to_vec::<C::Value>(v))
     }
-    pub fn _delete<C: Column>(&mut self, k: &<C::Key as Format>::T) {
+    pub fn delete<C: Column>(&mut self, k: &<C::Key as Format>::T) {
         self.0.delete(C::COL, to_vec::<C::Key>(k))
     }
 }
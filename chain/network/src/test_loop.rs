@@ -15,14 +15,18 @@ use crate::types::{
     NetworkRequests, NetworkResponses, PeerManagerMessageRequest, PeerManagerMessageResponse,
     SetChainInfo, StateSyncEvent,
 };
+use futures::future::BoxFuture;
 use near_async::actix::ActixResult;
-use near_async::futures::{FutureSpawner, FutureSpawnerExt};
+use near_async::futures::FutureSpawnerExt;
 use near_async::messaging::{Actor, AsyncSender, CanSend, Handler, SendAsync, Sender};
-use near_async::time::Clock;
+use near_async::test_loop::pending_events_sender::PendingEventsSender;
+use near_async::time::{Clock, Duration};
 use near_async::{MultiSend, MultiSenderFrom};
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::PeerId;
 use near_primitives::types::AccountId;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Subset of ClientSenderForNetwork required for the TestLoop network.
 /// We skip over the message handlers from view client.
@@ -68,8 +72,10 @@ type NetworkRequestHandler = Box<dyn Fn(NetworkRequests) -> Option<NetworkReques
 ///
 /// Examples of custom handlers
 /// - Override handler to skip sending messages to or from a specific client.
-/// - Override handler to simulate more network delays.
 /// - Override handler to modify data and simulate malicious behavior.
+///
+/// Latency, message drops and partitions between the nodes are simulated by the default handlers
+/// according to the `NetworkConditions` shared by all the network actors.
 pub struct TestLoopPeerManagerActor {
     handlers: Vec<NetworkRequestHandler>,
 }
@@ -83,17 +89,18 @@ impl TestLoopPeerManagerActor {
         clock: Clock,
         account_id: &AccountId,
         shared_state: Arc<TestLoopNetworkSharedState>,
-        future_spawner: Arc<dyn FutureSpawner>,
+        pending_events_sender: PendingEventsSender,
     ) -> Self {
+        let transport = Transport {
+            my_peer_id: shared_state.account_to_peer_id[account_id].clone(),
+            shared_state,
+            pending_events_sender,
+        };
         let handlers = vec![
-            network_message_to_client_handler(&account_id, shared_state.clone()),
-            network_message_to_view_client_handler(
-                account_id.clone(),
-                shared_state.clone(),
-                future_spawner,
-            ),
-            network_message_to_partial_witness_handler(&account_id, shared_state.clone()),
-            network_message_to_shards_manager_handler(clock, &account_id, shared_state),
+            network_message_to_client_handler(&account_id, transport.clone()),
+            network_message_to_view_client_handler(transport.clone()),
+            network_message_to_partial_witness_handler(&account_id, transport.clone()),
+            network_message_to_shards_manager_handler(clock, &account_id, transport),
             network_message_to_state_snapshot_handler(),
         ];
         Self { handlers }
@@ -107,16 +114,103 @@ impl TestLoopPeerManagerActor {
     }
 }
 
+/// Simulated conditions of the network between the TestLoop nodes.
+///
+/// Every message sent between two nodes is delivered after the latency of the link between them
+/// (on top of the delay of the senders), unless it is dropped at random or the nodes are in
+/// different partitions. All the randomness comes from a seeded RNG, so that the tests are
+/// deterministic.
+pub struct NetworkConditions(Mutex<NetworkConditionsInner>);
+
+struct NetworkConditionsInner {
+    rng: StdRng,
+    latency: Duration,
+    link_latency: HashMap<(AccountId, AccountId), Duration>,
+    drop_probability: f64,
+    /// Partition index of the nodes. Nodes in different partitions cannot communicate.
+    partitions: HashMap<AccountId, usize>,
+}
+
+impl Default for NetworkConditions {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl NetworkConditions {
+    pub fn new(seed: u64) -> Self {
+        Self(Mutex::new(NetworkConditionsInner {
+            rng: StdRng::seed_from_u64(seed),
+            latency: Duration::ZERO,
+            link_latency: HashMap::new(),
+            drop_probability: 0.,
+            partitions: HashMap::new(),
+        }))
+    }
+
+    /// Sets the latency of all the links without a latency set explicitly.
+    pub fn set_latency(&self, latency: Duration) {
+        self.0.lock().unwrap().latency = latency;
+    }
+
+    /// Sets the latency of the messages sent from `from` to `to`.
+    pub fn set_link_latency(&self, from: &AccountId, to: &AccountId, latency: Duration) {
+        self.0.lock().unwrap().link_latency.insert((from.clone(), to.clone()), latency);
+    }
+
+    /// Sets the probability of dropping every message.
+    pub fn set_drop_probability(&self, drop_probability: f64) {
+        assert!((0. ..=1.).contains(&drop_probability));
+        self.0.lock().unwrap().drop_probability = drop_probability;
+    }
+
+    /// Splits the network into partitions. Messages between nodes from different partitions
+    /// are dropped. Nodes not present in any partition can communicate with all the nodes.
+    pub fn partition(&self, partitions: &[&[AccountId]]) {
+        let mut inner = self.0.lock().unwrap();
+        inner.partitions.clear();
+        for (i, partition) in partitions.iter().enumerate() {
+            for account_id in *partition {
+                inner.partitions.insert(account_id.clone(), i);
+            }
+        }
+    }
+
+    /// Removes all the partitions.
+    pub fn heal(&self) {
+        self.0.lock().unwrap().partitions.clear();
+    }
+
+    /// Returns the delay after which the message should be delivered,
+    /// or None if the message should be dropped.
+    fn delivery_delay(&self, from: &AccountId, to: &AccountId) -> Option<Duration> {
+        let mut inner = self.0.lock().unwrap();
+        if let (Some(a), Some(b)) = (inner.partitions.get(from), inner.partitions.get(to)) {
+            if a != b {
+                return None;
+            }
+        }
+        let drop_probability = inner.drop_probability;
+        if drop_probability > 0. && inner.rng.gen_bool(drop_probability) {
+            return None;
+        }
+        Some(*inner.link_latency.get(&(from.clone(), to.clone())).unwrap_or(&inner.latency))
+    }
+}
+
 /// Shared state across all the network actors. It handles the mapping between AccountId,
 /// PeerId, and the route back CryptoHash, so that individual network actors can do
 /// routing.
 pub struct TestLoopNetworkSharedState {
     account_to_peer_id: HashMap<AccountId, PeerId>,
+    peer_to_account_id: HashMap<PeerId, AccountId>,
     senders: HashMap<PeerId, OneClientSenders>,
     route_back: Mutex<HashMap<CryptoHash, PeerId>>,
+    conditions: Arc<NetworkConditions>,
 }
 
 /// Senders available for the networking layer, for one node in the test loop.
+#[derive(Clone)]
 struct OneClientSenders {
     client_sender: ClientSenderForTestLoopNetwork,
     view_client_sender: ViewClientSenderForTestLoopNetwork,
@@ -125,7 +219,7 @@ struct OneClientSenders {
 }
 
 impl TestLoopNetworkSharedState {
-    pub fn new<'a, D>(datas: &'a [D]) -> Self
+    pub fn new<'a, D>(datas: &'a [D], conditions: Arc<NetworkConditions>) -> Self
     where
         AccountId: From<&'a D>,
         PeerId: From<&'a D>,
@@ -135,6 +229,7 @@ impl TestLoopNetworkSharedState {
         Sender<ShardsManagerRequestFromNetwork>: From<&'a D>,
     {
         let mut account_to_peer_id = HashMap::new();
+        let mut peer_to_account_id = HashMap::new();
         let mut senders = HashMap::new();
         for data in datas {
             let account_id = AccountId::from(data);
@@ -144,6 +239,7 @@ impl TestLoopNetworkSharedState {
             let partial_witness_sender = PartialWitnessSenderForNetwork::from(data);
             let shards_manager_sender = Sender::<ShardsManagerRequestFromNetwork>::from(data);
             account_to_peer_id.insert(account_id.clone(), peer_id.clone());
+            peer_to_account_id.insert(peer_id.clone(), account_id.clone());
             senders.insert(
                 peer_id.clone(),
                 OneClientSenders {
@@ -155,15 +251,13 @@ impl TestLoopNetworkSharedState {
             );
         }
 
-        Self { account_to_peer_id, senders, route_back: Mutex::new(HashMap::new()) }
-    }
-
-    fn senders_for_account(&self, account_id: &AccountId) -> &OneClientSenders {
-        self.senders.get(&self.account_to_peer_id[account_id]).unwrap()
-    }
-
-    fn senders_for_peer(&self, peer_id: &PeerId) -> &OneClientSenders {
-        self.senders.get(peer_id).unwrap()
+        Self {
+            account_to_peer_id,
+            peer_to_account_id,
+            senders,
+            route_back: Mutex::new(HashMap::new()),
+            conditions,
+        }
     }
 
     fn generate_route_back(&self, peer_id: &PeerId) -> CryptoHash {
@@ -173,10 +267,8 @@ impl TestLoopNetworkSharedState {
         route_id
     }
 
-    fn senders_for_route_back(&self, route_back: &CryptoHash) -> &OneClientSenders {
-        let lookup = self.route_back.lock().unwrap();
-        let peer_id = lookup.get(route_back).unwrap();
-        self.senders_for_peer(peer_id)
+    fn peer_for_route_back(&self, route_back: &CryptoHash) -> PeerId {
+        self.route_back.lock().unwrap().get(route_back).unwrap().clone()
     }
 
     fn accounts(&self) -> impl Iterator<Item = &AccountId> {
@@ -184,6 +276,74 @@ impl TestLoopNetworkSharedState {
     }
 }
 
+/// Delivers messages sent by one node to the other nodes according to the `NetworkConditions`.
+#[derive(Clone)]
+struct Transport {
+    my_peer_id: PeerId,
+    shared_state: Arc<TestLoopNetworkSharedState>,
+    pending_events_sender: PendingEventsSender,
+}
+
+impl Transport {
+    fn my_account_id(&self) -> &AccountId {
+        &self.shared_state.peer_to_account_id[&self.my_peer_id]
+    }
+
+    fn send_to_account(
+        &self,
+        account_id: &AccountId,
+        deliver: impl FnOnce(&OneClientSenders) + Send + 'static,
+    ) {
+        let peer_id = self.shared_state.account_to_peer_id[account_id].clone();
+        self.send_to_peer(&peer_id, deliver);
+    }
+
+    fn send_to_peer(
+        &self,
+        peer_id: &PeerId,
+        deliver: impl FnOnce(&OneClientSenders) + Send + 'static,
+    ) {
+        let shared_state = &self.shared_state;
+        let Some(delay) = shared_state
+            .conditions
+            .delivery_delay(self.my_account_id(), &shared_state.peer_to_account_id[peer_id])
+        else {
+            return;
+        };
+        let senders = shared_state.senders[peer_id].clone();
+        if delay == Duration::ZERO {
+            deliver(&senders);
+        } else {
+            self.pending_events_sender.send_with_delay(
+                format!("NetworkDelivery({})", peer_id),
+                Box::new(move |_| deliver(&senders)),
+                delay,
+            );
+        }
+    }
+
+    /// Sends a message to the peer and, once it handles the message, the response back to us.
+    fn request<R: Send + 'static>(
+        &self,
+        peer_id: &PeerId,
+        description: &'static str,
+        request: impl FnOnce(&OneClientSenders) -> BoxFuture<'static, R> + Send + 'static,
+        respond: impl FnOnce(&OneClientSenders, R) + Send + 'static,
+    ) {
+        let response_transport = Transport { my_peer_id: peer_id.clone(), ..self.clone() };
+        let my_peer_id = self.my_peer_id.clone();
+        let future_spawner = self.pending_events_sender.clone();
+        self.send_to_peer(peer_id, move |senders| {
+            let future = request(senders);
+            future_spawner.spawn(description, async move {
+                let response = future.await;
+                response_transport
+                    .send_to_peer(&my_peer_id, move |senders| respond(senders, response));
+            });
+        });
+    }
+}
+
 impl Handler<SetChainInfo> for TestLoopPeerManagerActor {
     fn handle(&mut self, _msg: SetChainInfo) {}
 }
@@ -215,23 +375,23 @@ impl Handler<PeerManagerMessageRequest> for TestLoopPeerManagerActor {
 
 fn network_message_to_client_handler(
     my_account_id: &AccountId,
-    shared_state: Arc<TestLoopNetworkSharedState>,
+    transport: Transport,
 ) -> NetworkRequestHandler {
     let my_account_id = my_account_id.clone();
     Box::new(move |request| match request {
         NetworkRequests::Block { block } => {
-            let my_peer_id = shared_state.account_to_peer_id.get(&my_account_id).unwrap();
-            for account_id in shared_state.accounts() {
+            let my_peer_id = transport.my_peer_id.clone();
+            for account_id in transport.shared_state.accounts() {
                 if account_id != &my_account_id {
-                    let future = shared_state
-                        .senders_for_account(account_id)
-                        .client_sender
-                        .send_async(BlockResponse {
-                            block: block.clone(),
-                            peer_id: my_peer_id.clone(),
+                    let block = block.clone();
+                    let peer_id = my_peer_id.clone();
+                    transport.send_to_account(account_id, move |senders| {
+                        drop(senders.client_sender.send_async(BlockResponse {
+                            block,
+                            peer_id,
                             was_requested: false,
-                        });
-                    drop(future);
+                        }));
+                    });
                 }
             }
             None
@@ -241,43 +401,47 @@ fn network_message_to_client_handler(
                 approval_message.target, my_account_id,
                 "Sending message to self not supported."
             );
-            let future = shared_state
-                .senders_for_account(&approval_message.target)
-                .client_sender
-                .send_async(BlockApproval(approval_message.approval, PeerId::random()));
-            drop(future);
+            transport.send_to_account(&approval_message.target, move |senders| {
+                drop(
+                    senders
+                        .client_sender
+                        .send_async(BlockApproval(approval_message.approval, PeerId::random())),
+                );
+            });
             None
         }
         NetworkRequests::ForwardTx(account, transaction) => {
             assert_ne!(account, my_account_id, "Sending message to self not supported.");
-            let future = shared_state.senders_for_account(&account).client_sender.send_async(
-                ProcessTxRequest { transaction, is_forwarded: true, check_only: false },
-            );
-            drop(future);
+            transport.send_to_account(&account, move |senders| {
+                drop(senders.client_sender.send_async(ProcessTxRequest {
+                    transaction,
+                    is_forwarded: true,
+                    check_only: false,
+                }));
+            });
             None
         }
         NetworkRequests::ChunkEndorsement(target, endorsement) => {
-            let future = shared_state
-                .senders_for_account(&target)
-                .client_sender
-                .send_async(ChunkEndorsementMessage(endorsement));
-            drop(future);
+            transport.send_to_account(&target, move |senders| {
+                drop(senders.client_sender.send_async(ChunkEndorsementMessage(endorsement)));
+            });
             None
         }
         NetworkRequests::EpochSyncRequest { peer_id } => {
-            let my_peer_id = shared_state.account_to_peer_id.get(&my_account_id).unwrap();
+            let my_peer_id = &transport.my_peer_id;
             assert_ne!(&peer_id, my_peer_id, "Sending message to self not supported.");
-            shared_state.senders_for_peer(&peer_id).client_sender.send(EpochSyncRequestMessage {
-                route_back: shared_state.generate_route_back(my_peer_id),
+            let route_back = transport.shared_state.generate_route_back(my_peer_id);
+            transport.send_to_peer(&peer_id, move |senders| {
+                senders.client_sender.send(EpochSyncRequestMessage { route_back });
             });
             None
         }
         NetworkRequests::EpochSyncResponse { route_back, proof } => {
-            let my_peer_id = shared_state.account_to_peer_id.get(&my_account_id).unwrap();
-            shared_state
-                .senders_for_route_back(&route_back)
-                .client_sender
-                .send(EpochSyncResponseMessage { from_peer: my_peer_id.clone(), proof });
+            let from_peer = transport.my_peer_id.clone();
+            let peer_id = transport.shared_state.peer_for_route_back(&route_back);
+            transport.send_to_peer(&peer_id, move |senders| {
+                senders.client_sender.send(EpochSyncResponseMessage { from_peer, proof });
+            });
             None
         }
         NetworkRequests::StateRequestPart { .. } => None,
@@ -286,40 +450,38 @@ fn network_message_to_client_handler(
     })
 }
 
-fn network_message_to_view_client_handler(
-    my_account_id: AccountId,
-    shared_state: Arc<TestLoopNetworkSharedState>,
-    future_spawner: Arc<dyn FutureSpawner>,
-) -> NetworkRequestHandler {
+fn network_message_to_view_client_handler(transport: Transport) -> NetworkRequestHandler {
     Box::new(move |request| match request {
         NetworkRequests::BlockHeadersRequest { hashes, peer_id } => {
-            let responder = shared_state.senders_for_account(&my_account_id).client_sender.clone();
-            let future = shared_state
-                .senders_for_peer(&peer_id)
-                .view_client_sender
-                .send_async(BlockHeadersRequest(hashes));
-            future_spawner.spawn("wait for ViewClient to handle BlockHeadersRequest", async move {
-                let response = future.await.unwrap().unwrap();
-                let future = responder.send_async(BlockHeadersResponse(response, peer_id));
-                drop(future);
-            });
+            let from_peer = peer_id.clone();
+            transport.request(
+                &peer_id,
+                "wait for ViewClient to handle BlockHeadersRequest",
+                move |senders| senders.view_client_sender.send_async(BlockHeadersRequest(hashes)),
+                move |senders, response| {
+                    let response = response.unwrap().unwrap();
+                    drop(
+                        senders.client_sender.send_async(BlockHeadersResponse(response, from_peer)),
+                    );
+                },
+            );
             None
         }
         NetworkRequests::BlockRequest { hash, peer_id } => {
-            let responder = shared_state.senders_for_account(&my_account_id).client_sender.clone();
-            let future = shared_state
-                .senders_for_peer(&peer_id)
-                .view_client_sender
-                .send_async(BlockRequest(hash));
-            future_spawner.spawn("wait for ViewClient to handle BlockRequest", async move {
-                let response = *future.await.unwrap().unwrap();
-                let future = responder.send_async(BlockResponse {
-                    block: response,
-                    peer_id,
-                    was_requested: true,
-                });
-                drop(future);
-            });
+            let from_peer = peer_id.clone();
+            transport.request(
+                &peer_id,
+                "wait for ViewClient to handle BlockRequest",
+                move |senders| senders.view_client_sender.send_async(BlockRequest(hash)),
+                move |senders, response| {
+                    let response = *response.unwrap().unwrap();
+                    drop(senders.client_sender.send_async(BlockResponse {
+                        block: response,
+                        peer_id: from_peer,
+                        was_requested: true,
+                    }));
+                },
+            );
             None
         }
         _ => Some(request),
@@ -328,34 +490,36 @@ fn network_message_to_view_client_handler(
 
 fn network_message_to_partial_witness_handler(
     my_account_id: &AccountId,
-    shared_state: Arc<TestLoopNetworkSharedState>,
+    transport: Transport,
 ) -> NetworkRequestHandler {
     let my_account_id = my_account_id.clone();
     Box::new(move |request| match request {
         NetworkRequests::ChunkStateWitnessAck(target, witness_ack) => {
             assert_ne!(target, my_account_id, "Sending message to self not supported.");
-            shared_state
-                .senders_for_account(&target)
-                .partial_witness_sender
-                .send(ChunkStateWitnessAckMessage(witness_ack));
+            transport.send_to_account(&target, move |senders| {
+                senders.partial_witness_sender.send(ChunkStateWitnessAckMessage(witness_ack));
+            });
             None
         }
 
         NetworkRequests::PartialEncodedStateWitness(validator_witness_tuple) => {
             for (target, partial_witness) in validator_witness_tuple.into_iter() {
-                shared_state
-                    .senders_for_account(&target)
-                    .partial_witness_sender
-                    .send(PartialEncodedStateWitnessMessage(partial_witness));
+                transport.send_to_account(&target, move |senders| {
+                    senders
+                        .partial_witness_sender
+                        .send(PartialEncodedStateWitnessMessage(partial_witness));
+                });
             }
             None
         }
         NetworkRequests::PartialEncodedStateWitnessForward(chunk_validators, partial_witness) => {
             for target in chunk_validators {
-                shared_state
-                    .senders_for_account(&target)
-                    .partial_witness_sender
-                    .send(PartialEncodedStateWitnessForwardMessage(partial_witness.clone()));
+                let partial_witness = partial_witness.clone();
+                transport.send_to_account(&target, move |senders| {
+                    senders
+                        .partial_witness_sender
+                        .send(PartialEncodedStateWitnessForwardMessage(partial_witness));
+                });
             }
             None
         }
@@ -373,48 +537,56 @@ fn network_message_to_state_snapshot_handler() -> NetworkRequestHandler {
 fn network_message_to_shards_manager_handler(
     clock: Clock,
     my_account_id: &AccountId,
-    shared_state: Arc<TestLoopNetworkSharedState>,
+    transport: Transport,
 ) -> NetworkRequestHandler {
     let my_account_id = my_account_id.clone();
     Box::new(move |request| match request {
         NetworkRequests::PartialEncodedChunkRequest { target, request, .. } => {
-            let my_peer_id = shared_state.account_to_peer_id.get(&my_account_id).unwrap();
-            let route_back = shared_state.generate_route_back(my_peer_id);
+            let route_back = transport.shared_state.generate_route_back(&transport.my_peer_id);
             let target = target.account_id.unwrap();
             assert!(target != my_account_id, "Sending message to self not supported.");
-            shared_state.senders_for_account(&target).shards_manager_sender.send(
-                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
-                    partial_encoded_chunk_request: request,
-                    route_back,
-                },
-            );
+            transport.send_to_account(&target, move |senders| {
+                senders.shards_manager_sender.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
+                        partial_encoded_chunk_request: request,
+                        route_back,
+                    },
+                );
+            });
             None
         }
         NetworkRequests::PartialEncodedChunkResponse { route_back, response } => {
             // Use route_back information to send the response back to the correct client.
-            shared_state.senders_for_route_back(&route_back).shards_manager_sender.send(
-                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
-                    partial_encoded_chunk_response: response,
-                    received_time: clock.now(),
-                },
-            );
+            let peer_id = transport.shared_state.peer_for_route_back(&route_back);
+            let clock = clock.clone();
+            transport.send_to_peer(&peer_id, move |senders| {
+                senders.shards_manager_sender.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
+                        partial_encoded_chunk_response: response,
+                        received_time: clock.now(),
+                    },
+                );
+            });
             None
         }
         NetworkRequests::PartialEncodedChunkMessage { account_id, partial_encoded_chunk } => {
             assert!(account_id != my_account_id, "Sending message to self not supported.");
-            shared_state.senders_for_account(&account_id).shards_manager_sender.send(
-                ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                    partial_encoded_chunk.into(),
-                ),
-            );
+            transport.send_to_account(&account_id, move |senders| {
+                senders.shards_manager_sender.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
+                        partial_encoded_chunk.into(),
+                    ),
+                );
+            });
             None
         }
         NetworkRequests::PartialEncodedChunkForward { account_id, forward } => {
             assert!(account_id != my_account_id, "Sending message to self not supported.");
-            shared_state
-                .senders_for_account(&account_id)
-                .shards_manager_sender
-                .send(ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(forward));
+            transport.send_to_account(&account_id, move |senders| {
+                senders.shards_manager_sender.send(
+                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(forward),
+                );
+            });
             None
         }
         _ => Some(request),
@@ -1,7 +1,7 @@
 /// Type that belong to the network protocol.
 pub use crate::network_protocol::{
-    Disconnect, Encoding, Handshake, HandshakeFailureReason, PeerMessage, RoutingTableUpdate,
-    SignedAccountData,
+    Disconnect, DisconnectReason, Encoding, Handshake, HandshakeFailureReason, PeerMessage,
+    RoutingTableUpdate, SignedAccountData,
 };
 /// Exported types, which are part of network protocol.
 pub use crate::network_protocol::{
@@ -83,6 +83,25 @@ pub enum ReasonForBan {
     BadChunkStateWitness = 16,
 }
 
+/// Misbehaviour of a peer which isn't conclusive enough to ban it right away.
+///
+/// Each offense lowers the reputation of the peer and the peer gets banned
+/// once its reputation falls below a threshold.  See
+/// `peer_manager::peer_store::reputation` for the penalties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+pub enum PeerOffense {
+    /// Sent a block which failed validation.
+    InvalidBlock,
+    /// Sent a malformed transaction, i.e. one which is invalid regardless of the chain state.
+    InvalidTransaction,
+    /// Didn't respond in time to a request for a block it announced.
+    Timeout,
+    /// Sent a message which isn't allowed by the protocol.
+    ProtocolViolation,
+    /// Sent more messages of some kind than its rate limit allows.
+    RateLimitExceeded,
+}
+
 /// Banning signal sent from Peer instance to PeerManager
 /// just before Peer instance is stopped.
 #[derive(actix::Message, Debug)]
@@ -93,7 +112,7 @@ pub struct Ban {
 }
 
 /// Status of the known peers.
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone, strum::IntoStaticStr)]
 pub enum KnownPeerStatus {
     /// We got information about this peer from someone, but we didn't
     /// verify them yet. This peer might not exist, invalid IP etc.
@@ -108,15 +127,19 @@ pub enum KnownPeerStatus {
 }
 
 /// Information node stores about known peers.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct KnownPeerState {
     pub peer_info: PeerInfo,
     pub status: KnownPeerStatus,
     pub first_seen: time::Utc,
     pub last_seen: time::Utc,
     // Last time we tried to connect to this peer.
-    // This data is not persisted in storage.
     pub last_outbound_attempt: Option<(time::Utc, Result<(), String>)>,
+    // Number of consecutive failed attempts to connect to this peer.
+    pub failed_attempts: u32,
+    // Time and reason of the last disconnect reported by this peer.
+    // It is not persisted in the store.
+    pub last_disconnect: Option<(time::Utc, DisconnectReason)>,
 }
 
 impl KnownPeerState {
@@ -127,8 +150,27 @@ impl KnownPeerState {
             first_seen: now,
             last_seen: now,
             last_outbound_attempt: None,
+            failed_attempts: 0,
+            last_disconnect: None,
         }
     }
+
+    /// Whether we should hold off connecting to this peer, given the reason
+    /// of the last disconnect it reported.
+    pub fn reconnect_delayed(&self, now: time::Utc) -> bool {
+        let Some((time, reason)) = self.last_disconnect else {
+            return false;
+        };
+        let delay = match reason {
+            DisconnectReason::Unknown => return false,
+            DisconnectReason::ShuttingDown => time::Duration::minutes(1),
+            DisconnectReason::TooManyPeers => time::Duration::minutes(5),
+            DisconnectReason::Banned | DisconnectReason::IncompatibleVersion => {
+                time::Duration::hours(1)
+            }
+        };
+        now < time + delay
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -253,6 +295,8 @@ pub enum NetworkRequests {
     },
     /// Ban given peer.
     BanPeer { peer_id: PeerId, ban_reason: ReasonForBan },
+    /// Lower reputation of given peer, banning it if it falls too low.
+    ReportPeer { peer_id: PeerId, offense: PeerOffense },
     /// Announce account
     AnnounceAccount(AnnounceAccount),
     /// Broadcast information about a hosted snapshot.
@@ -379,6 +423,14 @@ pub struct ConnectedPeerInfo {
     pub peer_type: PeerType,
     /// Nonce used for the connection with the peer.
     pub nonce: u64,
+    /// Whether the peer has proven to own the key of a current TIER1 account.
+    pub is_validator: bool,
+    /// Number of invalid messages received from the peer.
+    pub invalid_messages: u64,
+    /// Number of the peer's block, header and state requests we've responded to.
+    pub requests_served: u64,
+    /// Average time it took the peer to respond to our requests.
+    pub avg_response_latency: Option<time::Duration>,
 }
 
 #[derive(Debug, Clone, actix::MessageResponse, PartialEq, Eq)]
@@ -501,6 +553,8 @@ mod tests {
 pub struct AccountIdOrPeerTrackingShard {
     /// Target account to send the request to
     pub account_id: Option<AccountId>,
+    /// Accounts to send the request to, in order, if there is no route to `account_id`.
+    pub fallback_account_ids: Vec<AccountId>,
     /// Whether to check peers first or target account first
     pub prefer_peer: bool,
     /// Select peers that track shard `shard_id`
@@ -3,6 +3,7 @@ use near_config_utils::{ValidationError, ValidationErrors};
 use near_crypto::key_conversion::is_valid_staking_key;
 use near_primitives::state_record::StateRecord;
 use near_primitives::types::AccountId;
+use near_primitives::version::ProtocolFeature;
 use num_rational::Rational32;
 use std::collections::{HashMap, HashSet};
 
@@ -109,6 +110,13 @@ impl<'a> GenesisValidator<'a> {
             self.validation_errors.push_genesis_semantics_error(error_message)
         }
 
+        for account_id in validators.keys() {
+            if !self.account_ids.contains(account_id) {
+                let error_message = format!("validator {} does not have an account", account_id);
+                self.validation_errors.push_genesis_semantics_error(error_message)
+            }
+        }
+
         if validators != self.staked_accounts {
             let error_message = format!("Validator accounts do not match staked accounts.");
             self.validation_errors.push_genesis_semantics_error(error_message)
@@ -180,6 +188,45 @@ impl<'a> GenesisValidator<'a> {
             let error_message = format!("Epoch Length must be greater than 0");
             self.validation_errors.push_genesis_semantics_error(error_message)
         }
+
+        let shard_ids: Vec<_> = self.genesis_config.shard_layout.shard_ids().collect();
+        let num_shards = shard_ids.len();
+        let seats_per_shard = [
+            (
+                "num_block_producer_seats_per_shard",
+                &self.genesis_config.num_block_producer_seats_per_shard,
+            ),
+            (
+                "avg_hidden_validator_seats_per_shard",
+                &self.genesis_config.avg_hidden_validator_seats_per_shard,
+            ),
+        ];
+        for (name, seats) in seats_per_shard {
+            if !seats.is_empty() && seats.len() != num_shards {
+                let error_message = format!(
+                    "{} has {} entries, but the shard layout has {} shards",
+                    name,
+                    seats.len(),
+                    num_shards
+                );
+                self.validation_errors.push_genesis_semantics_error(error_message)
+            }
+        }
+
+        // Before chunk-only producers, validators are assigned to shards by
+        // looking up the seats of each shard by its id.
+        let protocol_version = self.genesis_config.protocol_version;
+        if !ProtocolFeature::ChunkOnlyProducers.enabled(protocol_version) {
+            let seats = &self.genesis_config.num_block_producer_seats_per_shard;
+            let missing = shard_ids.iter().find(|&&shard_id| shard_id as usize >= seats.len());
+            if let Some(shard_id) = missing {
+                let error_message = format!(
+                    "num_block_producer_seats_per_shard has no entry for shard {}, which protocol version {} requires",
+                    shard_id, protocol_version
+                );
+                self.validation_errors.push_genesis_semantics_error(error_message)
+            }
+        }
     }
 
     fn result_with_full_error(&self) -> Result<(), ValidationError> {
@@ -212,6 +259,7 @@ mod test {
     #[test]
     fn test_total_supply_does_not_depend_on_permanent_storage_bytes() {
         let mut config = GenesisConfig::default();
+        config.protocol_version = PROTOCOL_VERSION;
         config.epoch_length = 42;
         config.total_supply = 110;
         config.validators = vec![AccountInfo {
@@ -279,6 +327,66 @@ mod test {
         validate_genesis(genesis).unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "validator test1 does not have an account")]
+    fn test_validator_without_account() {
+        let mut config = GenesisConfig::default();
+        config.validators = vec![AccountInfo {
+            account_id: "test1".parse().unwrap(),
+            public_key: VALID_ED25519_RISTRETTO_KEY.parse().unwrap(),
+            amount: 10,
+        }];
+        config.total_supply = 110;
+        let records = GenesisRecords(vec![StateRecord::Account {
+            account_id: "test".parse().unwrap(),
+            account: create_account(),
+        }]);
+        let genesis = &Genesis::new(config, records).unwrap();
+        validate_genesis(genesis).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "num_block_producer_seats_per_shard has 2 entries, but the shard layout has 1 shards"
+    )]
+    fn test_seats_per_shard_not_match() {
+        let mut config = GenesisConfig::default();
+        config.validators = vec![AccountInfo {
+            account_id: "test".parse().unwrap(),
+            public_key: VALID_ED25519_RISTRETTO_KEY.parse().unwrap(),
+            amount: 10,
+        }];
+        config.total_supply = 110;
+        config.num_block_producer_seats_per_shard = vec![1, 1];
+        let records = GenesisRecords(vec![StateRecord::Account {
+            account_id: "test".parse().unwrap(),
+            account: create_account(),
+        }]);
+        let genesis = &Genesis::new(config, records).unwrap();
+        validate_genesis(genesis).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "num_block_producer_seats_per_shard has no entry for shard 0, which protocol version 55 requires"
+    )]
+    fn test_seats_per_shard_missing_before_chunk_only_producers() {
+        let mut config = GenesisConfig::default();
+        config.protocol_version = 55;
+        config.validators = vec![AccountInfo {
+            account_id: "test".parse().unwrap(),
+            public_key: VALID_ED25519_RISTRETTO_KEY.parse().unwrap(),
+            amount: 10,
+        }];
+        config.total_supply = 110;
+        let records = GenesisRecords(vec![StateRecord::Account {
+            account_id: "test".parse().unwrap(),
+            account: create_account(),
+        }]);
+        let genesis = &Genesis::new(config, records).unwrap();
+        validate_genesis(genesis).unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "No validators in genesis")]
     fn test_empty_validator() {
@@ -940,6 +940,9 @@ pub enum EpochError {
     MissingBlock(CryptoHash),
     /// Error due to IO (DB read/write, serialization, etc.).
     IOErr(String),
+    /// Data read from the DB is corrupted.  Unlike [`EpochError::IOErr`],
+    /// retrying won't help; the database needs repairing.
+    DBCorruption(String),
     /// Given account ID is not a validator in the given epoch ID.
     NotAValidator(AccountId, EpochId),
     /// Error getting information for a shard
@@ -969,6 +972,7 @@ impl Display for EpochError {
             }
             EpochError::MissingBlock(hash) => write!(f, "Missing block {}", hash),
             EpochError::IOErr(err) => write!(f, "IO: {}", err),
+            EpochError::DBCorruption(err) => write!(f, "DB corruption: {}", err),
             EpochError::NotAValidator(account_id, epoch_id) => {
                 write!(f, "{} is not a validator in epoch {:?}", account_id, epoch_id)
             }
@@ -995,6 +999,7 @@ impl Debug for EpochError {
             EpochError::EpochOutOfBounds(epoch_id) => write!(f, "EpochOutOfBounds({:?})", epoch_id),
             EpochError::MissingBlock(hash) => write!(f, "MissingBlock({})", hash),
             EpochError::IOErr(err) => write!(f, "IOErr({})", err),
+            EpochError::DBCorruption(err) => write!(f, "DBCorruption({})", err),
             EpochError::NotAValidator(account_id, epoch_id) => {
                 write!(f, "NotAValidator({}, {:?})", account_id, epoch_id)
             }
@@ -1260,6 +1265,8 @@ pub enum FunctionCallError {
 #[derive(Debug)]
 pub enum ChunkAccessError {
     ChunkMissing(ChunkHash),
+    /// The chunk couldn't be read from the storage.
+    IOError(std::io::Error),
 }
 
 impl std::fmt::Display for ChunkAccessError {
@@ -217,6 +217,10 @@ pub struct ViewStateResult {
     #[serde_as(as = "Vec<Base64>")]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub proof: Vec<Arc<[u8]>>,
+    /// Set if the values were truncated by the requested `limit`.  Passed as
+    /// `after_key` it fetches the next page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_key: Option<StoreKey>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
@@ -303,6 +307,13 @@ pub enum QueryRequest {
         prefix: StoreKey,
         #[serde(default, skip_serializing_if = "is_false")]
         include_proof: bool,
+        /// Only the keys strictly greater than this one are returned.
+        #[serde(rename = "after_key_base64", default, skip_serializing_if = "Option::is_none")]
+        after_key: Option<StoreKey>,
+        /// Maximum number of values returned.  The node caps it and may
+        /// return fewer values to keep the response size bounded.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<u64>,
     },
     ViewAccessKey {
         account_id: AccountId,
@@ -344,6 +355,13 @@ pub struct StatusSyncInfo {
     pub earliest_block_time: Option<Utc>,
     pub epoch_id: Option<EpochId>,
     pub epoch_start_height: Option<BlockHeight>,
+    /// Height of the highest known block header.  While the node is syncing
+    /// it is ahead of `latest_block_height`.
+    #[serde(default)]
+    pub latest_header_height: Option<BlockHeight>,
+    /// Number of peers the node is connected to.
+    #[serde(default)]
+    pub num_connected_peers: Option<usize>,
 }
 
 // TODO: add more information to ValidatorInfo
@@ -371,6 +389,15 @@ pub struct PeerInfoView {
     pub is_outbound_peer: bool,
     /// Connection nonce.
     pub nonce: u64,
+    /// Number of invalid messages received from the peer.
+    #[serde(default)]
+    pub invalid_messages: u64,
+    /// Number of the peer's block, header and state requests served.
+    #[serde(default)]
+    pub requests_served: u64,
+    /// Average time it took the peer to respond to our requests.
+    #[serde(default)]
+    pub avg_response_latency_millis: Option<u64>,
 }
 
 /// Information about a Producer: its account name, peer_id and a list of connected peers that
@@ -454,6 +481,26 @@ pub struct SnapshotHostsView {
     pub hosts: Vec<SnapshotHostInfoView>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct MessageTypeBandwidthView {
+    pub message_type: String,
+    pub sent_bytes_total: u64,
+    pub received_bytes_total: u64,
+    pub sent_bytes_per_min: u64,
+    pub received_bytes_per_min: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct PeerBandwidthView {
+    pub peer_id: PeerId,
+    pub message_types: Vec<MessageTypeBandwidthView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct NetworkBandwidthView {
+    pub peers: Vec<PeerBandwidthView>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
 pub struct EdgeView {
     pub peer0: PeerId,
@@ -2484,6 +2531,16 @@ pub type StateChangesView = Vec<StateChangeWithCauseView>;
 /// Maintenance windows view are a vector of maintenance window.
 pub type MaintenanceWindowsView = Vec<Range<BlockHeight>>;
 
+/// Logs emitted by a single execution (of a transaction or a receipt) on an account.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractLogsView {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    /// Id of the transaction or receipt whose execution emitted the logs.
+    pub id: CryptoHash,
+    pub logs: Vec<String>,
+}
+
 /// Contains the split storage information.
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct SplitStorageInfoView {
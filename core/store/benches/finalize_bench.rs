@@ -33,6 +33,7 @@ use near_primitives::transaction::{Action, FunctionCallAction, SignedTransaction
 use near_primitives::types::AccountId;
 use near_primitives::validator_signer::InMemoryValidatorSigner;
 use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
+use near_store::adapter::StoreUpdateAdapter;
 use near_store::DBCol;
 use rand::prelude::SliceRandom;
 use reed_solomon_erasure::galois_8::ReedSolomon;
@@ -58,9 +59,8 @@ fn benchmark_write_shard_chunk(bench: &mut Bencher) {
     });
 }
 
-/// `PartialEncodedChunk` -> `StoreUpdate::insert_ser`.
-///
-/// ~70ms for ~24MB receipts
+/// `PartialEncodedChunk` -> `ChunkStoreUpdateAdapter::insert_partial_chunk`,
+/// which stores the parts in DBCol::PartialChunkParts.
 fn benchmark_write_partial_encoded_chunk(bench: &mut Bencher) {
     let transactions = vec![];
     let receipts = create_benchmark_receipts();
@@ -75,11 +75,8 @@ fn benchmark_write_partial_encoded_chunk(bench: &mut Bencher) {
     bench.iter(|| {
         let mut update = store.store_update();
         update
-            .insert_ser(
-                DBCol::PartialChunks,
-                chunk_hash.as_ref(),
-                &chunks.choose(&mut rand::thread_rng()),
-            )
+            .chunk_store_update()
+            .insert_partial_chunk(chunks.choose(&mut rand::thread_rng()).unwrap())
             .unwrap();
         black_box(update);
     });
@@ -1,11 +1,16 @@
+use std::io;
 use std::sync::Arc;
 
 use near_primitives::errors::ChunkAccessError;
-use near_primitives::sharding::{ChunkHash, PartialEncodedChunk, ShardChunk};
+use near_primitives::sharding::{
+    ChunkHash, PartialEncodedChunk, PartialEncodedChunkPart, PartialEncodedChunkV1,
+    PartialEncodedChunkV2, ShardChunk,
+};
 
-use crate::{DBCol, Store};
+use crate::keys::{ChunkPartKey, ColumnKey, PartialChunkKey};
+use crate::{DBCol, Store, StoreUpdate};
 
-use super::StoreAdapter;
+use super::{StoreAdapter, StoreUpdateAdapter, StoreUpdateHolder};
 
 #[derive(Clone)]
 pub struct ChunkStoreAdapter {
@@ -23,16 +28,55 @@ impl ChunkStoreAdapter {
         Self { store }
     }
 
+    pub fn store_update(&self) -> ChunkStoreUpdateAdapter<'static> {
+        ChunkStoreUpdateAdapter {
+            store_update: StoreUpdateHolder::Owned(self.store.store_update()),
+        }
+    }
+
+    /// Returns the partial chunk together with all of its parts.
     pub fn get_partial_chunk(
         &self,
         chunk_hash: &ChunkHash,
     ) -> Result<Arc<PartialEncodedChunk>, ChunkAccessError> {
+        let partial_chunk = self.get_partial_chunk_without_parts(chunk_hash)?;
+        let parts = self
+            .store
+            .iter_prefix_ser::<PartialEncodedChunkPart>(
+                DBCol::PartialChunkParts,
+                chunk_hash.as_ref(),
+            )
+            .map(|item| item.map(|(_, part)| part))
+            .collect::<io::Result<_>>()
+            .map_err(ChunkAccessError::IOError)?;
+        Ok(Arc::new(with_parts(partial_chunk, parts)))
+    }
+
+    /// Returns the partial chunk with the header and receipts only.  The parts
+    /// are stored separately and can be read with
+    /// [`Self::get_partial_chunk_part`].
+    pub fn get_partial_chunk_without_parts(
+        &self,
+        chunk_hash: &ChunkHash,
+    ) -> Result<PartialEncodedChunk, ChunkAccessError> {
         self.store
-            .get_ser(DBCol::PartialChunks, chunk_hash.as_ref())
-            .expect("Borsh should not have failed here")
+            .get_ser_by_key(&PartialChunkKey(chunk_hash))
+            .map_err(ChunkAccessError::IOError)?
             .ok_or_else(|| ChunkAccessError::ChunkMissing(chunk_hash.clone()))
     }
 
+    /// Returns a single part of the partial chunk without reading the rest of
+    /// the chunk.
+    pub fn get_partial_chunk_part(
+        &self,
+        chunk_hash: &ChunkHash,
+        part_ord: u64,
+    ) -> Option<PartialEncodedChunkPart> {
+        self.store
+            .get_ser_by_key(&ChunkPartKey { chunk_hash, part_ord })
+            .expect("Borsh should not have failed here")
+    }
+
     pub fn get_chunk(&self, chunk_hash: &ChunkHash) -> Result<Arc<ShardChunk>, ChunkAccessError> {
         self.store
             .get_ser(DBCol::Chunks, chunk_hash.as_ref())
@@ -40,3 +84,115 @@ impl ChunkStoreAdapter {
             .ok_or_else(|| ChunkAccessError::ChunkMissing(chunk_hash.clone()))
     }
 }
+
+pub struct ChunkStoreUpdateAdapter<'a> {
+    store_update: StoreUpdateHolder<'a>,
+}
+
+impl Into<StoreUpdate> for ChunkStoreUpdateAdapter<'static> {
+    fn into(self) -> StoreUpdate {
+        self.store_update.into()
+    }
+}
+
+impl ChunkStoreUpdateAdapter<'static> {
+    pub fn commit(self) -> io::Result<()> {
+        let store_update: StoreUpdate = self.into();
+        store_update.commit()
+    }
+}
+
+impl<'a> StoreUpdateAdapter for ChunkStoreUpdateAdapter<'a> {
+    fn store_update(&mut self) -> &mut StoreUpdate {
+        &mut self.store_update
+    }
+}
+
+impl<'a> ChunkStoreUpdateAdapter<'a> {
+    pub fn new(store_update: &'a mut StoreUpdate) -> Self {
+        Self { store_update: StoreUpdateHolder::Reference(store_update) }
+    }
+
+    /// Saves the partial chunk, storing its parts in DBCol::PartialChunkParts.
+    pub fn insert_partial_chunk(&mut self, partial_chunk: &PartialEncodedChunk) -> io::Result<()> {
+        let chunk_hash = partial_chunk.chunk_hash();
+        for part in partial_chunk.parts() {
+            self.store_update.insert_ser_by_key(
+                &ChunkPartKey { chunk_hash: &chunk_hash, part_ord: part.part_ord },
+                part,
+            )?;
+        }
+        let partial_chunk = PartialEncodedChunk::new(
+            partial_chunk.cloned_header(),
+            vec![],
+            partial_chunk.prev_outgoing_receipts().to_vec(),
+        );
+        self.store_update.insert_ser_by_key(&PartialChunkKey(&chunk_hash), &partial_chunk)
+    }
+
+    /// Deletes the partial chunk together with all of its parts.
+    pub fn delete_partial_chunk(&mut self, chunk_hash: &ChunkHash) {
+        self.store_update.delete_by_key(&PartialChunkKey(chunk_hash));
+        // The end of the range is exclusive, so the last possible part is
+        // deleted separately.
+        self.store_update.delete_range(
+            ChunkPartKey::COLUMN,
+            &ChunkPartKey { chunk_hash, part_ord: 0 }.key_bytes(),
+            &ChunkPartKey { chunk_hash, part_ord: u64::MAX }.key_bytes(),
+        );
+        self.store_update.delete_by_key(&ChunkPartKey { chunk_hash, part_ord: u64::MAX });
+    }
+}
+
+/// Replaces parts of the partial chunk.
+pub fn with_parts(
+    partial_chunk: PartialEncodedChunk,
+    parts: Vec<PartialEncodedChunkPart>,
+) -> PartialEncodedChunk {
+    match partial_chunk {
+        PartialEncodedChunk::V1(chunk) => {
+            PartialEncodedChunk::V1(PartialEncodedChunkV1 { parts, ..chunk })
+        }
+        PartialEncodedChunk::V2(chunk) => {
+            PartialEncodedChunk::V2(PartialEncodedChunkV2 { parts, ..chunk })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_primitives::sharding::{PartialEncodedChunk, PartialEncodedChunkPart};
+    use near_primitives::stateless_validation::state_witness::ChunkStateWitness;
+
+    use crate::adapter::StoreAdapter;
+    use crate::test_utils::create_test_store;
+
+    #[test]
+    fn test_partial_chunk_parts() {
+        let chunk_store = create_test_store().chunk_store();
+        let header = ChunkStateWitness::new_dummy(1, 0, Default::default()).chunk_header;
+        let chunk_hash = header.chunk_hash();
+        let parts = [0, 2, 256, u64::MAX]
+            .map(|part_ord| PartialEncodedChunkPart {
+                part_ord,
+                part: vec![part_ord as u8].into_boxed_slice(),
+                merkle_proof: vec![],
+            })
+            .to_vec();
+        let partial_chunk = PartialEncodedChunk::new(header, parts.clone(), vec![]);
+
+        let mut store_update = chunk_store.store_update();
+        store_update.insert_partial_chunk(&partial_chunk).unwrap();
+        store_update.commit().unwrap();
+        assert_eq!(*chunk_store.get_partial_chunk(&chunk_hash).unwrap(), partial_chunk);
+        assert_eq!(chunk_store.get_partial_chunk_part(&chunk_hash, 2), Some(parts[1].clone()));
+        assert_eq!(chunk_store.get_partial_chunk_part(&chunk_hash, 1), None);
+
+        let mut store_update = chunk_store.store_update();
+        store_update.delete_partial_chunk(&chunk_hash);
+        store_update.commit().unwrap();
+        assert!(chunk_store.get_partial_chunk(&chunk_hash).is_err());
+        assert_eq!(chunk_store.get_partial_chunk_part(&chunk_hash, 256), None);
+        assert_eq!(chunk_store.get_partial_chunk_part(&chunk_hash, u64::MAX), None);
+    }
+}
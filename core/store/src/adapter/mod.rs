@@ -115,4 +115,8 @@ pub trait StoreUpdateAdapter: Sized {
     fn flat_store_update(&mut self) -> flat_store::FlatStoreUpdateAdapter {
         flat_store::FlatStoreUpdateAdapter::new(self.store_update())
     }
+
+    fn chunk_store_update(&mut self) -> chunk_store::ChunkStoreUpdateAdapter {
+        chunk_store::ChunkStoreUpdateAdapter::new(self.store_update())
+    }
 }
@@ -81,9 +81,10 @@ pub enum DBCol {
     /// - *Rows*: ChunkHash (CryptoHash)
     /// - *Content type*: [near_primitives::sharding::ShardChunk]
     Chunks,
-    /// Storage for  PartialEncodedChunk.
+    /// Storage for  PartialEncodedChunk.  The parts of the chunk are stored
+    /// separately in DBCol::PartialChunkParts.
     /// - *Rows*: ChunkHash (CryptoHash)
-    /// - *Content type*: [near_primitives::sharding::PartialEncodedChunk]
+    /// - *Content type*: [near_primitives::sharding::PartialEncodedChunk] with no parts
     PartialChunks,
     /// Blocks for which chunks need to be applied after the state is downloaded for a particular epoch
     /// - *Rows*: BlockHash (CryptoHash)
@@ -300,6 +301,22 @@ pub enum DBCol {
     /// - *Rows*: only one key with 0 bytes.
     /// - *Column type*: `EpochSyncProof`
     EpochSyncProof,
+    /// Parts of the chunks stored in DBCol::PartialChunks.  Kept in a separate
+    /// column so that a single part can be read or deleted without reading
+    /// and rewriting the whole partial chunk.
+    /// - *Rows*: ChunkHash (CryptoHash) || part_ord (u64, big-endian)
+    /// - *Column type*: [near_primitives::sharding::PartialEncodedChunkPart]
+    PartialChunkParts,
+    /// Peers banned by the network layer, so that bans survive restarts.
+    /// - *Rows*: PeerId
+    /// - *Column type*: reason for the ban and the time it was issued
+    BannedPeers,
+    /// Peers known to the network layer together with their connection
+    /// history, so that the node doesn't have to rediscover the network from
+    /// the boot nodes on every restart.
+    /// - *Rows*: PeerId
+    /// - *Column type*: [network_primitives::types::KnownPeerState]
+    KnownPeers,
 }
 
 /// Defines different logical parts of a db key.
@@ -361,6 +378,7 @@ impl DBCol {
             | DBCol::Chunks
             | DBCol::InvalidChunks
             | DBCol::PartialChunks
+            | DBCol::PartialChunkParts
             | DBCol::TransactionResultForBlock => true,
             _ => false,
         }
@@ -411,6 +429,28 @@ impl DBCol {
         }
     }
 
+    /// How long values in this column are kept, if the column holds
+    /// ephemeral data which expires on its own.
+    ///
+    /// Every value written to such column is stored with its expiry time
+    /// attached (see [`crate::db::ttl`]).  Expired values are treated as
+    /// missing when reading and are removed from disk during compaction so
+    /// the column doesn’t need to be garbage collected by hand.
+    ///
+    /// Columns with TTL must not be reference counted.
+    pub const fn ttl(&self) -> Option<std::time::Duration> {
+        match self {
+            // Accounts are announced again every epoch so anything older
+            // than a couple of epochs is stale.
+            DBCol::AccountAnnouncements => Some(std::time::Duration::from_secs(2 * 24 * 60 * 60)),
+            // BannedPeers and KnownPeers expire after the ban window and the
+            // peer expiration duration from the network config, which a
+            // fixed TTL can't follow.  The PeerStore deletes their rows as it
+            // drops the peers from memory instead.
+            _ => None,
+        }
+    }
+
     /// Whether this column should be copied to the cold storage.
     ///
     /// This doesn't include DbVersion and BlockMisc columns which are present
@@ -473,7 +513,7 @@ impl DBCol {
             // Deprecated.
             DBCol::_ReceiptIdToShardId => false,
             // This can be re-constructed from the Chunks column, so no need to store in Cold DB.
-            DBCol::PartialChunks => false,
+            DBCol::PartialChunks | DBCol::PartialChunkParts => false,
 
             // Columns that are not GC-ed need not be copied to the cold storage.
             DBCol::BlockHeader
@@ -481,6 +521,8 @@ impl DBCol {
             | DBCol::BlockHeight
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::BannedPeers
+            | DBCol::KnownPeers
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
@@ -527,6 +569,8 @@ impl DBCol {
             DBCol::IncomingReceipts => &[DBKeyType::BlockHash, DBKeyType::ShardId],
             DBCol::_Peers => &[DBKeyType::PeerId],
             DBCol::RecentOutboundConnections => &[DBKeyType::Empty],
+            DBCol::BannedPeers => &[DBKeyType::PeerId],
+            DBCol::KnownPeers => &[DBKeyType::PeerId],
             DBCol::EpochInfo => &[DBKeyType::EpochId],
             DBCol::BlockInfo => &[DBKeyType::BlockHash],
             DBCol::Chunks => &[DBKeyType::ChunkHash],
@@ -575,6 +619,7 @@ impl DBCol {
             DBCol::LatestChunkStateWitnesses => &[DBKeyType::LatestWitnessesKey],
             DBCol::LatestWitnessesByIndex => &[DBKeyType::LatestWitnessIndex],
             DBCol::EpochSyncProof => &[DBKeyType::Empty],
+            DBCol::PartialChunkParts => &[DBKeyType::ChunkHash, DBKeyType::PartId],
         }
     }
 }
@@ -594,7 +639,9 @@ mod tests {
     fn column_props_sanity() {
         for col in DBCol::iter() {
             // Check that rc and write_once are mutually exclusive.
-            assert!((col.is_rc() as u32) + (col.is_insert_only() as u32) <= 1, "{col}")
+            assert!((col.is_rc() as u32) + (col.is_insert_only() as u32) <= 1, "{col}");
+            // Check that rc and ttl are mutually exclusive.
+            assert!(!col.is_rc() || col.ttl().is_none(), "{col}");
         }
     }
 
@@ -44,6 +44,36 @@ pub struct StoreConfig {
     /// the performance of the storage
     pub block_size: bytesize::ByteSize,
 
+    /// Whether to write changes to the RocksDB write-ahead log before applying
+    /// them.  Default value: true.
+    /// Disabling the WAL makes commits faster, but any changes not yet flushed
+    /// to SST files are lost if the node crashes.  Only use it on test networks
+    /// or on nodes, e.g. indexers, whose database can be cheaply recreated.
+    pub enable_wal: bool,
+
+    /// Whether every commit waits for the write-ahead log to be synced to disk.
+    /// Default value: false.
+    /// Without it, committed changes survive a crash of the node process but
+    /// may be lost on an OS crash or power failure.  Enabling it gives maximum
+    /// durability at the cost of commit latency.  Ignored if `enable_wal` is
+    /// false.
+    pub sync_writes: bool,
+
+    /// Path to a file with the key used to encrypt values stored in the
+    /// database.  If relative, resolved relative to the database directory.
+    /// The file must contain a 32-byte key encoded as 64 hex digits.
+    /// Keys of the database entries are not encrypted.
+    /// Encryption can only be enabled when creating a new database and the
+    /// same key has to be supplied every time the database is opened.
+    pub encryption_key_file: Option<std::path::PathBuf>,
+
+    /// Whether to store a checksum with every value and verify it on read.
+    /// Values which fail verification are reported as
+    /// [`crate::StoreError::Corruption`].  Like encryption, this changes the
+    /// format of values on disk so it can only be enabled when creating a new
+    /// database.
+    pub value_checksums: bool,
+
     /// Trie cache configuration per shard for normal (non-view) caches.
     pub trie_cache: TrieCacheConfig,
     /// Trie cache configuration per shard for view caches.
@@ -218,6 +248,15 @@ impl Default for StoreConfig {
             // we use it since then.
             block_size: bytesize::ByteSize::kib(16),
 
+            // Validators should not lose writes on a crash, but syncing on
+            // every commit is expensive and a process crash is covered by the
+            // WAL alone.
+            enable_wal: true,
+            sync_writes: false,
+
+            encryption_key_file: None,
+            value_checksums: false,
+
             trie_cache: TrieCacheConfig {
                 default_max_bytes: bytesize::ByteSize::mb(500),
                 // TODO(resharding) The cache size needs to adjusted for every resharding.
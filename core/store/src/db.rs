@@ -13,6 +13,7 @@ mod splitdb;
 pub mod refcount;
 mod slice;
 mod testdb;
+pub mod ttl;
 
 mod database_tests;
 
@@ -1,8 +1,10 @@
+use self::encryption::ValueCipher;
 use crate::config::Mode;
-use crate::db::{refcount, DBIterator, DBOp, DBSlice, DBTransaction, Database, StatsValue};
+use crate::db::{refcount, ttl, DBIterator, DBOp, DBSlice, DBTransaction, Database, StatsValue};
 use crate::{metadata, metrics, DBCol, StoreConfig, StoreStatistics, Temperature};
 use ::rocksdb::{
-    BlockBasedOptions, Cache, ColumnFamily, Env, IteratorMode, Options, ReadOptions, WriteBatch, DB,
+    BlockBasedOptions, Cache, ColumnFamily, Env, IteratorMode, Options, ReadOptions, WriteBatch,
+    WriteOptions, DB,
 };
 use anyhow::Context;
 use itertools::Itertools;
@@ -13,6 +15,8 @@ use std::sync::LazyLock;
 use strum::IntoEnumIterator;
 use tracing::warn;
 
+mod checksum;
+mod encryption;
 mod instance_tracker;
 pub(crate) mod snapshot;
 
@@ -47,6 +51,17 @@ pub struct RocksDB {
     db: DB,
     db_opt: Options,
 
+    /// Options used for every write, set from the durability settings in
+    /// [`StoreConfig`].
+    write_options: WriteOptions,
+
+    /// Cipher used to encrypt values, if encryption at rest is enabled.
+    cipher: Option<ValueCipher>,
+
+    /// Whether values are stored with checksums.  See
+    /// [`StoreConfig::value_checksums`].
+    value_checksums: bool,
+
     /// Map from [`DBCol`] to a column family handler in the RocksDB.
     ///
     /// Rather than accessing this field directly, use [`RocksDB::cf_handle`]
@@ -57,6 +72,10 @@ pub struct RocksDB {
     // RAII-style of keeping track of the number of instances of RocksDB and
     // counting total sum of max_open_files.
     _instance_tracker: instance_tracker::InstanceTracker,
+
+    /// Directory with the info logs of a secondary instance.  Removed when the
+    /// database is dropped.  `None` unless opened with [`Self::open_secondary`].
+    _secondary_dir: Option<tempfile::TempDir>,
 }
 
 // DB was already Send+Sync. cf and read_options are const pointers using only functions in
@@ -117,9 +136,131 @@ impl RocksDB {
     ) -> io::Result<Self> {
         let counter = instance_tracker::InstanceTracker::try_new(store_config.max_open_files)
             .map_err(io::Error::other)?;
+        let cipher = Self::open_cipher(path, store_config)?;
         let (db, db_opt) = Self::open_db(path, store_config, mode, temp, columns)?;
         let cf_handles = Self::get_cf_handles(&db, columns);
-        Ok(Self { db, db_opt, cf_handles, _instance_tracker: counter })
+        let db = Self {
+            db,
+            db_opt,
+            write_options: rocksdb_write_options(store_config),
+            cipher,
+            value_checksums: store_config.value_checksums,
+            cf_handles,
+            _instance_tracker: counter,
+            _secondary_dir: None,
+        };
+        db.check_value_format(mode)?;
+        Ok(db)
+    }
+
+    /// Opens the database as a RocksDB secondary instance.
+    ///
+    /// Unlike opening in [`Mode::ReadOnly`], this is safe to do while another
+    /// process, e.g. a running node, has the database open for writing.  The
+    /// secondary instance doesn’t take the database lock, keeps its info logs
+    /// in a temporary directory and sees the data as of the time it was opened.
+    /// Any attempt to write to it fails.
+    pub fn open_secondary(
+        path: &Path,
+        store_config: &StoreConfig,
+        temp: Temperature,
+    ) -> io::Result<Self> {
+        let counter = instance_tracker::InstanceTracker::try_new(store_config.max_open_files)
+            .map_err(io::Error::other)?;
+        let cipher = Self::open_cipher(path, store_config)?;
+        let secondary_dir = tempfile::Builder::new().prefix("rocksdb-secondary-").tempdir()?;
+        let columns = DBCol::iter().collect_vec();
+        let mut options = rocksdb_options(store_config, Mode::ReadOnly);
+        // The primary may delete SST files at any point after compaction.  The
+        // secondary can only keep reading them if it never closes their file
+        // descriptors, which is why RocksDB requires unlimited open files here.
+        options.set_max_open_files(-1);
+        let cfs = cf_descriptors(&columns, store_config, temp);
+        let db = DB::open_cf_descriptors_as_secondary(&options, path, secondary_dir.path(), cfs)
+            .map_err(io::Error::other)?;
+        let cf_handles = Self::get_cf_handles(&db, &columns);
+        let db = Self {
+            db,
+            db_opt: options,
+            write_options: rocksdb_write_options(store_config),
+            cipher,
+            value_checksums: store_config.value_checksums,
+            cf_handles,
+            _instance_tracker: counter,
+            _secondary_dir: Some(secondary_dir),
+        };
+        db.check_value_format(Mode::ReadOnly)?;
+        Ok(db)
+    }
+
+    /// Loads the encryption key if encryption at rest is configured.
+    ///
+    /// Relative key file paths are resolved relative to the database directory.
+    fn open_cipher(path: &Path, store_config: &StoreConfig) -> io::Result<Option<ValueCipher>> {
+        store_config
+            .encryption_key_file
+            .as_deref()
+            .map(|key_file| ValueCipher::from_key_file(&path.join(key_file)))
+            .transpose()
+    }
+
+    /// Checks that values in the database are stored in the format the
+    /// configuration asks for.
+    ///
+    /// Encryption and checksums change the format of every value so they are
+    /// recorded in [`DBCol::DbVersion`] when the database is created.  Opening
+    /// the database with a different configuration, or enabling either of them
+    /// for an existing database, fails instead of handing out values which
+    /// can’t be read.
+    fn check_value_format(&self, mode: Mode) -> io::Result<()> {
+        let is_new = metadata::DbMetadata::maybe_read_version(self)?.is_none();
+        let mut transaction = DBTransaction::new();
+        self.check_format_marker(
+            "encryption",
+            metadata::ENCRYPTION_KEY,
+            self.cipher.as_ref().map(ValueCipher::key_check),
+            is_new,
+            &mut transaction,
+        )?;
+        self.check_format_marker(
+            "value checksums",
+            metadata::CHECKSUMS_KEY,
+            self.value_checksums.then_some(checksum::ALGORITHM),
+            is_new,
+            &mut transaction,
+        )?;
+        if mode.read_write() && !transaction.ops.is_empty() {
+            self.write(transaction)?;
+        }
+        Ok(())
+    }
+
+    /// Compares the marker of a value format stored under `key` in
+    /// [`DBCol::DbVersion`] with the `expected` one.  `None` means the format
+    /// isn’t used.  For new databases, adds the missing marker to
+    /// `transaction`.
+    fn check_format_marker(
+        &self,
+        what: &str,
+        key: &[u8],
+        expected: Option<&str>,
+        is_new: bool,
+        transaction: &mut DBTransaction,
+    ) -> io::Result<()> {
+        let stored = self.get_raw_bytes(DBCol::DbVersion, key)?;
+        let msg = match (stored.as_deref(), expected) {
+            (stored, expected) if stored == expected.map(str::as_bytes) => return Ok(()),
+            (None, Some(expected)) if is_new => {
+                transaction.set(DBCol::DbVersion, key.to_vec(), expected.as_bytes().to_vec());
+                return Ok(());
+            }
+            (None, Some(_)) => format!("{what} can only be enabled when creating a new database"),
+            (Some(_), None) => format!("database was created with {what} enabled"),
+            (Some(_), Some(_)) => {
+                format!("{what} settings don’t match the ones the database was created with")
+            }
+        };
+        Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
     }
 
     /// Opens the database with given column families configured.
@@ -252,17 +393,38 @@ impl RocksDB {
             read_options.set_iterate_upper_bound(upper_bound);
         }
         let iter = self.db.iterator_cf_opt(cf_handle, read_options, IteratorMode::Start);
-        RocksDBIterator(iter)
+        let decoder = self.is_encoded(col).then_some(self);
+        RocksDBIterator { iter, col, decoder }
     }
 }
 
-struct RocksDBIterator<'a>(rocksdb::DBIteratorWithThreadMode<'a, DB>);
+struct RocksDBIterator<'a> {
+    iter: rocksdb::DBIteratorWithThreadMode<'a, DB>,
+    col: DBCol,
+    /// Database to decode values with, if values in the column are encoded.
+    /// See [`RocksDB::encode_value`].
+    decoder: Option<&'a RocksDB>,
+}
 
 impl<'a> Iterator for RocksDBIterator<'a> {
     type Item = io::Result<(Box<[u8]>, Box<[u8]>)>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(self.0.next()?.map_err(io::Error::other))
+        loop {
+            let (key, value) = match self.iter.next()? {
+                Ok(item) => item,
+                Err(err) => return Some(Err(io::Error::other(err))),
+            };
+            let Some(decoder) = self.decoder else {
+                return Some(Ok((key, value)));
+            };
+            match decoder.decode_value(self.col, &key, &value) {
+                Ok(Some(value)) => return Some(Ok((key, value.into_boxed_slice()))),
+                // Skip expired values.
+                Ok(None) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
     }
 }
 
@@ -298,6 +460,48 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Whether values in given column are stored in a different form than
+    /// they are written in.  See [`Self::encode_value`].
+    fn is_encoded(&self, col: DBCol) -> bool {
+        col.ttl().is_some()
+            || (self.value_checksums && checksum::has_checksum(col))
+            || (self.cipher.is_some() && ValueCipher::is_encrypted(col))
+    }
+
+    /// Converts the value into the form it is stored on disk in.
+    ///
+    /// Encrypts the value if encryption at rest is enabled, attaches checksum
+    /// if checksums are enabled and attaches expiry time to values of columns
+    /// with TTL.
+    fn encode_value(&self, col: DBCol, key: &[u8], value: Vec<u8>) -> Vec<u8> {
+        let value = match &self.cipher {
+            Some(cipher) => cipher.encrypt(col, key, value),
+            None => value,
+        };
+        let value =
+            if self.value_checksums { checksum::add_checksum(col, key, value) } else { value };
+        ttl::add_expiry(col, value)
+    }
+
+    /// Inverse of [`Self::encode_value`].  Returns `None` if the value has
+    /// expired.
+    fn decode_value(&self, col: DBCol, key: &[u8], value: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let Some(value) = ttl::strip_expiry(col, value) else {
+            return Ok(None);
+        };
+        let verified;
+        let value = if self.value_checksums {
+            verified = checksum::strip_checksum(col, key, value)?;
+            &verified[..]
+        } else {
+            value
+        };
+        match &self.cipher {
+            Some(cipher) => cipher.decrypt(col, key, value).map(Some),
+            None => Ok(Some(value.to_vec())),
+        }
+    }
+
     #[tracing::instrument(
         target = "store::db::rocksdb",
         level = "trace",
@@ -310,6 +514,7 @@ impl RocksDB {
         for op in transaction.ops {
             match op {
                 DBOp::Set { col, key, value } => {
+                    let value = self.encode_value(col, &key, value);
                     batch.put_cf(self.cf_handle(col)?, key, value);
                 }
                 DBOp::Insert { col, key, value } => {
@@ -318,9 +523,11 @@ impl RocksDB {
                             super::assert_no_overwrite(col, &key, &value, &*old_value)
                         }
                     }
+                    let value = self.encode_value(col, &key, value);
                     batch.put_cf(self.cf_handle(col)?, key, value);
                 }
                 DBOp::UpdateRefcount { col, key, value } => {
+                    let value = self.encode_value(col, &key, value);
                     batch.merge_cf(self.cf_handle(col)?, key, value);
                 }
                 DBOp::Delete { col, key } => {
@@ -352,8 +559,13 @@ impl Database for RocksDB {
         let result = self
             .db
             .get_pinned_cf_opt(self.cf_handle(col)?, key, &read_options)
-            .map_err(io::Error::other)?
-            .map(DBSlice::from_rocksdb_slice);
+            .map_err(io::Error::other)?;
+        let result = match result {
+            Some(value) if self.is_encoded(col) => {
+                self.decode_value(col, key, &value)?.map(DBSlice::from_vec)
+            }
+            result => result.map(DBSlice::from_rocksdb_slice),
+        };
         timer.observe_duration();
         Ok(result)
     }
@@ -399,7 +611,7 @@ impl Database for RocksDB {
                 backtrace = %std::backtrace::Backtrace::force_capture()
             );
         }
-        self.db.write(batch).map_err(io::Error::other)
+        self.db.write_opt(batch, &self.write_options).map_err(io::Error::other)
     }
 
     #[tracing::instrument(
@@ -565,6 +777,14 @@ fn rocksdb_read_options() -> ReadOptions {
     read_options
 }
 
+fn rocksdb_write_options(store_config: &StoreConfig) -> WriteOptions {
+    let mut write_options = WriteOptions::default();
+    write_options.disable_wal(!store_config.enable_wal);
+    // Syncing is meaningless without the WAL and RocksDB rejects such writes.
+    write_options.set_sync(store_config.enable_wal && store_config.sync_writes);
+    write_options
+}
+
 /// If true then we enable caching of index blocks inside block cache
 fn use_block_cache_for_index_and_filter_blocks(db_col: DBCol) -> bool {
     match db_col {
@@ -618,6 +838,12 @@ fn rocksdb_column_options(col: DBCol, store_config: &StoreConfig, temp: Temperat
         opts.set_merge_operator("refcount merge", RocksDB::refcount_merge, RocksDB::refcount_merge);
         opts.set_compaction_filter("empty value filter", RocksDB::empty_value_compaction_filter);
     }
+    if col.ttl().is_some() {
+        opts.set_compaction_filter(
+            "expired value filter",
+            RocksDB::expired_value_compaction_filter,
+        );
+    }
     opts
 }
 
@@ -898,6 +1124,150 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_open_secondary() {
+        let (tmp_dir, opener) = NodeStorage::test_opener();
+        let primary = opener.open().unwrap().get_hot_store();
+        let mut store_update = primary.store_update();
+        store_update.insert(DBCol::Block, vec![1], vec![42]);
+        store_update.commit().unwrap();
+        primary.flush().unwrap();
+
+        // The primary is still open and holds the lock.
+        let path = tmp_dir.path().join("data");
+        let secondary =
+            RocksDB::open_secondary(&path, &StoreConfig::test_config(), Temperature::Hot).unwrap();
+        assert_eq!(
+            secondary.get_raw_bytes(DBCol::Block, &[1]).unwrap().as_deref(),
+            Some(&[42][..])
+        );
+
+        let mut transaction = crate::db::DBTransaction::new();
+        transaction.insert(DBCol::Block, vec![2], vec![42]);
+        assert!(secondary.write(transaction).is_err());
+        assert_eq!(primary.get(DBCol::Block, &[2]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_durability_options() {
+        for (enable_wal, sync_writes) in [(true, true), (false, false), (false, true)] {
+            let tmp_dir = tempfile::tempdir().unwrap();
+            let config = StoreConfig { enable_wal, sync_writes, ..StoreConfig::test_config() };
+            let store = NodeStorage::opener(tmp_dir.path(), false, &config, None)
+                .open()
+                .unwrap()
+                .get_hot_store();
+            let mut store_update = store.store_update();
+            store_update.insert(DBCol::Block, vec![1], vec![42]);
+            store_update.commit().unwrap();
+            assert_eq!(store.get(DBCol::Block, &[1]).unwrap().as_deref(), Some(&[42][..]));
+        }
+    }
+
+    #[test]
+    fn test_encryption() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let key_file = tmp_dir.path().join("key");
+        std::fs::write(&key_file, hex::encode([7; 32])).unwrap();
+        let config =
+            StoreConfig { encryption_key_file: Some(key_file), ..StoreConfig::test_config() };
+        let path = tmp_dir.path().join("data");
+        {
+            let db = RocksDB::open(&path, &config, Mode::Create, Temperature::Hot).unwrap();
+            let mut transaction = crate::db::DBTransaction::new();
+            transaction.insert(DBCol::Block, vec![1], vec![42]);
+            transaction.update_refcount(DBCol::State, vec![2], vec![43, 1, 0, 0, 0, 0, 0, 0, 0]);
+            db.write(transaction).unwrap();
+        }
+
+        let db = RocksDB::open(&path, &config, Mode::ReadOnly, Temperature::Hot).unwrap();
+        assert_eq!(db.get_raw_bytes(DBCol::Block, &[1]).unwrap().as_deref(), Some(&[42][..]));
+        assert_eq!(
+            db.get_with_rc_stripped(DBCol::State, &[2]).unwrap().as_deref(),
+            Some(&[43][..])
+        );
+        let values: Vec<_> = db
+            .iter(DBCol::State)
+            .map(|item| item.map(|(key, value)| (key.into_vec(), value.into_vec())).unwrap())
+            .collect();
+        assert_eq!(values, [(vec![2], vec![43])]);
+        drop(db);
+
+        // The database can't be opened without the key or with a different one.
+        assert!(RocksDB::open(
+            &path,
+            &StoreConfig::test_config(),
+            Mode::ReadOnly,
+            Temperature::Hot
+        )
+        .is_err());
+        let other_key_file = tmp_dir.path().join("other-key");
+        std::fs::write(&other_key_file, hex::encode([8; 32])).unwrap();
+        let other_config =
+            StoreConfig { encryption_key_file: Some(other_key_file), ..StoreConfig::test_config() };
+        assert!(RocksDB::open(&path, &other_config, Mode::ReadOnly, Temperature::Hot).is_err());
+
+        // Nor can encryption be enabled for an existing database.
+        let path = tmp_dir.path().join("plain");
+        {
+            let db =
+                RocksDB::open(&path, &StoreConfig::test_config(), Mode::Create, Temperature::Hot)
+                    .unwrap();
+            let mut transaction = crate::db::DBTransaction::new();
+            let version = crate::metadata::DB_VERSION.to_string().into_bytes();
+            transaction.set(DBCol::DbVersion, crate::metadata::VERSION_KEY.to_vec(), version);
+            db.write(transaction).unwrap();
+        }
+        assert!(RocksDB::open(&path, &config, Mode::ReadWrite, Temperature::Hot).is_err());
+    }
+
+    #[test]
+    fn test_value_checksums() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let config = StoreConfig { value_checksums: true, ..StoreConfig::test_config() };
+        {
+            let db =
+                RocksDB::open(tmp_dir.path(), &config, Mode::Create, Temperature::Hot).unwrap();
+            let mut transaction = crate::db::DBTransaction::new();
+            transaction.insert(DBCol::Block, vec![1], vec![42]);
+            transaction.update_refcount(DBCol::State, vec![2], vec![43, 1, 0, 0, 0, 0, 0, 0, 0]);
+            db.write(transaction).unwrap();
+        }
+        {
+            let db =
+                RocksDB::open(tmp_dir.path(), &config, Mode::ReadWrite, Temperature::Hot).unwrap();
+            assert_eq!(db.get_raw_bytes(DBCol::Block, &[1]).unwrap().as_deref(), Some(&[42][..]));
+            assert_eq!(
+                db.get_with_rc_stripped(DBCol::State, &[2]).unwrap().as_deref(),
+                Some(&[43][..])
+            );
+        }
+
+        // The database can't be opened without checksums.
+        assert!(RocksDB::open(
+            tmp_dir.path(),
+            &StoreConfig::test_config(),
+            Mode::ReadOnly,
+            Temperature::Hot
+        )
+        .is_err());
+
+        // Overwrite the value bypassing the checksums to simulate corruption.
+        {
+            let db =
+                RocksDB::open(tmp_dir.path(), &config, Mode::ReadWrite, Temperature::Hot).unwrap();
+            let cf_handle = db.cf_handle(DBCol::Block).unwrap();
+            db.db.put_cf(cf_handle, [1], [42, 0, 0, 0, 0]).unwrap();
+        }
+        let db = RocksDB::open(tmp_dir.path(), &config, Mode::ReadOnly, Temperature::Hot).unwrap();
+        let err = db.get_raw_bytes(DBCol::Block, &[1]).unwrap_err();
+        assert_eq!(
+            crate::StoreError::from_io(&err),
+            Some(&crate::StoreError::Corruption { column: DBCol::Block, key: vec![1] })
+        );
+        assert!(db.iter(DBCol::Block).next().unwrap().is_err());
+    }
+
     #[test]
     fn test_delete_range() {
         let store = NodeStorage::test_opener().1.open().unwrap().get_hot_store();
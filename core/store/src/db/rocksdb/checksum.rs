@@ -0,0 +1,121 @@
+//! Checksums of values stored in the database.
+//!
+//! When [`crate::StoreConfig::value_checksums`] is set, a CRC32 checksum of
+//! the key and the value is appended to every value written to RocksDB and
+//! verified whenever the value is read back.  A mismatch is reported as
+//! [`StoreError::Corruption`] rather than handed to the caller as if it was
+//! valid data.
+//!
+//! For reference counted columns the checksum is placed before the reference
+//! count and doesn’t cover it.  RocksDB merges reference counts on its own so
+//! only the payload, which has to be identical in all merged values anyway,
+//! can be protected.
+
+use crate::db::refcount;
+use crate::{DBCol, StoreError};
+use std::io;
+
+/// Length of the checksum attached to values, in bytes.
+const CHECKSUM_LEN: usize = 4;
+
+/// Name of the checksum algorithm recorded in the database metadata.
+pub(crate) const ALGORITHM: &str = "crc32";
+
+fn checksum(key: &[u8], payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(key);
+    hasher.update(payload);
+    hasher.finalize().to_le_bytes()
+}
+
+/// Returns whether values in given column carry checksums.
+///
+/// Database metadata is left as is so that it can be read regardless of the
+/// configuration the database is opened with.
+pub(crate) fn has_checksum(col: DBCol) -> bool {
+    col != DBCol::DbVersion
+}
+
+/// Attaches checksum to a value as written to the database.
+pub(crate) fn add_checksum(col: DBCol, key: &[u8], mut value: Vec<u8>) -> Vec<u8> {
+    if !has_checksum(col) {
+        return value;
+    }
+    if !col.is_rc() {
+        value.extend_from_slice(&checksum(key, &value));
+        return value;
+    }
+    match value.len().checked_sub(8) {
+        // Values with non-positive reference count carry no payload.
+        Some(0) | None => value,
+        Some(len) => {
+            let rc = value.split_off(len);
+            value.extend_from_slice(&checksum(key, &value));
+            value.extend_from_slice(&rc);
+            value
+        }
+    }
+}
+
+/// Verifies and strips the checksum from a value read from the database.
+/// Inverse of [`add_checksum`].
+pub(crate) fn strip_checksum(col: DBCol, key: &[u8], value: &[u8]) -> io::Result<Vec<u8>> {
+    if !has_checksum(col) {
+        return Ok(value.to_vec());
+    }
+    if !col.is_rc() {
+        return verify(col, key, value).map(<[u8]>::to_vec);
+    }
+    match refcount::decode_value_with_rc(value) {
+        (Some(payload), rc) if !payload.is_empty() => {
+            let mut value = verify(col, key, payload)?.to_vec();
+            value.extend_from_slice(&rc.to_le_bytes());
+            Ok(value)
+        }
+        _ => Ok(value.to_vec()),
+    }
+}
+
+fn verify<'a>(col: DBCol, key: &[u8], value: &'a [u8]) -> io::Result<&'a [u8]> {
+    match value.split_last_chunk::<CHECKSUM_LEN>() {
+        Some((payload, stored)) if *stored == checksum(key, payload) => Ok(payload),
+        _ => Err(StoreError::corruption(col, key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_checksum, strip_checksum};
+    use crate::{DBCol, StoreError};
+
+    #[test]
+    fn test_round_trip() {
+        let value = add_checksum(DBCol::Block, b"key", b"value".to_vec());
+        assert_eq!(value.len(), 9);
+        assert_eq!(strip_checksum(DBCol::Block, b"key", &value).unwrap(), b"value");
+
+        let corruption = StoreError::Corruption { column: DBCol::Block, key: b"key".to_vec() };
+        let mut damaged = value.clone();
+        damaged[0] ^= 1;
+        let err = strip_checksum(DBCol::Block, b"key", &damaged).unwrap_err();
+        assert_eq!(StoreError::from_io(&err), Some(&corruption));
+        // A value moved under a different key is also detected.
+        assert!(strip_checksum(DBCol::Block, b"other", &value).is_err());
+        assert!(strip_checksum(DBCol::Block, b"key", b"abc").is_err());
+
+        let value = add_checksum(DBCol::DbVersion, b"VERSION", b"42".to_vec());
+        assert_eq!(value, b"42");
+    }
+
+    #[test]
+    fn test_refcount() {
+        let value = b"value\x02\0\0\0\0\0\0\0".to_vec();
+        let stored = add_checksum(DBCol::State, b"key", value.clone());
+        assert_eq!(&stored[stored.len() - 8..], b"\x02\0\0\0\0\0\0\0");
+        assert_eq!(strip_checksum(DBCol::State, b"key", &stored).unwrap(), value);
+
+        let minus_one = b"\xff\xff\xff\xff\xff\xff\xff\xff".to_vec();
+        assert_eq!(add_checksum(DBCol::State, b"key", minus_one.clone()), minus_one);
+        assert_eq!(strip_checksum(DBCol::State, b"key", &minus_one).unwrap(), minus_one);
+    }
+}
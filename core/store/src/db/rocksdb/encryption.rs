@@ -0,0 +1,215 @@
+//! Encryption of values stored in the database.
+//!
+//! When [`crate::StoreConfig::encryption_key_file`] is set, every value is
+//! encrypted with ChaCha20-Poly1305 before it is handed to RocksDB and
+//! decrypted when read back.  Keys are stored in plain text since RocksDB
+//! needs them to keep columns ordered for iteration.  The column name and the
+//! key are authenticated together with the value so an encrypted value cannot
+//! be moved to a different key without detection.
+//!
+//! The nonce is derived from the column, key and plain-text value rather than
+//! chosen randomly.  That makes encryption deterministic which is required for
+//! reference counted columns: RocksDB merges reference counts of the same
+//! value written more than once and expects the stored bytes to be identical.
+//! The only thing this reveals is whether the same value has been written
+//! under the same key twice.
+
+use crate::db::refcount;
+use crate::DBCol;
+use ring::{aead, hmac};
+use std::io;
+use std::path::Path;
+
+/// Length of the key expected in the key file, in bytes.
+const KEY_LEN: usize = 32;
+
+pub(crate) struct ValueCipher {
+    aead_key: aead::LessSafeKey,
+    nonce_key: hmac::Key,
+    /// Hex-encoded value derived from the key which identifies it without
+    /// revealing it.  Stored in the database to detect a wrong key.
+    key_check: String,
+}
+
+impl ValueCipher {
+    /// Reads the key from given file.
+    ///
+    /// The file must contain the 32-byte key encoded as 64 hex digits.
+    /// Surrounding whitespace is ignored.
+    pub(crate) fn from_key_file(path: &Path) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("unable to read store encryption key from {}: {err}", path.display()),
+            )
+        })?;
+        let key = hex::decode(contents.trim())
+            .ok()
+            .and_then(|key| <[u8; KEY_LEN]>::try_from(key).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "store encryption key in {} must be {KEY_LEN} hex-encoded bytes",
+                        path.display()
+                    ),
+                )
+            })?;
+        Ok(Self::new(&key))
+    }
+
+    pub(crate) fn new(key: &[u8; KEY_LEN]) -> Self {
+        // Derive separate keys for encryption and nonce generation so that the
+        // same secret is never used with two different algorithms.
+        let master = hmac::Key::new(hmac::HMAC_SHA256, key);
+        let aead_key = hmac::sign(&master, b"near-store value encryption");
+        let nonce_key = hmac::sign(&master, b"near-store value nonce");
+        let key_check = hmac::sign(&master, b"near-store key check");
+        let aead_key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, aead_key.as_ref())
+            .expect("HMAC-SHA256 output is a valid ChaCha20-Poly1305 key");
+        Self {
+            aead_key: aead::LessSafeKey::new(aead_key),
+            nonce_key: hmac::Key::new(hmac::HMAC_SHA256, nonce_key.as_ref()),
+            key_check: hex::encode(key_check.as_ref()),
+        }
+    }
+
+    /// Returns the value identifying the key which is stored in the database.
+    pub(crate) fn key_check(&self) -> &str {
+        &self.key_check
+    }
+
+    /// Returns whether values in given column are encrypted.
+    ///
+    /// Database metadata is kept in plain text so that the version and kind of
+    /// the database can be checked before the key is needed.
+    pub(crate) fn is_encrypted(col: DBCol) -> bool {
+        col != DBCol::DbVersion
+    }
+
+    /// Encrypts a value as written to the database.
+    ///
+    /// For reference counted columns, `value` includes the reference count
+    /// which is kept in plain text so that RocksDB can merge it.
+    pub(crate) fn encrypt(&self, col: DBCol, key: &[u8], value: Vec<u8>) -> Vec<u8> {
+        if !Self::is_encrypted(col) {
+            return value;
+        }
+        if !col.is_rc() {
+            return self.seal(col, key, value);
+        }
+        match value.len().checked_sub(8) {
+            // Values with non-positive reference count carry no payload.
+            Some(0) | None => value,
+            Some(len) => {
+                let mut value = value;
+                let rc = value.split_off(len);
+                let mut sealed = self.seal(col, key, value);
+                sealed.extend_from_slice(&rc);
+                sealed
+            }
+        }
+    }
+
+    /// Decrypts a value read from the database.  Inverse of [`Self::encrypt`].
+    pub(crate) fn decrypt(&self, col: DBCol, key: &[u8], value: &[u8]) -> io::Result<Vec<u8>> {
+        if !Self::is_encrypted(col) {
+            return Ok(value.to_vec());
+        }
+        if !col.is_rc() {
+            return self.open(col, key, value);
+        }
+        match refcount::decode_value_with_rc(value) {
+            (Some(payload), rc) if !payload.is_empty() => {
+                let mut value = self.open(col, key, payload)?;
+                value.extend_from_slice(&rc.to_le_bytes());
+                Ok(value)
+            }
+            _ => Ok(value.to_vec()),
+        }
+    }
+
+    fn seal(&self, col: DBCol, key: &[u8], mut value: Vec<u8>) -> Vec<u8> {
+        let aad = Self::aad(col, key);
+        let mut nonce_ctx = hmac::Context::with_key(&self.nonce_key);
+        nonce_ctx.update(&aad);
+        nonce_ctx.update(&value);
+        let mut nonce = [0; aead::NONCE_LEN];
+        nonce.copy_from_slice(&nonce_ctx.sign().as_ref()[..aead::NONCE_LEN]);
+
+        self.aead_key
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(aad),
+                &mut value,
+            )
+            .expect("value too large to encrypt");
+        [&nonce[..], &value].concat()
+    }
+
+    fn open(&self, col: DBCol, key: &[u8], value: &[u8]) -> io::Result<Vec<u8>> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unable to decrypt value in column {col}; \
+                     wrong encryption key or the database was not created with encryption"
+                ),
+            )
+        };
+        if value.len() < aead::NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, ciphertext) = value.split_at(aead::NONCE_LEN);
+        let nonce = aead::Nonce::assume_unique_for_key(nonce.try_into().unwrap());
+        let mut buffer = ciphertext.to_vec();
+        let len = self
+            .aead_key
+            .open_in_place(nonce, aead::Aad::from(Self::aad(col, key)), &mut buffer)
+            .map_err(|_| invalid())?
+            .len();
+        buffer.truncate(len);
+        Ok(buffer)
+    }
+
+    fn aad(col: DBCol, key: &[u8]) -> Vec<u8> {
+        let col: &str = col.into();
+        [col.as_bytes(), b"\0", key].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueCipher;
+    use crate::DBCol;
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = ValueCipher::new(&[7; 32]);
+        let encrypted = cipher.encrypt(DBCol::Block, b"key", b"value".to_vec());
+        assert_ne!(encrypted.as_slice(), b"value");
+        assert_eq!(cipher.decrypt(DBCol::Block, b"key", &encrypted).unwrap(), b"value");
+        // The value is bound to the key and column it was written under.
+        assert!(cipher.decrypt(DBCol::Block, b"other", &encrypted).is_err());
+        assert!(cipher.decrypt(DBCol::BlockHeader, b"key", &encrypted).is_err());
+        // And can't be read with another key.
+        assert!(ValueCipher::new(&[8; 32]).decrypt(DBCol::Block, b"key", &encrypted).is_err());
+
+        let encrypted = cipher.encrypt(DBCol::DbVersion, b"VERSION", b"42".to_vec());
+        assert_eq!(encrypted.as_slice(), b"42");
+    }
+
+    #[test]
+    fn test_refcount() {
+        let cipher = ValueCipher::new(&[7; 32]);
+        let value = b"value\x02\0\0\0\0\0\0\0".to_vec();
+        let encrypted = cipher.encrypt(DBCol::State, b"key", value.clone());
+        assert_eq!(&encrypted[encrypted.len() - 8..], b"\x02\0\0\0\0\0\0\0");
+        assert_eq!(encrypted, cipher.encrypt(DBCol::State, b"key", value.clone()));
+        assert_eq!(cipher.decrypt(DBCol::State, b"key", &encrypted).unwrap(), value);
+
+        let minus_one = b"\xff\xff\xff\xff\xff\xff\xff\xff".to_vec();
+        assert_eq!(cipher.encrypt(DBCol::State, b"key", minus_one.clone()), minus_one);
+        assert_eq!(cipher.decrypt(DBCol::State, b"key", &minus_one).unwrap(), minus_one);
+    }
+}
@@ -3,7 +3,7 @@ use std::io;
 use std::ops::Bound;
 use std::sync::{Arc, RwLock};
 
-use crate::db::{refcount, DBIterator, DBOp, DBSlice, DBTransaction, Database};
+use crate::db::{refcount, ttl, DBIterator, DBOp, DBSlice, DBTransaction, Database};
 use crate::{DBCol, StoreStatistics};
 
 /// An in-memory database intended for tests and IO-agnostic estimations.
@@ -34,7 +34,9 @@ impl TestDB {
 
 impl Database for TestDB {
     fn get_raw_bytes(&self, col: DBCol, key: &[u8]) -> io::Result<Option<DBSlice<'_>>> {
-        Ok(self.db.read().unwrap()[col].get(key).cloned().map(DBSlice::from_vec))
+        let db = self.db.read().unwrap();
+        let value = db[col].get(key).and_then(|value| ttl::strip_expiry(col, value));
+        Ok(value.map(|value| DBSlice::from_vec(value.to_vec())))
     }
 
     fn iter<'a>(&'a self, col: DBCol) -> DBIterator<'a> {
@@ -47,7 +49,7 @@ impl Database for TestDB {
             .clone()
             .into_iter()
             .map(|(k, v)| Ok((k.into_boxed_slice(), v.into_boxed_slice())));
-        Box::new(iterator)
+        ttl::iter_with_ttl_logic(col, iterator)
     }
 
     fn iter_prefix<'a>(&'a self, col: DBCol, key_prefix: &'a [u8]) -> DBIterator<'a> {
@@ -56,7 +58,7 @@ impl Database for TestDB {
             .take_while(move |(k, _)| k.starts_with(&key_prefix))
             .map(|(k, v)| Ok((k.clone().into_boxed_slice(), v.clone().into_boxed_slice())))
             .collect::<Vec<io::Result<_>>>();
-        refcount::iter_with_rc_logic(col, iterator.into_iter())
+        refcount::iter_with_rc_logic(col, ttl::iter_with_ttl_logic(col, iterator.into_iter()))
     }
 
     fn iter_range<'a>(
@@ -72,7 +74,7 @@ impl Database for TestDB {
             .range((lower, upper))
             .map(|(k, v)| Ok((k.clone().into_boxed_slice(), v.clone().into_boxed_slice())))
             .collect::<Vec<io::Result<_>>>();
-        refcount::iter_with_rc_logic(col, iterator.into_iter())
+        refcount::iter_with_rc_logic(col, ttl::iter_with_ttl_logic(col, iterator.into_iter()))
     }
 
     fn write(&self, transaction: DBTransaction) -> io::Result<()> {
@@ -80,7 +82,7 @@ impl Database for TestDB {
         for op in transaction.ops {
             match op {
                 DBOp::Set { col, key, value } => {
-                    db[col].insert(key, value);
+                    db[col].insert(key, ttl::add_expiry(col, value));
                 }
                 DBOp::Insert { col, key, value } => {
                     if cfg!(debug_assertions) {
@@ -88,7 +90,7 @@ impl Database for TestDB {
                             super::assert_no_overwrite(col, &key, &value, &*old_value)
                         }
                     }
-                    db[col].insert(key, value);
+                    db[col].insert(key, ttl::add_expiry(col, value));
                 }
                 DBOp::UpdateRefcount { col, key, value } => {
                     let existing = db[col].get(&key).map(Vec::as_slice);
@@ -0,0 +1,126 @@
+//! Functions for handling columns with time-based expiry.
+//!
+//! Some of the columns in the database hold ephemeral data which is only
+//! useful for a limited time.  Those are the ones for which [`DBCol::ttl`]
+//! returns a duration.  Rather than having to be garbage collected explicitly,
+//! values in such columns expire on their own.
+//!
+//! The expiry time is stored together with the value by simply attaching
+//! little-endian encoded 64-bit number of seconds since Unix epoch at the end
+//! of it.  Expired values are filtered out when reading and RocksDB removes
+//! them from disk during compaction.
+
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rocksdb::compaction_filter::Decision;
+
+use crate::db::RocksDB;
+use crate::DBCol;
+
+/// Returns current time in seconds since Unix epoch.
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+/// Attaches expiry time to a value being written to given column.
+///
+/// Values of columns without TTL are returned unchanged.
+pub fn add_expiry(col: DBCol, value: Vec<u8>) -> Vec<u8> {
+    add_expiry_at(col, value, now())
+}
+
+fn add_expiry_at(col: DBCol, mut value: Vec<u8>, now: u64) -> Vec<u8> {
+    if let Some(ttl) = col.ttl() {
+        value.extend_from_slice(&now.saturating_add(ttl.as_secs()).to_le_bytes());
+    }
+    value
+}
+
+/// Extracts expiry time from raw value and returns it along with the value.
+///
+/// Returns `None` if `bytes` are too short to fit the expiry time.
+fn decode_value_with_expiry(bytes: &[u8]) -> Option<(&[u8], u64)> {
+    let (value, expiry) = bytes.split_last_chunk::<8>()?;
+    Some((value, u64::from_le_bytes(*expiry)))
+}
+
+/// Strips expiry time from a value read from given column.
+///
+/// Returns `None` if the value has expired.  Values of columns without TTL are
+/// returned unchanged.
+pub fn strip_expiry(col: DBCol, bytes: &[u8]) -> Option<&[u8]> {
+    strip_expiry_at(col, bytes, now())
+}
+
+fn strip_expiry_at(col: DBCol, bytes: &[u8], now: u64) -> Option<&[u8]> {
+    if col.ttl().is_none() {
+        return Some(bytes);
+    }
+    let (value, expiry) = decode_value_with_expiry(bytes)?;
+    (expiry > now).then_some(value)
+}
+
+/// Iterator skips expired values and strips expiry time from the rest.
+pub(crate) fn iter_with_ttl_logic<'a>(
+    col: DBCol,
+    iterator: impl Iterator<Item = io::Result<(Box<[u8]>, Box<[u8]>)>> + 'a,
+) -> crate::db::DBIterator<'a> {
+    if col.ttl().is_none() {
+        return Box::new(iterator);
+    }
+    let now = now();
+    Box::new(iterator.filter_map(move |item| match item {
+        Err(err) => Some(Err(err)),
+        Ok((key, value)) => strip_expiry_at(col, &value, now).map(|value| Ok((key, value.into()))),
+    }))
+}
+
+impl RocksDB {
+    /// Compaction filter for columns with TTL.  Removes expired values.
+    pub(crate) fn expired_value_compaction_filter(
+        _level: u32,
+        _key: &[u8],
+        value: &[u8],
+    ) -> Decision {
+        match decode_value_with_expiry(value) {
+            Some((_, expiry)) if expiry > now() => Decision::Keep,
+            _ => Decision::Remove,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const COL: DBCol = DBCol::AccountAnnouncements;
+
+    #[test]
+    fn expiry() {
+        let ttl = COL.ttl().unwrap().as_secs();
+        let value = add_expiry_at(COL, b"foo".to_vec(), 1000);
+        assert_eq!(value, [&b"foo"[..], &(1000 + ttl).to_le_bytes()].concat());
+        assert_eq!(strip_expiry_at(COL, &value, 1000), Some(&b"foo"[..]));
+        assert_eq!(strip_expiry_at(COL, &value, 1000 + ttl - 1), Some(&b"foo"[..]));
+        assert_eq!(strip_expiry_at(COL, &value, 1000 + ttl), None);
+        assert_eq!(strip_expiry_at(COL, b"foo", 1000), None);
+    }
+
+    #[test]
+    fn no_ttl() {
+        let value = add_expiry_at(DBCol::Block, b"foo".to_vec(), 1000);
+        assert_eq!(value, b"foo");
+        assert_eq!(strip_expiry_at(DBCol::Block, &value, u64::MAX), Some(&b"foo"[..]));
+    }
+
+    #[test]
+    fn iter() {
+        let items = [(b"a", add_expiry(COL, b"foo".to_vec())), (b"b", b"bar".to_vec())]
+            .map(|(key, value)| Ok((key.to_vec().into_boxed_slice(), value.into_boxed_slice())));
+        let got: Vec<_> = iter_with_ttl_logic(COL, items.into_iter())
+            .map(|item| item.map(|(key, value)| (key.into_vec(), value.into_vec())).unwrap())
+            .collect();
+        assert_eq!(got, [(b"a".to_vec(), b"foo".to_vec())]);
+    }
+}
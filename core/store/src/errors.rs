@@ -0,0 +1,52 @@
+use crate::DBCol;
+use near_fmt::StorageKey;
+use std::io;
+
+/// Error reading data from the store which is not caused by failing I/O.
+///
+/// Store methods return [`io::Error`] and errors of this type are carried
+/// inside of them with [`io::ErrorKind::InvalidData`] kind.  Use
+/// [`StoreError::from_io`] to tell them apart from other failures.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    /// Value stored under the key is damaged: either it doesn’t match its
+    /// checksum or it can’t be deserialized.  Unlike a missing value, this
+    /// indicates the database needs repairing.
+    #[error("corrupted value in column {column} under key {}", StorageKey(key))]
+    Corruption { column: DBCol, key: Vec<u8> },
+}
+
+impl StoreError {
+    pub(crate) fn corruption(column: DBCol, key: &[u8]) -> io::Error {
+        Self::Corruption { column, key: key.to_vec() }.into()
+    }
+
+    /// Returns the store error carried by given I/O error, if any.
+    pub fn from_io(err: &io::Error) -> Option<&Self> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl From<StoreError> for io::Error {
+    fn from(err: StoreError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StoreError;
+    use crate::DBCol;
+    use std::io;
+
+    #[test]
+    fn test_from_io() {
+        let err = StoreError::corruption(DBCol::Block, b"key");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            StoreError::from_io(&err),
+            Some(&StoreError::Corruption { column: DBCol::Block, key: b"key".to_vec() })
+        );
+        assert_eq!(StoreError::from_io(&io::Error::other("other")), None);
+    }
+}
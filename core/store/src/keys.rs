@@ -0,0 +1,144 @@
+//! Typed keys of database columns.
+//!
+//! Rather than building keys from raw byte slices at every call site, readers
+//! and writers of a column use the key type defined for it here together with
+//! [`crate::Store::get_ser_by_key`] and the `*_by_key` methods of
+//! [`crate::StoreUpdate`].  The key type determines the column, so passing
+//! a key of one column to another or getting the format of a composite key
+//! wrong is a compile error rather than a silent miss.
+
+use crate::DBCol;
+use near_primitives::epoch_manager::AGGREGATOR_KEY;
+use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::ChunkHash;
+use near_primitives::types::EpochId;
+use std::borrow::Cow;
+
+/// Key of a row in a specific column.
+pub trait ColumnKey {
+    /// Column the key refers to.
+    const COLUMN: DBCol;
+
+    /// Returns the key as stored in the database.
+    fn key_bytes(&self) -> Cow<'_, [u8]>;
+
+    /// Same as [`Self::COLUMN`], for use with `impl ColumnKey` arguments.
+    fn column(&self) -> DBCol {
+        Self::COLUMN
+    }
+}
+
+/// Key in [`DBCol::EpochInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpochInfoKey<'a> {
+    /// Information about given epoch.
+    Epoch(&'a EpochId),
+    /// Epoch info aggregator of the epoch manager.
+    Aggregator,
+}
+
+impl<'a> EpochInfoKey<'a> {
+    pub fn epoch(epoch_id: &'a EpochId) -> Self {
+        Self::Epoch(epoch_id)
+    }
+}
+
+impl ColumnKey for EpochInfoKey<'_> {
+    const COLUMN: DBCol = DBCol::EpochInfo;
+
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            Self::Epoch(epoch_id) => Cow::Borrowed(epoch_id.as_ref()),
+            Self::Aggregator => Cow::Borrowed(AGGREGATOR_KEY),
+        }
+    }
+}
+
+/// Key in [`DBCol::EpochValidatorInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EpochValidatorInfoKey<'a>(pub &'a EpochId);
+
+impl ColumnKey for EpochValidatorInfoKey<'_> {
+    const COLUMN: DBCol = DBCol::EpochValidatorInfo;
+
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.0.as_ref())
+    }
+}
+
+/// Key in [`DBCol::EpochStart`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EpochStartKey<'a>(pub &'a EpochId);
+
+impl ColumnKey for EpochStartKey<'_> {
+    const COLUMN: DBCol = DBCol::EpochStart;
+
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.0.as_ref())
+    }
+}
+
+/// Key in [`DBCol::BlockInfo`]: hash of the block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockInfoKey<'a>(pub &'a CryptoHash);
+
+impl ColumnKey for BlockInfoKey<'_> {
+    const COLUMN: DBCol = DBCol::BlockInfo;
+
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.0.as_ref())
+    }
+}
+
+/// Key in [`DBCol::PartialChunks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartialChunkKey<'a>(pub &'a ChunkHash);
+
+impl ColumnKey for PartialChunkKey<'_> {
+    const COLUMN: DBCol = DBCol::PartialChunks;
+
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.0.as_ref())
+    }
+}
+
+/// Key in [`DBCol::PartialChunkParts`].
+///
+/// The part ordinal is encoded as big-endian so that parts of a chunk are
+/// iterated in order and all of them can be found by the chunk hash prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkPartKey<'a> {
+    pub chunk_hash: &'a ChunkHash,
+    pub part_ord: u64,
+}
+
+impl ColumnKey for ChunkPartKey<'_> {
+    const COLUMN: DBCol = DBCol::PartialChunkParts;
+
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned([self.chunk_hash.as_ref(), &self.part_ord.to_be_bytes()[..]].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkPartKey, ColumnKey, EpochInfoKey};
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::sharding::ChunkHash;
+    use near_primitives::types::EpochId;
+
+    #[test]
+    fn test_key_bytes() {
+        let hash = CryptoHash::hash_bytes(b"foo");
+        let epoch_id = EpochId(hash);
+        assert_eq!(EpochInfoKey::epoch(&epoch_id).key_bytes().as_ref(), hash.as_bytes());
+        assert_eq!(EpochInfoKey::Aggregator.key_bytes().as_ref(), b"AGGREGATOR");
+
+        let chunk_hash = ChunkHash(hash);
+        let key = ChunkPartKey { chunk_hash: &chunk_hash, part_ord: 258 };
+        assert_eq!(
+            key.key_bytes().as_ref(),
+            [&hash.as_bytes()[..], &[0, 0, 0, 0, 0, 0, 1, 2]].concat()
+        );
+    }
+}
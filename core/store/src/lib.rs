@@ -21,7 +21,8 @@ pub use db::{
     GENESIS_STATE_ROOTS_KEY, HEADER_HEAD_KEY, HEAD_KEY, LARGEST_TARGET_HEIGHT_KEY,
     LATEST_KNOWN_KEY, STATE_SNAPSHOT_KEY, STATE_SYNC_DUMP_KEY, TAIL_KEY,
 };
-use metadata::{DbKind, DbVersion, KIND_KEY, VERSION_KEY};
+use keys::ColumnKey;
+use metadata::{DbKind, DbVersion, DB_VERSION, KIND_KEY, VERSION_KEY};
 use near_crypto::PublicKey;
 use near_fmt::{AbbrBytes, StorageKey};
 use near_primitives::account::{AccessKey, Account};
@@ -50,8 +51,10 @@ mod columns;
 pub mod config;
 pub mod contract;
 pub mod db;
+mod errors;
 pub mod flat;
 pub mod genesis;
+pub mod keys;
 pub mod metadata;
 pub mod metrics;
 pub mod migrations;
@@ -62,6 +65,7 @@ pub mod test_utils;
 pub mod trie;
 
 pub use crate::config::{Mode, StoreConfig};
+pub use crate::errors::StoreError;
 pub use crate::opener::{
     checkpoint_hot_storage_and_cleanup_columns, StoreMigrator, StoreOpener, StoreOpenerError,
 };
@@ -296,6 +300,31 @@ impl Store {
         Self { storage }
     }
 
+    /// Opens the hot database at `path` for reading only.
+    ///
+    /// This is meant for tools which inspect the database of a node which may
+    /// be running at the same time.  The database lock is not taken, the data
+    /// is seen as of the time of opening and all writes fail.  See
+    /// [`crate::db::RocksDB::open_secondary`].
+    ///
+    /// `config` should be the store configuration of the node so that the
+    /// values are read the way the node writes them.
+    ///
+    /// Fails if the database doesn’t exist or has a version different than
+    /// [`DB_VERSION`] since no migrations can be performed.
+    pub fn open_read_only(path: &Path, config: &StoreConfig) -> Result<Self, StoreOpenerError> {
+        let metadata = crate::db::RocksDB::get_metadata(path, config)?
+            .ok_or(StoreOpenerError::DbDoesNotExist)?;
+        if metadata.version != DB_VERSION {
+            return Err(StoreOpenerError::DbVersionMismatchOnRead {
+                got: metadata.version,
+                want: DB_VERSION,
+            });
+        }
+        let db = crate::db::RocksDB::open_secondary(path, config, Temperature::Hot)?;
+        Ok(Self { storage: Arc::new(db) })
+    }
+
     /// Fetches value from given column.
     ///
     /// If the key does not exist in the column returns `None`.  Otherwise
@@ -318,8 +347,24 @@ impl Store {
         Ok(value)
     }
 
+    /// Fetches value from given column and deserializes it.
+    ///
+    /// Values which can’t be deserialized are reported as
+    /// [`StoreError::Corruption`].
     pub fn get_ser<T: BorshDeserialize>(&self, column: DBCol, key: &[u8]) -> io::Result<Option<T>> {
-        self.get(column, key)?.as_deref().map(T::try_from_slice).transpose()
+        self.get(column, key)?
+            .as_deref()
+            .map(|value| T::try_from_slice(value).map_err(|_| StoreError::corruption(column, key)))
+            .transpose()
+    }
+
+    /// Fetches value stored under a typed key and deserializes it.  See
+    /// [`keys`].
+    pub fn get_ser_by_key<T: BorshDeserialize>(
+        &self,
+        key: &impl ColumnKey,
+    ) -> io::Result<Option<T>> {
+        self.get_ser(key.column(), &key.key_bytes())
     }
 
     pub fn exists(&self, column: DBCol, key: &[u8]) -> io::Result<bool> {
@@ -363,9 +408,12 @@ impl Store {
         col: DBCol,
         key_prefix: &'a [u8],
     ) -> impl Iterator<Item = io::Result<(Box<[u8]>, T)>> + 'a {
-        self.storage
-            .iter_prefix(col, key_prefix)
-            .map(|item| item.and_then(|(key, value)| Ok((key, T::try_from_slice(value.as_ref())?))))
+        self.storage.iter_prefix(col, key_prefix).map(move |item| {
+            item.and_then(|(key, value)| match T::try_from_slice(value.as_ref()) {
+                Ok(value) => Ok((key, value)),
+                Err(_) => Err(StoreError::corruption(col, &key)),
+            })
+        })
     }
 
     /// Saves state (`State` and `FlatState` columns) to given file.
@@ -452,6 +500,17 @@ impl Store {
     }
 }
 
+/// Largest [`StoreUpdate`], in bytes of keys and values, which can be committed
+/// atomically.
+///
+/// RocksDB write batches can’t exceed 4 GiB and ones anywhere near that size
+/// cause large memory spikes when written.  Larger updates have to be committed
+/// with [`StoreUpdate::commit_in_batches`].
+pub const MAX_ATOMIC_COMMIT_BYTES: usize = 1 << 30;
+
+/// Size of the batches [`StoreUpdate::commit_in_batches`] splits updates into.
+pub const COMMIT_BATCH_BYTES: usize = 64 << 20;
+
 /// Keeps track of current changes to the database and can commit all of them to the database.
 pub struct StoreUpdate {
     transaction: DBTransaction,
@@ -505,6 +564,16 @@ impl StoreUpdate {
         Ok(())
     }
 
+    /// Same as [`Self::insert_ser`] with the column and key given by a typed
+    /// key.  See [`keys`].
+    pub fn insert_ser_by_key<T: BorshSerialize>(
+        &mut self,
+        key: &impl ColumnKey,
+        value: &T,
+    ) -> io::Result<()> {
+        self.insert_ser(key.column(), &key.key_bytes(), value)
+    }
+
     /// Inserts a new reference-counted value or increases its reference count
     /// if it’s already there.
     ///
@@ -583,6 +652,16 @@ impl StoreUpdate {
         Ok(())
     }
 
+    /// Same as [`Self::set_ser`] with the column and key given by a typed key.
+    /// See [`keys`].
+    pub fn set_ser_by_key<T: BorshSerialize + ?Sized>(
+        &mut self,
+        key: &impl ColumnKey,
+        value: &T,
+    ) -> io::Result<()> {
+        self.set_ser(key.column(), &key.key_bytes(), value)
+    }
+
     /// Modify raw value stored in the database, without doing any sanity checks
     /// for ref counts.
     ///
@@ -602,6 +681,12 @@ impl StoreUpdate {
         self.transaction.delete(column, key.to_vec());
     }
 
+    /// Same as [`Self::delete`] with the column and key given by a typed key.
+    /// See [`keys`].
+    pub fn delete_by_key(&mut self, key: &impl ColumnKey) {
+        self.delete(key.column(), &key.key_bytes())
+    }
+
     pub fn delete_all(&mut self, column: DBCol) {
         self.transaction.delete_all(column);
     }
@@ -620,6 +705,15 @@ impl StoreUpdate {
         self.transaction.merge(other.transaction)
     }
 
+    /// Returns total size of keys and values in the update.
+    pub fn size_bytes(&self) -> usize {
+        self.transaction.ops.iter().map(DBOp::bytes).sum()
+    }
+
+    /// Commits the update atomically.
+    ///
+    /// Fails without writing anything if the update is larger than
+    /// [`MAX_ATOMIC_COMMIT_BYTES`].
     #[tracing::instrument(
         level = "trace",
         target = "store::update",
@@ -660,13 +754,21 @@ impl StoreUpdate {
             "Transaction overwrites itself: {:?}",
             self
         );
+        let total_bytes = self.size_bytes();
+        if total_bytes > MAX_ATOMIC_COMMIT_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "store update of {total_bytes} bytes exceeds the limit of \
+                     {MAX_ATOMIC_COMMIT_BYTES} bytes for atomic commits"
+                ),
+            ));
+        }
         let span = tracing::Span::current();
         if !span.is_disabled() {
             let [mut insert_count, mut set_count, mut update_rc_count] = [0u64; 3];
             let [mut delete_count, mut delete_all_count, mut delete_range_count] = [0u64; 3];
-            let mut total_bytes = 0;
             for op in &self.transaction.ops {
-                total_bytes += op.bytes();
                 let count = match op {
                     DBOp::Set { .. } => &mut set_count,
                     DBOp::Insert { .. } => &mut insert_count,
@@ -735,6 +837,38 @@ impl StoreUpdate {
         }
         self.storage.write(self.transaction)
     }
+
+    /// Commits the update, splitting it into batches of about
+    /// [`COMMIT_BATCH_BYTES`] if it’s larger than that.
+    ///
+    /// An update which fits in a single batch is committed atomically, same as
+    /// with [`Self::commit`].  Otherwise, the batches are committed one after
+    /// another in the order the operations were added to the update.  Each
+    /// batch is atomic but the update as a whole is not: if committing fails or
+    /// the node crashes half way through, only some prefix of the operations is
+    /// persisted.  Use this only for updates which leave the database
+    /// consistent after any such prefix.
+    ///
+    /// A single operation larger than [`MAX_ATOMIC_COMMIT_BYTES`] still fails.
+    pub fn commit_in_batches(self) -> io::Result<()> {
+        self.commit_in_batches_of(COMMIT_BATCH_BYTES)
+    }
+
+    fn commit_in_batches_of(self, batch_bytes: usize) -> io::Result<()> {
+        let Self { transaction, storage } = self;
+        let mut batch = StoreUpdate::new(Arc::clone(&storage));
+        let mut size = 0;
+        for op in transaction.ops {
+            let op_bytes = op.bytes();
+            if size > 0 && size + op_bytes > batch_bytes {
+                std::mem::replace(&mut batch, StoreUpdate::new(Arc::clone(&storage))).commit()?;
+                size = 0;
+            }
+            size += op_bytes;
+            batch.transaction.ops.push(op);
+        }
+        batch.commit()
+    }
 }
 
 impl fmt::Debug for StoreUpdate {
@@ -1188,6 +1322,25 @@ mod tests {
         assert_eq!(store.get(DBCol::State, &[1]).unwrap(), None);
     }
 
+    #[test]
+    fn test_commit_in_batches() {
+        let store = crate::test_utils::create_test_store();
+        let mut store_update = store.store_update();
+        for key in 0..10u8 {
+            store_update.set(DBCol::BlockMisc, &[key], &[key; 10]);
+        }
+        // Operations on the same key in different batches are applied in order.
+        store_update.delete(DBCol::BlockMisc, &[3]);
+        store_update.commit_in_batches_of(25).unwrap();
+        for key in 0..10u8 {
+            let expected = (key != 3).then(|| vec![key; 10]);
+            assert_eq!(
+                store.get(DBCol::BlockMisc, &[key]).unwrap().map(|value| value.to_vec()),
+                expected
+            );
+        }
+    }
+
     #[test]
     fn clear_column_rocksdb() {
         let (_tmp_dir, opener) = NodeStorage::test_opener();
@@ -2,7 +2,7 @@
 pub type DbVersion = u32;
 
 /// Current version of the database.
-pub const DB_VERSION: DbVersion = 40;
+pub const DB_VERSION: DbVersion = 42;
 
 /// Database version at which point DbKind was introduced.
 const DB_VERSION_WITH_KIND: DbVersion = 34;
@@ -22,6 +22,21 @@ pub(super) const VERSION_KEY: &[u8; 7] = b"VERSION";
 /// The key holds a [`DbKind`] value serialised to a string.
 pub(super) const KIND_KEY: &[u8; 4] = b"KIND";
 
+/// Key for the encryption entry in DBCol::DbVersion.
+///
+/// Present only if values in the database are encrypted, see
+/// [`crate::StoreConfig::encryption_key_file`].  The key holds a value derived
+/// from the encryption key which lets a wrong key be detected when opening the
+/// database.
+pub(super) const ENCRYPTION_KEY: &[u8; 10] = b"ENCRYPTION";
+
+/// Key for the value checksums entry in DBCol::DbVersion.
+///
+/// Present only if values in the database carry checksums, see
+/// [`crate::StoreConfig::value_checksums`].  The key holds the name of the
+/// checksum algorithm.
+pub(super) const CHECKSUMS_KEY: &[u8; 9] = b"CHECKSUMS";
+
 /// Describes what kind the storage is.
 #[derive(
     Clone, Copy, Debug, Eq, PartialEq, strum::Display, strum::EnumString, strum::IntoStaticStr,
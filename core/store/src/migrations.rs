@@ -1,9 +1,12 @@
+use crate::adapter::chunk_store::with_parts;
+use crate::keys::ChunkPartKey;
 use crate::metadata::DbKind;
 use crate::{DBCol, Store, StoreUpdate};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_primitives::epoch_manager::EpochSummary;
 use near_primitives::epoch_manager::AGGREGATOR_KEY;
 use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::PartialEncodedChunk;
 use near_primitives::state::FlatStateValue;
 use near_primitives::transaction::{ExecutionOutcomeWithIdAndProof, ExecutionOutcomeWithProof};
 use near_primitives::types::{
@@ -358,3 +361,52 @@ pub fn migrate_39_to_40(store: &Store) -> anyhow::Result<()> {
     update.commit()?;
     Ok(())
 }
+
+/// Migrates the database from version 40 to 41.
+///
+/// Values in the AccountAnnouncements column now have their expiry time
+/// attached.  The announcements are ephemeral and get re-broadcast every epoch
+/// so rather than rewriting them, the column is simply cleared.
+pub fn migrate_40_to_41(store: &Store) -> anyhow::Result<()> {
+    let mut update = store.store_update();
+    update.delete_all(DBCol::AccountAnnouncements);
+    update.commit()?;
+    Ok(())
+}
+
+/// Migrates the database from version 41 to 42.
+///
+/// Moves parts of the partial chunks out of PartialChunks column into the new
+/// PartialChunkParts column.
+pub fn migrate_41_to_42(store: &Store) -> anyhow::Result<()> {
+    let _span =
+        tracing::info_span!(target: "migrations", "Moving partial chunk parts to PartialChunkParts column").entered();
+    let mut update = store.store_update();
+    let mut num_migrated = 0;
+    for result in store.iter(DBCol::PartialChunks) {
+        let (key, value) = result?;
+        let partial_chunk = PartialEncodedChunk::try_from_slice(&value)?;
+        if partial_chunk.parts().is_empty() {
+            continue;
+        }
+        let chunk_hash = partial_chunk.chunk_hash();
+        for part in partial_chunk.parts() {
+            update.insert_ser_by_key(
+                &ChunkPartKey { chunk_hash: &chunk_hash, part_ord: part.part_ord },
+                part,
+            )?;
+        }
+        // PartialChunks is an insert-only column so overwriting the value
+        // needs to bypass the sanity checks.
+        let partial_chunk = with_parts(partial_chunk, vec![]);
+        update.set_raw_bytes(DBCol::PartialChunks, &key, &borsh::to_vec(&partial_chunk)?);
+
+        num_migrated += 1;
+        if num_migrated % 1000 == 0 {
+            std::mem::replace(&mut update, store.store_update()).commit()?;
+            info!(target: "migrations", num_migrated, "Migrated partial chunks");
+        }
+    }
+    update.commit()?;
+    Ok(())
+}
@@ -228,6 +228,13 @@ impl<'a> StoreOpener<'a> {
         &self.hot.path
     }
 
+    /// Returns path to the cold RocksDB database if cold storage is configured.
+    ///
+    /// Does not check whether the database actually exists.
+    pub fn cold_path(&self) -> Option<&std::path::Path> {
+        self.cold.as_ref().map(|cold| cold.path.as_path())
+    }
+
     #[cfg(test)]
     pub(crate) fn config(&self) -> &StoreConfig {
         self.hot.config
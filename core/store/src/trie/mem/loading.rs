@@ -10,6 +10,7 @@ use crate::{DBCol, NibbleSlice, Store};
 use near_primitives::errors::StorageError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::{get_block_shard_uid, ShardUId};
+use near_primitives::state::FlatStateValue;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{BlockHeight, StateRoot};
 use std::collections::BTreeSet;
@@ -84,6 +85,29 @@ fn load_memtrie_single_thread(
     Ok((arena, root_id))
 }
 
+/// Computes the root of the trie containing exactly the given entries, which
+/// must be sorted by key, e.g. as returned by a flat storage iterator. The trie
+/// is built in memory and dropped as soon as the root is known, so this can be
+/// used to check flat storage against the chain without reading trie nodes.
+pub fn compute_state_root_from_sorted_entries<E>(
+    shard_uid: ShardUId,
+    entries: impl IntoIterator<Item = Result<(Vec<u8>, FlatStateValue), E>>,
+) -> Result<StateRoot, StorageError>
+where
+    StorageError: From<E>,
+{
+    let mut arena = STArena::new(shard_uid.to_string());
+    let mut recon = TrieConstructor::new(&mut arena);
+    for item in entries {
+        let (key, value) = item?;
+        recon.add_leaf(NibbleSlice::new(&key), value);
+    }
+    Ok(match recon.finalize() {
+        Some(root_id) => root_id.as_ptr(arena.memory()).view().node_hash(),
+        None => StateRoot::default(),
+    })
+}
+
 fn get_state_root(
     store: &Store,
     block_hash: CryptoHash,
@@ -177,7 +201,7 @@ pub fn load_trie_from_flat_state_and_delta(
 
 #[cfg(test)]
 mod tests {
-    use super::load_trie_from_flat_state_and_delta;
+    use super::{compute_state_root_from_sorted_entries, load_trie_from_flat_state_and_delta};
     use crate::adapter::{StoreAdapter, StoreUpdateAdapter};
     use crate::flat::test_utils::MockChain;
     use crate::flat::{BlockInfo, FlatStorageReadyStatus, FlatStorageStatus};
@@ -215,6 +239,11 @@ mod tests {
         let state_root = test_populate_trie(&shard_tries, &Trie::EMPTY_ROOT, shard_uid, changes);
 
         eprintln!("Trie and flat storage populated");
+        let flat_store = shard_tries.store().store().flat_store();
+        let flat_state_root =
+            compute_state_root_from_sorted_entries(shard_uid, flat_store.iter(shard_uid)).unwrap();
+        assert_eq!(flat_state_root, state_root);
+
         let in_memory_trie = load_trie_from_flat_state(
             &shard_tries.store().store(),
             shard_uid,
@@ -24,7 +24,9 @@ use near_client::test_utils::test_loop::test_loop_sync_actor_maker;
 use near_client::{Client, PartialWitnessActor, SyncAdapter, ViewClientActorInner};
 use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::{EpochManager, EpochManagerAdapter};
-use near_network::test_loop::{TestLoopNetworkSharedState, TestLoopPeerManagerActor};
+use near_network::test_loop::{
+    NetworkConditions, TestLoopNetworkSharedState, TestLoopPeerManagerActor,
+};
 use near_parameters::RuntimeConfigStore;
 use near_primitives::epoch_manager::EpochConfigStore;
 use near_primitives::network::PeerId;
@@ -64,6 +66,8 @@ pub(crate) struct TestLoopBuilder {
     drop_chunks_validated_by: Option<AccountId>,
     /// Whether test loop should drop all endorsements from the given account.
     drop_endorsements_from: Option<AccountId>,
+    /// Simulated latency, message drops and partitions of the network between the clients.
+    network_conditions: Arc<NetworkConditions>,
     /// Number of latest epochs to keep before garbage collecting associated data.
     gc_num_epochs_to_keep: Option<u64>,
     /// The store of runtime configurations to be passed into runtime adapters.
@@ -87,6 +91,7 @@ impl TestLoopBuilder {
             chunks_storage: Default::default(),
             drop_chunks_validated_by: None,
             drop_endorsements_from: None,
+            network_conditions: Default::default(),
             gc_num_epochs_to_keep: None,
             runtime_config_store: None,
             config_modifier: None,
@@ -146,6 +151,13 @@ impl TestLoopBuilder {
         self
     }
 
+    /// Simulates the network between the clients according to the given conditions,
+    /// which can be changed by the test at any time.
+    pub(crate) fn network_conditions(mut self, network_conditions: Arc<NetworkConditions>) -> Self {
+        self.network_conditions = network_conditions;
+        self
+    }
+
     pub(crate) fn gc_num_epochs_to_keep(mut self, num_epochs: u64) -> Self {
         self.gc_num_epochs_to_keep = Some(num_epochs);
         self
@@ -538,13 +550,14 @@ impl TestLoopBuilder {
         network_adapters: &Vec<Arc<LateBoundSender<TestLoopSender<TestLoopPeerManagerActor>>>>,
         epoch_manager_adapters: &Vec<Arc<dyn EpochManagerAdapter>>,
     ) {
-        let shared_state = Arc::new(TestLoopNetworkSharedState::new(&datas));
+        let shared_state =
+            Arc::new(TestLoopNetworkSharedState::new(&datas, self.network_conditions.clone()));
         for (idx, data) in datas.iter().enumerate() {
             let mut peer_manager_actor = TestLoopPeerManagerActor::new(
                 self.test_loop.clock(),
                 &data.account_id,
                 shared_state.clone(),
-                Arc::new(self.test_loop.future_spawner()),
+                self.test_loop.future_spawner(),
             );
 
             if let Some(account_id) = &self.drop_chunks_validated_by {
@@ -0,0 +1,87 @@
+use assert_matches::assert_matches;
+use near_async::messaging::Handler;
+use near_async::time::Duration;
+use near_client::GetContractLogs;
+use near_client_primitives::types::GetContractLogsError;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::test_utils::create_user_test_signer;
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::AccountId;
+
+use crate::test_loop::env::TestLoopEnv;
+use crate::test_loop::utils::setups::standard_setup_1;
+use crate::test_loop::utils::transactions::{execute_tx, get_shared_block_hash, run_tx};
+use crate::test_loop::utils::TGAS;
+
+/// Logs emitted by a function call can be read back with `GetContractLogs`.
+#[test]
+fn test_contract_logs() {
+    init_test_logger();
+    let mut env: TestLoopEnv = standard_setup_1();
+
+    let account0: AccountId = "account0".parse().unwrap();
+    let account0_signer = &create_user_test_signer(&account0).into();
+
+    let deploy_contract_tx = SignedTransaction::deploy_contract(
+        101,
+        &account0,
+        near_test_contracts::rs_contract().into(),
+        account0_signer,
+        get_shared_block_hash(&env.datas, &env.test_loop),
+    );
+    run_tx(&mut env.test_loop, deploy_contract_tx, &env.datas, Duration::seconds(5));
+
+    let log_tx = SignedTransaction::call(
+        102,
+        account0.clone(),
+        account0.clone(),
+        account0_signer,
+        0,
+        "log_something".into(),
+        vec![],
+        300 * TGAS,
+        get_shared_block_hash(&env.datas, &env.test_loop),
+    );
+    let log_tx_res =
+        execute_tx(&mut env.test_loop, log_tx, &env.datas, Duration::seconds(5)).unwrap();
+    let outcome = log_tx_res
+        .receipts_outcome
+        .into_iter()
+        .find(|outcome| !outcome.outcome.logs.is_empty())
+        .unwrap();
+    assert_eq!(outcome.outcome.logs, vec!["hello".to_string()]);
+
+    // The last node is the rpc node which tracks all shards.
+    let rpc_data = env.datas.last().unwrap();
+    let chain = &env.test_loop.data.get(&rpc_data.client_sender.actor_handle()).client.chain;
+    let height = chain.get_block_header(&outcome.block_hash).unwrap().height();
+    let head_height = chain.head().unwrap().height;
+    let view_client = env.test_loop.data.get_mut(&rpc_data.view_client_sender.actor_handle());
+    let logs = view_client
+        .handle(GetContractLogs {
+            account_id: account0.clone(),
+            from_height: height - 5,
+            to_height: head_height,
+        })
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].block_hash, outcome.block_hash);
+    assert_eq!(logs[0].block_height, height);
+    assert_eq!(logs[0].id, outcome.id);
+    assert_eq!(logs[0].logs, vec!["hello".to_string()]);
+
+    // Blocks above the head may still be produced.
+    let res = view_client.handle(GetContractLogs {
+        account_id: account0.clone(),
+        from_height: height,
+        to_height: head_height + 1,
+    });
+    assert_matches!(res, Err(GetContractLogsError::UnknownBlock(_)));
+
+    // Blocks below the genesis height were never observed.
+    let res =
+        view_client.handle(GetContractLogs { account_id: account0, from_height: 0, to_height: 10 });
+    assert_matches!(res, Err(GetContractLogsError::UnknownBlock(_)));
+
+    env.shutdown_and_drain_remaining_events(Duration::seconds(20));
+}
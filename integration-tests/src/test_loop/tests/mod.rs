@@ -1,12 +1,14 @@
 mod chunk_validator_kickout;
 pub mod congestion_control;
 pub mod congestion_control_genesis_bootstrap;
+mod contract_logs;
 pub mod epoch_sync;
 pub mod fix_min_stake_ratio;
 pub mod in_memory_tries;
 pub mod max_receipt_size;
 pub mod multinode_stateless_validators;
 pub mod multinode_test_loop_example;
+pub mod network_partition;
 mod resharding_v3;
 pub mod simple_test_loop_example;
 pub mod syncing;
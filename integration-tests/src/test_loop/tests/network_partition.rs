@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use near_async::test_loop::data::{TestLoopData, TestLoopDataHandle};
+use near_async::time::Duration;
+use near_chain_configs::test_genesis::TestGenesisBuilder;
+use near_client::client_actor::ClientActorInner;
+use near_network::test_loop::NetworkConditions;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::types::AccountId;
+
+use crate::test_loop::builder::TestLoopBuilder;
+use crate::test_loop::env::TestLoopEnv;
+use crate::test_loop::utils::ONE_NEAR;
+
+const NUM_CLIENTS: usize = 4;
+
+/// Isolates one of the validators from the rest of the network. The remaining validators
+/// hold enough stake to keep producing blocks, and the isolated one catches up once the
+/// partition is healed.
+#[test]
+fn test_network_partition() {
+    init_test_logger();
+    let network_conditions = Arc::new(NetworkConditions::new(42));
+    let builder = TestLoopBuilder::new().network_conditions(network_conditions.clone());
+
+    let initial_balance = 10000 * ONE_NEAR;
+    let accounts: Vec<AccountId> =
+        (0..NUM_CLIENTS).map(|i| format!("account{}", i).parse().unwrap()).collect();
+    let clients = accounts.clone();
+
+    let mut genesis_builder = TestGenesisBuilder::new();
+    genesis_builder
+        .genesis_time_from_clock(&builder.clock())
+        .protocol_version_latest()
+        .genesis_height(10000)
+        .gas_prices_free()
+        .gas_limit_one_petagas()
+        .shard_layout_single()
+        .transaction_validity_period(1000)
+        .epoch_length(50)
+        .validators_desired_roles(&clients.iter().map(|t| t.as_str()).collect_vec(), &[]);
+    for account in &accounts {
+        genesis_builder.add_user_account_simple(account.clone(), initial_balance);
+    }
+    let (genesis, epoch_config_store) = genesis_builder.build();
+
+    let TestLoopEnv { mut test_loop, datas: node_datas, tempdir } =
+        builder.genesis(genesis).epoch_config_store(epoch_config_store).clients(clients).build();
+    network_conditions.set_latency(Duration::milliseconds(20));
+
+    let client_handle = node_datas[0].client_sender.actor_handle();
+    let isolated_handle = node_datas[NUM_CLIENTS - 1].client_sender.actor_handle();
+    let head_height = |test_loop_data: &TestLoopData,
+                       handle: &TestLoopDataHandle<ClientActorInner>| {
+        test_loop_data.get(handle).client.chain.head().unwrap().height
+    };
+
+    network_conditions.partition(&[&accounts[..NUM_CLIENTS - 1], &accounts[NUM_CLIENTS - 1..]]);
+    let partition_height = head_height(&test_loop.data, &client_handle);
+    test_loop.run_until(
+        |test_loop_data| head_height(test_loop_data, &client_handle) > partition_height + 10,
+        Duration::seconds(20),
+    );
+    // Blocks which were in flight when the partition started might have still been delivered.
+    assert!(head_height(&test_loop.data, &isolated_handle) <= partition_height + 1);
+
+    network_conditions.heal();
+    test_loop.run_until(
+        |test_loop_data| {
+            head_height(test_loop_data, &isolated_handle)
+                >= head_height(test_loop_data, &client_handle)
+        },
+        Duration::seconds(20),
+    );
+
+    TestLoopEnv { test_loop, datas: node_datas, tempdir }
+        .shutdown_and_drain_remaining_events(Duration::seconds(20));
+}
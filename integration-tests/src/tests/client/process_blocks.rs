@@ -989,6 +989,10 @@ fn client_sync_headers() {
                     connection_established_time: near_async::time::Instant::now(),
                     peer_type: PeerType::Outbound,
                     nonce: 1,
+                    is_validator: false,
+                    invalid_messages: 0,
+                    requests_served: 0,
+                    avg_response_latency: None,
                 }],
                 num_connected_peers: 1,
                 peer_max_count: 1,
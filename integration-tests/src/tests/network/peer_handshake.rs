@@ -28,6 +28,8 @@ fn make_peer_manager(
     let mut config = config::NetworkConfig::from_seed(seed, node_addr);
     config.peer_store.boot_nodes = convert_boot_nodes(boot_nodes);
     config.max_num_peers = peer_max_count;
+    config.max_num_inbound_peers = peer_max_count;
+    config.max_num_outbound_peers = peer_max_count;
     config.ideal_connections_hi = peer_max_count;
     config.ideal_connections_lo = peer_max_count;
 
@@ -435,6 +435,8 @@ impl Runner {
             config::NetworkConfig::from_seed(config.account_id.as_ref(), config.node_addr);
         network_config.peer_store.ban_window = config.ban_window;
         network_config.max_num_peers = config.max_num_peers;
+        network_config.max_num_inbound_peers = config.max_num_peers;
+        network_config.max_num_outbound_peers = config.max_num_peers;
         network_config.ttl_account_id_router = time::Duration::seconds(5);
         network_config.routed_message_ttl = config.routed_message_ttl;
         network_config.peer_store.blacklist = blacklist;
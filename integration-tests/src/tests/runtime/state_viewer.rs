@@ -205,8 +205,9 @@ fn assert_view_state(
         .map(|(key, value)| StateItem { key: key.to_vec().into(), value: value.to_vec().into() })
         .collect::<Vec<_>>();
 
-    let view_state =
-        |include_proof| trie_viewer.view_state(&state_update, &alice, prefix, include_proof);
+    let view_state = |include_proof| {
+        trie_viewer.view_state(&state_update, &alice, prefix, Default::default(), include_proof)
+    };
 
     // Test without proof
     let result = view_state(false).unwrap();
@@ -365,10 +366,59 @@ fn test_view_state_too_large() {
         &Account::new(0, 0, 0, CryptoHash::default(), 50_001, PROTOCOL_VERSION),
     );
     let trie_viewer = TrieViewer::new(Some(50_000), None);
-    let result = trie_viewer.view_state(&state_update, &alice_account(), b"", false);
+    let result =
+        trie_viewer.view_state(&state_update, &alice_account(), b"", Default::default(), false);
     assert!(matches!(result, Err(errors::ViewStateError::AccountStateTooLarge { .. })));
 }
 
+#[test]
+fn test_view_state_paginated() {
+    let (_, tries, root) = get_runtime_and_trie();
+    let mut state_update = tries.new_trie_update(TEST_SHARD_UID, root);
+    set_account(
+        &mut state_update,
+        alice_account(),
+        &Account::new(0, 0, 0, CryptoHash::default(), 50_001, PROTOCOL_VERSION),
+    );
+    for key in [b"a", b"b", b"c"] {
+        state_update.set(
+            TrieKey::ContractData { account_id: alice_account(), key: key.to_vec() },
+            key.to_vec(),
+        );
+    }
+    state_update.commit(StateChangeCause::InitialState);
+    let trie_changes = state_update.finalize().unwrap().1;
+    let mut db_changes = tries.store_update();
+    let new_root = tries.apply_all(&trie_changes, TEST_SHARD_UID, &mut db_changes);
+    db_changes.commit().unwrap();
+
+    let state_update = tries.new_trie_update(TEST_SHARD_UID, new_root);
+    // The state size limit doesn't apply to paginated requests.
+    let trie_viewer = TrieViewer::new(Some(50_000), None);
+    let view_page = |after_key: Option<&[u8]>| {
+        let page = ViewStatePage { after_key, limit: Some(2) };
+        let result =
+            trie_viewer.view_state(&state_update, &alice_account(), b"", page, false).unwrap();
+        let keys = result.values.into_iter().map(|item| item.key.to_vec()).collect::<Vec<_>>();
+        (keys, result.last_key.map(|key| key.to_vec()))
+    };
+
+    let (keys, last_key) = view_page(None);
+    assert_eq!(keys, vec![b"a".to_vec(), b"b".to_vec()]);
+    assert_eq!(last_key, Some(b"b".to_vec()));
+    let (keys, last_key) = view_page(last_key.as_deref());
+    assert_eq!(keys, vec![b"c".to_vec()]);
+    assert_eq!(last_key, None);
+
+    // The state size limit bounds the size of each page instead.
+    let trie_viewer = TrieViewer::new(Some(1), None);
+    let page = ViewStatePage { after_key: Some(b"a"), limit: Some(2) };
+    let result = trie_viewer.view_state(&state_update, &alice_account(), b"", page, false).unwrap();
+    let keys = result.values.into_iter().map(|item| item.key.to_vec()).collect::<Vec<_>>();
+    assert_eq!(keys, vec![b"b".to_vec()]);
+    assert_eq!(result.last_key.map(|key| key.to_vec()), Some(b"b".to_vec()));
+}
+
 #[test]
 fn test_view_state_with_large_contract() {
     let (_, tries, root) = get_runtime_and_trie();
@@ -381,7 +431,8 @@ fn test_view_state_with_large_contract() {
     );
     state_update.set(TrieKey::ContractCode { account_id: alice_account() }, contract_code);
     let trie_viewer = TrieViewer::new(Some(50_000), None);
-    let result = trie_viewer.view_state(&state_update, &alice_account(), b"", false);
+    let result =
+        trie_viewer.view_state(&state_update, &alice_account(), b"", Default::default(), false);
     assert!(result.is_ok());
 }
 
@@ -80,6 +80,8 @@ impl User for RpcUser {
             account_id: account_id.clone(),
             prefix: prefix.to_vec().into(),
             include_proof: false,
+            after_key: None,
+            limit: None,
         };
         match self.query(query)?.kind {
             QueryResponseKind::ViewState(view_state_result) => Ok(view_state_result),
@@ -38,6 +38,7 @@ fn test_config_duration_all_std() {
         rpc: Some(RpcConfig {
             experimental_debug_pages_src_path: Some(Default::default()),
             prometheus_addr: Some(Default::default()),
+            allowed_methods: Some(Default::default()),
             ..Default::default()
         }),
         rosetta_rpc: Some(Default::default()),
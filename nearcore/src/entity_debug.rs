@@ -14,7 +14,6 @@ use near_primitives::block::Tip;
 use near_primitives::challenge::{PartialState, TrieValue};
 use near_primitives::congestion_info::CongestionInfo;
 use near_primitives::epoch_block_info::BlockInfo;
-use near_primitives::epoch_manager::AGGREGATOR_KEY;
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::merkle::PartialMerkleTree;
 use near_primitives::receipt::Receipt;
@@ -35,6 +34,7 @@ use near_store::adapter::trie_store::get_key_from_shard_uid_and_hash;
 use near_store::db::GENESIS_CONGESTION_INFO_KEY;
 use near_store::flat::delta::KeyForFlatStateDelta;
 use near_store::flat::{FlatStateChanges, FlatStateDeltaMetadata, FlatStorageStatus};
+use near_store::keys::{BlockInfoKey, EpochInfoKey};
 use near_store::{
     DBCol, NibbleSlice, RawTrieNode, RawTrieNodeWithSize, ShardUId, Store, CHUNK_TAIL_KEY,
     COLD_HEAD_KEY, FINAL_HEAD_KEY, FORK_TAIL_KEY, GENESIS_JSON_HASH_KEY, GENESIS_STATE_ROOTS_KEY,
@@ -93,7 +93,7 @@ impl EntityDebugHandlerImpl {
             }
             EntityQuery::BlockInfoByHash { block_hash } => {
                 let block_info = store
-                    .get_ser::<BlockInfo>(DBCol::BlockInfo, &borsh::to_vec(&block_hash).unwrap())?
+                    .get_ser_by_key::<BlockInfo>(&BlockInfoKey(&block_hash))?
                     .ok_or_else(|| anyhow!("BlockInfo not found"))?;
                 Ok(serialize_entity(&block_info))
             }
@@ -156,7 +156,7 @@ impl EntityDebugHandlerImpl {
             }
             EntityQuery::EpochInfoAggregator(()) => {
                 let aggregator = store
-                    .get_ser::<EpochInfoAggregator>(DBCol::EpochInfo, AGGREGATOR_KEY)?
+                    .get_ser_by_key::<EpochInfoAggregator>(&EpochInfoKey::Aggregator)?
                     .ok_or_else(|| anyhow!("Aggregator not found"))?;
                 Ok(serialize_entity(&aggregator))
             }
@@ -1,4 +1,5 @@
 use crate::near_primitives::shard_layout::ShardUId;
+use crate::state_viewer::ViewStatePage;
 use near_crypto::PublicKey;
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::hash::CryptoHash;
@@ -64,6 +65,7 @@ pub trait ViewRuntimeAdapter {
         state_root: MerkleHash,
         account_id: &AccountId,
         prefix: &[u8],
+        page: ViewStatePage<'_>,
         include_proof: bool,
     ) -> Result<ViewStateResult, crate::state_viewer::errors::ViewStateError>;
 }
@@ -26,6 +26,20 @@ use tracing::debug;
 
 pub mod errors;
 
+/// Selects a page of the contract state returned by [`TrieViewer::view_state`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ViewStatePage<'a> {
+    /// Only the keys strictly greater than this one are returned.
+    pub after_key: Option<&'a [u8]>,
+    /// Maximum number of values returned, capped at [`MAX_VIEW_STATE_PAGE_LIMIT`].
+    /// When it is set, the state size limit of the viewer bounds the size of
+    /// the page instead of the size of the whole contract state.
+    pub limit: Option<usize>,
+}
+
+/// Maximum number of values returned in a single page of the contract state.
+pub const MAX_VIEW_STATE_PAGE_LIMIT: usize = 1000;
+
 /// State for the view call.
 #[derive(Debug)]
 pub struct ViewApplyState {
@@ -143,6 +157,7 @@ impl TrieViewer {
         state_update: &TrieUpdate,
         account_id: &AccountId,
         prefix: &[u8],
+        page: ViewStatePage<'_>,
         include_proof: bool,
     ) -> Result<ViewStateResult, errors::ViewStateError> {
         match get_account(state_update, account_id)? {
@@ -150,7 +165,7 @@ impl TrieViewer {
                 let code_len = get_code(state_update, account_id, Some(account.code_hash()))?
                     .map(|c| c.code().len() as u64)
                     .unwrap_or_default();
-                if let Some(limit) = self.state_size_limit {
+                if let (Some(limit), None) = (self.state_size_limit, page.limit) {
                     if account.storage_usage().saturating_sub(code_len) > limit {
                         return Err(errors::ViewStateError::AccountStateTooLarge {
                             requested_account_id: account_id.clone(),
@@ -166,17 +181,42 @@ impl TrieViewer {
         };
 
         let mut values = vec![];
+        let mut last_key = None;
+        let mut page_size = 0;
+        // An empty page wouldn't have a key to continue from.
+        let limit = page.limit.map(|limit| limit.clamp(1, MAX_VIEW_STATE_PAGE_LIMIT));
         let query = trie_key_parsers::get_raw_prefix_for_contract_data(account_id, prefix);
         let acc_sep_len = query.len() - prefix.len();
         let mut iter = state_update.trie().disk_iter()?;
         iter.remember_visited_nodes(include_proof);
-        iter.seek_prefix(&query)?;
+        // Start right at `after_key` so that neither the iteration nor the
+        // proof covers the keys of the previous pages.
+        let after_key = page.after_key.map(|after_key| [&query[..acc_sep_len], after_key].concat());
+        match &after_key {
+            Some(after_key) if after_key > &query => iter.seek(after_key)?,
+            _ => iter.seek_prefix(&query)?,
+        }
         for item in &mut iter {
             let (key, value) = item?;
+            if !key.starts_with(&query) {
+                break;
+            }
+            if after_key.as_ref() == Some(&key) {
+                continue;
+            }
+            let page_full = limit.is_some_and(|limit| {
+                values.len() >= limit
+                    || self.state_size_limit.is_some_and(|size_limit| page_size >= size_limit)
+            });
+            if page_full {
+                last_key = values.last().map(|item: &StateItem| item.key.clone());
+                break;
+            }
+            page_size += (key.len() - acc_sep_len + value.len()) as u64;
             values.push(StateItem { key: key[acc_sep_len..].to_vec().into(), value: value.into() });
         }
         let proof = iter.into_visited_nodes();
-        Ok(ViewStateResult { values, proof })
+        Ok(ViewStateResult { values, proof, last_key })
     }
 
     pub fn call_function(
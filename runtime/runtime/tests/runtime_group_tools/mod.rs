@@ -67,6 +67,7 @@ impl StandaloneRuntime {
         let runtime = Runtime::new();
         let genesis = Genesis::new(
             GenesisConfig {
+                protocol_version: PROTOCOL_VERSION,
                 validators,
                 total_supply: get_initial_supply(state_records),
                 epoch_length: 60,
@@ -203,6 +203,7 @@ impl Network {
                 self.keep_sending(|peer| NetworkRequests::PartialEncodedChunkRequest {
                     target: AccountIdOrPeerTrackingShard {
                         account_id: peer.peer_info.account_id,
+                        fallback_account_ids: vec![],
                         prefer_peer: true,
                         shard_id: ch.shard_id(),
                         only_archival: false,
@@ -9,6 +9,7 @@ use near_primitives::block::Tip;
 use near_primitives::epoch_block_info::BlockInfo;
 use near_primitives::hash::CryptoHash;
 use near_store::cold_storage::{copy_all_data_to_cold, update_cold_db, update_cold_head};
+use near_store::keys::BlockInfoKey;
 use near_store::metadata::DbKind;
 use near_store::{DBCol, NodeStorage, Store, StoreOpener};
 use near_store::{COLD_HEAD_KEY, FINAL_HEAD_KEY, HEAD_KEY, TAIL_KEY};
@@ -427,12 +428,12 @@ impl PrepareHotCmd {
 
         let cold_head_hash = cold_head.last_block_hash;
         let cold_head_block_info =
-            rpc_store.get_ser::<BlockInfo>(DBCol::BlockInfo, cold_head_hash.as_ref())?;
+            rpc_store.get_ser_by_key::<BlockInfo>(&BlockInfoKey(&cold_head_hash))?;
         let cold_head_block_info = cold_head_block_info
             .ok_or_else(|| anyhow::anyhow!("Cold head block info is not in rpc db"))?;
         let cold_epoch_first_block = *cold_head_block_info.epoch_first_block();
         let cold_epoch_first_block_info =
-            rpc_store.get_ser::<BlockInfo>(DBCol::BlockInfo, cold_epoch_first_block.as_ref())?;
+            rpc_store.get_ser_by_key::<BlockInfo>(&BlockInfoKey(&cold_epoch_first_block))?;
 
         if cold_epoch_first_block_info.is_none() {
             return Err(anyhow::anyhow!(
@@ -1,6 +1,5 @@
 use clap::Parser;
-use near_store::db::{Database, RocksDB};
-use near_store::DBCol;
+use near_store::{DBCol, Store};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -8,7 +7,7 @@ use std::sync::{Arc, Mutex};
 use std::{panic, println};
 use strum::IntoEnumIterator;
 
-use crate::utils::{open_rocksdb, resolve_column};
+use crate::utils::{open_store_read_only, resolve_column};
 
 #[derive(Parser)]
 pub(crate) struct AnalyseDataSizeDistributionCommand {
@@ -120,7 +119,7 @@ impl DataSizeDistribution {
     }
 }
 
-fn read_all_pairs(db: &RocksDB, col_families: &Vec<DBCol>) -> DataSizeDistribution {
+fn read_all_pairs(store: &Store, col_families: &Vec<DBCol>) -> DataSizeDistribution {
     // Initialize counters
     let key_sizes: Arc<Mutex<HashMap<usize, usize>>> = Arc::new(Mutex::new(HashMap::new()));
     let value_sizes: Arc<Mutex<HashMap<usize, usize>>> = Arc::new(Mutex::new(HashMap::new()));
@@ -140,7 +139,7 @@ fn read_all_pairs(db: &RocksDB, col_families: &Vec<DBCol>) -> DataSizeDistributi
         let mut local_value_sizes: HashMap<usize, usize> = HashMap::new();
 
         //let cf_handle = db.cf_handle(col_family).unwrap();
-        for res in db.iter_raw_bytes(*col_family) {
+        for res in store.iter_raw_bytes(*col_family) {
             match res {
                 Ok(tuple) => {
                     // Count key sizes
@@ -191,11 +190,40 @@ fn get_column_families(input_col: &Option<String>) -> anyhow::Result<Vec<DBCol>>
 
 impl AnalyseDataSizeDistributionCommand {
     pub(crate) fn run(&self, home: &PathBuf) -> anyhow::Result<()> {
-        let db = open_rocksdb(home, near_store::Mode::ReadOnly)?;
+        // The node doesn't need to be stopped to analyse its database.
+        let store = open_store_read_only(home)?;
         let column_families = get_column_families(&self.column)?;
-        let results = read_all_pairs(&db, &column_families);
+        let results = read_all_pairs(&store, &column_families);
         results.print_results(self.top_k);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::read_all_pairs;
+    use near_store::{DBCol, NodeStorage, Store, StoreConfig};
+
+    /// Checks that the database can be analysed while it is open for writing,
+    /// e.g. by a running node.
+    #[test]
+    fn test_read_all_pairs_of_open_database() {
+        let home_dir = tempfile::tempdir().unwrap();
+        let store_config = StoreConfig::test_config();
+        let node_storage =
+            NodeStorage::opener(home_dir.path(), false, &store_config, None).open().unwrap();
+        let store = node_storage.get_hot_store();
+        let mut store_update = store.store_update();
+        store_update.insert(DBCol::Block, vec![0], vec![42; 10]);
+        store_update.insert(DBCol::Block, vec![1], vec![42; 20]);
+        store_update.commit().unwrap();
+        store.flush().unwrap();
+
+        let read_only_store = Store::open_read_only(&home_dir.path().join("data")).unwrap();
+        let results = read_all_pairs(&read_only_store, &vec![DBCol::Block]);
+        assert_eq!(results.total_num_of_pairs, 2);
+        assert_eq!(results.value_sizes.len(), 2);
+        drop(node_storage);
+    }
+}
@@ -8,6 +8,8 @@ use crate::compact::RunCompactionCommand;
 use crate::corrupt::CorruptStateSnapshotCommand;
 use crate::make_snapshot::MakeSnapshotCommand;
 use crate::memtrie::LoadMemTrieCommand;
+use crate::rebuild_derived_columns::RebuildDerivedColumnsCommand;
+use crate::reset_data::ResetDataCommand;
 use crate::resharding_v2::ReshardingV2Command;
 use crate::run_migrations::RunMigrationsCommand;
 use crate::state_perf::StatePerfCommand;
@@ -25,7 +27,7 @@ pub struct DatabaseCommand {
 #[derive(Parser)]
 #[clap(subcommand_required = true, arg_required_else_help = true)]
 enum SubCommand {
-    /// Analyse data size distribution in RocksDB
+    /// Analyse data size distribution in RocksDB, also while the node is running
     AnalyseDataSizeDistribution(AnalyseDataSizeDistributionCommand),
 
     /// Analyse gas usage in a chosen sequnce of blocks
@@ -43,6 +45,14 @@ enum SubCommand {
     /// Make snapshot of the database
     MakeSnapshot(MakeSnapshotCommand),
 
+    /// Regenerate derived columns (height index, outcome ids, block infos)
+    /// from the stored blocks
+    RebuildDerivedColumns(RebuildDerivedColumnsCommand),
+
+    /// Delete the chain data, keeping the config and the keys, or only the
+    /// data recomputed by the node with --derived-only
+    ResetData(ResetDataCommand),
+
     /// Run migrations
     RunMigrations(RunMigrationsCommand),
 
@@ -82,6 +92,16 @@ impl DatabaseCommand {
                 let near_config = load_config(home, genesis_validation);
                 cmd.run(home, near_config.config.archive, &near_config.config.store)
             }
+            SubCommand::RebuildDerivedColumns(cmd) => cmd.run(home, genesis_validation),
+            SubCommand::ResetData(cmd) => {
+                let near_config = load_config(home, genesis_validation);
+                cmd.run(
+                    home,
+                    near_config.config.archive,
+                    &near_config.config.store,
+                    near_config.config.cold_store.as_ref(),
+                )
+            }
             SubCommand::RunMigrations(cmd) => cmd.run(home, genesis_validation),
             SubCommand::StatePerf(cmd) => cmd.run(home),
             SubCommand::LoadMemTrie(cmd) => cmd.run(home, genesis_validation),
@@ -9,6 +9,8 @@ pub mod commands;
 mod compact;
 mod corrupt;
 mod make_snapshot;
+mod rebuild_derived_columns;
+mod reset_data;
 mod memtrie;
 mod resharding_v2;
 mod run_migrations;
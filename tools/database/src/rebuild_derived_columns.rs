@@ -0,0 +1,382 @@
+use near_chain::{BlockHeader, ChainStore, ChainStoreAccess};
+use near_chain_configs::{GenesisConfig, GenesisValidationMode};
+use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
+use near_epoch_manager::{EpochManager, EpochManagerAdapter};
+use near_primitives::epoch_block_info::BlockInfo;
+use near_primitives::hash::CryptoHash;
+use near_primitives::merkle::{Direction, MerklePath};
+use near_primitives::receipt::ReceiptEnum;
+use near_primitives::shard_layout::account_id_to_shard_id;
+use near_primitives::sharding::ReceiptProof;
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
+use near_primitives::utils::{get_block_shard_id, index_to_bytes};
+use near_store::keys::BlockInfoKey;
+use near_store::{DBCol, NodeStorage, Store, StoreError};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Number of blocks whose entries are written in a single store update.
+const BATCH_SIZE: usize = 1000;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[clap(rename_all = "kebab_case")]
+enum DerivedColumn {
+    /// `BlockInfo` column: validator proposals and epoch of each block.
+    BlockInfo,
+    /// `BlockHeight` column: index of canonical blocks by height.
+    BlockHeight,
+    /// `OutcomeIds` column: execution outcomes of each chunk, in order.
+    OutcomeIds,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct RebuildDerivedColumnsCommand {
+    /// Columns to rebuild, comma separated. Defaults to all of them.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    columns: Vec<DerivedColumn>,
+    /// Height of the first block to process. Defaults to the chain tail.
+    #[clap(long)]
+    start_height: Option<BlockHeight>,
+    /// Height of the last block to process. Defaults to the chain head.
+    #[clap(long)]
+    end_height: Option<BlockHeight>,
+}
+
+impl RebuildDerivedColumnsCommand {
+    pub(crate) fn run(
+        &self,
+        home_dir: &Path,
+        genesis_validation: GenesisValidationMode,
+    ) -> anyhow::Result<()> {
+        let near_config = nearcore::config::load_config(&home_dir, genesis_validation)?;
+        let opener = NodeStorage::opener(
+            home_dir,
+            near_config.config.archive,
+            &near_config.config.store,
+            near_config.config.cold_store.as_ref(),
+        );
+        let storage = opener.open()?;
+        let store = storage.get_hot_store();
+        let genesis_config = &near_config.genesis.config;
+        let chain_store = ChainStore::new(
+            store.clone(),
+            genesis_config.genesis_height,
+            near_config.client_config.save_trie_changes,
+        );
+
+        let start_height = match self.start_height {
+            Some(height) => height,
+            None => chain_store.tail()?,
+        };
+        let end_height = match self.end_height {
+            Some(height) => height,
+            None => chain_store.head()?.height,
+        };
+        let headers = canonical_headers(&chain_store, start_height, end_height)?;
+        eprintln!(
+            "Found {} canonical blocks between heights {start_height} and {end_height}",
+            headers.len()
+        );
+
+        let columns = if self.columns.is_empty() {
+            vec![DerivedColumn::BlockInfo, DerivedColumn::BlockHeight, DerivedColumn::OutcomeIds]
+        } else {
+            self.columns.clone()
+        };
+        // Block infos go first since the epochs of the blocks are needed to
+        // assign outcomes to shards.
+        if columns.contains(&DerivedColumn::BlockInfo) {
+            rebuild_block_info(&store, genesis_config, &chain_store, &headers)?;
+        }
+        if columns.contains(&DerivedColumn::BlockHeight) {
+            rebuild_block_height(&store, start_height, end_height, &headers)?;
+        }
+        if columns.contains(&DerivedColumn::OutcomeIds) {
+            let epoch_manager = EpochManager::new_arc_handle(store.clone(), genesis_config);
+            let shard_tracker = ShardTracker::new(
+                TrackedConfig::from_config(&near_config.client_config),
+                epoch_manager.clone(),
+            );
+            let me = near_config.validator_signer.get().map(|signer| signer.validator_id().clone());
+            rebuild_outcome_ids(
+                &store,
+                &chain_store,
+                epoch_manager.as_ref(),
+                &shard_tracker,
+                me.as_ref(),
+                &headers,
+            )?;
+        }
+        eprintln!("Rebuilding is finished!");
+        Ok(())
+    }
+}
+
+/// Returns headers of the canonical blocks with heights in the given range in
+/// increasing order of height.
+///
+/// The chain is followed from the head by previous block hashes rather than
+/// looked up in the height index so that the result is correct even if the
+/// index is corrupted.
+fn canonical_headers(
+    chain_store: &ChainStore,
+    start_height: BlockHeight,
+    end_height: BlockHeight,
+) -> anyhow::Result<Vec<BlockHeader>> {
+    let genesis_height = chain_store.get_genesis_height();
+    let mut headers = vec![];
+    let mut header = chain_store.get_block_header(&chain_store.head()?.last_block_hash)?;
+    while header.height() >= start_height {
+        let height = header.height();
+        let prev_hash = *header.prev_hash();
+        if height <= end_height {
+            headers.push(header);
+        }
+        if height == genesis_height {
+            break;
+        }
+        header = chain_store.get_block_header(&prev_hash)?;
+    }
+    headers.reverse();
+    Ok(headers)
+}
+
+/// Recomputes block infos which are missing or can't be read by replaying
+/// headers of the blocks through the epoch manager.
+fn rebuild_block_info(
+    store: &Store,
+    genesis_config: &GenesisConfig,
+    chain_store: &ChainStore,
+    headers: &[BlockHeader],
+) -> anyhow::Result<()> {
+    let mut broken = vec![];
+    let mut num_corrupted = 0;
+    for header in headers {
+        match store.get_ser_by_key::<BlockInfo>(&BlockInfoKey(header.hash())) {
+            Ok(Some(_)) => continue,
+            Ok(None) => {}
+            Err(err) if StoreError::from_io(&err).is_some() => num_corrupted += 1,
+            Err(err) => return Err(err.into()),
+        }
+        broken.push(header);
+    }
+    // Corrupted entries have to be removed first, otherwise the epoch
+    // manager would consider those blocks already recorded.
+    let mut store_update = store.store_update();
+    for header in &broken {
+        store_update.delete_by_key(&BlockInfoKey(header.hash()));
+    }
+    store_update.commit()?;
+
+    let epoch_manager = EpochManager::new_arc_handle(store.clone(), genesis_config);
+    for header in &broken {
+        let last_finalized_height = chain_store.get_block_height(header.last_final_block())?;
+        epoch_manager
+            .add_validator_proposals(
+                BlockInfo::from_header(header, last_finalized_height),
+                *header.random_value(),
+            )?
+            .commit()?;
+    }
+    eprintln!("BlockInfo: rebuilt {} entries, {num_corrupted} of them corrupted", broken.len());
+    Ok(())
+}
+
+/// Points every height in the range at its canonical block and removes
+/// entries for heights which have been skipped.
+fn rebuild_block_height(
+    store: &Store,
+    start_height: BlockHeight,
+    end_height: BlockHeight,
+    headers: &[BlockHeader],
+) -> anyhow::Result<()> {
+    let canonical: HashMap<BlockHeight, CryptoHash> =
+        headers.iter().map(|header| (header.height(), *header.hash())).collect();
+    let mut store_update = store.store_update();
+    let mut num_fixed = 0;
+    for height in start_height..=end_height {
+        let key = index_to_bytes(height);
+        let stored = store.get_ser::<CryptoHash>(DBCol::BlockHeight, &key).ok().flatten();
+        let expected = canonical.get(&height);
+        if stored.as_ref() == expected {
+            continue;
+        }
+        match expected {
+            Some(block_hash) => store_update.set_ser(DBCol::BlockHeight, &key, block_hash)?,
+            None => store_update.delete(DBCol::BlockHeight, &key),
+        }
+        num_fixed += 1;
+    }
+    store_update.commit()?;
+    eprintln!("BlockHeight: fixed {num_fixed} entries");
+    Ok(())
+}
+
+/// Regenerates lists of outcome ids of the chunks of the tracked shards which
+/// are missing or can't be read from the stored execution outcomes.
+///
+/// Outcomes are keyed by outcome id first, so rather than scanning the whole
+/// column the candidates are collected block by block: transactions of the
+/// new chunks, receipts they convert to which are executed in the same block,
+/// and incoming receipts, which are kept until they are executed since they
+/// may be delayed.  Receipts delayed before `start_height` are not found, so
+/// the range should start before any of its delayed receipts were received.
+fn rebuild_outcome_ids(
+    store: &Store,
+    chain_store: &ChainStore,
+    epoch_manager: &dyn EpochManagerAdapter,
+    shard_tracker: &ShardTracker,
+    me: Option<&AccountId>,
+    headers: &[BlockHeader],
+) -> anyhow::Result<()> {
+    // Ids of the action receipts which were received but not executed yet.
+    let mut pending_receipts: HashSet<CryptoHash> = HashSet::new();
+    let mut store_update = store.store_update();
+    let mut num_rebuilt = 0;
+    for (index, header) in headers.iter().enumerate() {
+        if index > 0 && index % BATCH_SIZE == 0 {
+            std::mem::replace(&mut store_update, store.store_update()).commit()?;
+        }
+        let block_hash = header.hash();
+        let shard_layout = epoch_manager.get_shard_layout(header.epoch_id())?;
+        let tracked_shards: Vec<ShardId> = shard_layout
+            .shard_ids()
+            .filter(|&shard_id| {
+                shard_tracker.care_about_shard(me, header.prev_hash(), shard_id, true)
+            })
+            .collect();
+
+        // Receipts are applied with the chunk of their shard in the block they
+        // are received in, unless there is no new chunk or they get delayed.
+        for &shard_id in &tracked_shards {
+            let proofs = match chain_store.get_incoming_receipts(block_hash, shard_id) {
+                Ok(proofs) => proofs,
+                Err(near_chain::Error::DBNotFoundErr(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            for ReceiptProof(receipts, _) in proofs.iter() {
+                for receipt in receipts {
+                    // Data receipts don't have outcomes of their own.
+                    if matches!(
+                        receipt.receipt(),
+                        ReceiptEnum::Action(_) | ReceiptEnum::PromiseYield(_)
+                    ) {
+                        pending_receipts.insert(*receipt.receipt_id());
+                    }
+                }
+            }
+        }
+
+        // Intact entries are kept since the reconstruction may miss outcomes,
+        // they only tell which of the pending receipts have been executed.
+        let mut broken_shards = vec![];
+        for &shard_id in &tracked_shards {
+            let key = get_block_shard_id(block_hash, shard_id);
+            match store.get_ser::<Vec<CryptoHash>>(DBCol::OutcomeIds, &key) {
+                Ok(Some(ids)) => {
+                    for id in &ids {
+                        pending_receipts.remove(id);
+                    }
+                    continue;
+                }
+                Ok(None) => {}
+                Err(err) if StoreError::from_io(&err).is_some() => {}
+                Err(err) => return Err(err.into()),
+            }
+            broken_shards.push(shard_id);
+        }
+        if broken_shards.is_empty() {
+            continue;
+        }
+
+        let mut candidates: Vec<CryptoHash> = pending_receipts.iter().copied().collect();
+        let block = chain_store.get_block(block_hash)?;
+        for chunk_header in block.chunks().iter() {
+            if tracked_shards.contains(&chunk_header.shard_id())
+                && chunk_header.is_new_chunk(header.height())
+            {
+                let chunk = chain_store.get_chunk(&chunk_header.chunk_hash())?;
+                candidates.extend(chunk.transactions().iter().map(|tx| tx.get_hash()));
+            }
+        }
+
+        let mut outcome_ids: BTreeMap<ShardId, Vec<(Vec<u8>, CryptoHash)>> =
+            broken_shards.iter().map(|&shard_id| (shard_id, vec![])).collect();
+        while let Some(outcome_id) = candidates.pop() {
+            let Some(outcome) =
+                chain_store.get_outcome_by_id_and_block_hash(&outcome_id, block_hash)?
+            else {
+                continue;
+            };
+            pending_receipts.remove(&outcome_id);
+            // Receipts converted from transactions of the same account are
+            // executed right away; any others are not found in this block.
+            candidates.extend(outcome.outcome.receipt_ids.iter().copied());
+            // Transactions are converted in the shard of the signer and
+            // receipts are executed in the shard of the receiver, both of
+            // which are the executor.
+            let shard_id = account_id_to_shard_id(&outcome.outcome.executor_id, &shard_layout);
+            if let Some(ids) = outcome_ids.get_mut(&shard_id) {
+                ids.push((position_in_merkle_tree(&outcome.proof), outcome_id));
+            }
+        }
+        num_rebuilt += outcome_ids.len();
+        for (shard_id, mut ids) in outcome_ids {
+            ids.sort();
+            ids.dedup();
+            let ids: Vec<CryptoHash> = ids.into_iter().map(|(_, outcome_id)| outcome_id).collect();
+            store_update.set_ser(
+                DBCol::OutcomeIds,
+                &get_block_shard_id(block_hash, shard_id),
+                &ids,
+            )?;
+        }
+    }
+    store_update.commit()?;
+    eprintln!(
+        "OutcomeIds: rebuilt {num_rebuilt} entries, {} received receipts were not executed",
+        pending_receipts.len()
+    );
+    Ok(())
+}
+
+/// Returns a key which sorts outcomes of a chunk in the order they were
+/// produced in.
+///
+/// Outcomes are merklized in that order.  Paths of any two outcomes from the
+/// root of the tree are the same until the node where they branch off, so
+/// comparing the directions taken from the root recovers the original order.
+fn position_in_merkle_tree(proof: &MerklePath) -> Vec<u8> {
+    proof
+        .iter()
+        .rev()
+        .map(|item| match item.direction {
+            // The sibling is on the right so the outcome is in the left subtree.
+            Direction::Right => 0,
+            Direction::Left => 1,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::position_in_merkle_tree;
+    use near_primitives::merkle::merklize;
+
+    #[test]
+    fn test_position_in_merkle_tree() {
+        for len in 1..20u64 {
+            let items: Vec<u64> = (0..len).collect();
+            let (_, paths) = merklize(&items);
+            let mut positions: Vec<(Vec<u8>, u64)> = paths
+                .iter()
+                .zip(&items)
+                .map(|(path, item)| (position_in_merkle_tree(path), *item))
+                .rev()
+                .collect();
+            positions.sort();
+            let sorted: Vec<u64> = positions.into_iter().map(|(_, item)| item).collect();
+            assert_eq!(sorted, items);
+        }
+    }
+}
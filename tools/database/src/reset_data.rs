@@ -0,0 +1,185 @@
+use anyhow::Context;
+use near_store::{DBCol, Mode, NodeStorage, StoreConfig};
+use std::path::{Path, PathBuf};
+
+/// Columns which are recomputed by the node when missing.
+const DERIVED_COLUMNS: &[DBCol] = &[DBCol::CachedContractCode];
+
+#[derive(clap::Args)]
+pub(crate) struct ResetDataCommand {
+    /// Only delete the data which the node recomputes on demand (the compiled
+    /// contracts cache), keeping the chain.
+    #[clap(long)]
+    derived_only: bool,
+    /// Don't ask for confirmation.
+    #[clap(long)]
+    yes: bool,
+}
+
+impl ResetDataCommand {
+    pub(crate) fn run(
+        &self,
+        home_dir: &Path,
+        archive: bool,
+        store_config: &StoreConfig,
+        cold_store_config: Option<&StoreConfig>,
+    ) -> anyhow::Result<()> {
+        if self.derived_only {
+            if !self.confirm(&format!("delete columns {DERIVED_COLUMNS:?}")) {
+                return Ok(());
+            }
+            let opener = NodeStorage::opener(home_dir, archive, store_config, cold_store_config);
+            // Fails if the database is in use by a running node.
+            let storage = opener.open_in_mode(Mode::ReadWriteExisting)?;
+            let mut store_update = storage.get_hot_store().store_update();
+            for col in DERIVED_COLUMNS {
+                store_update.delete_all(*col);
+            }
+            store_update.commit()?;
+            return Ok(());
+        }
+
+        // A cold database which doesn't exist has nothing to delete.
+        let opener = NodeStorage::opener(home_dir, archive, store_config, cold_store_config);
+        let paths: Vec<PathBuf> = std::iter::once(opener.path())
+            .chain(opener.cold_path().filter(|path| path.exists()))
+            .map(Path::to_path_buf)
+            .collect();
+        if !self.confirm(&format!("delete {paths:?}; config and keys are kept")) {
+            return Ok(());
+        }
+        // The databases aren't opened, so that corrupted ones or ones with an
+        // unsupported version can be deleted as well.  Locking them fails if
+        // they are in use by a running node.
+        let locks = paths.iter().map(|path| lock_database(path)).collect::<Result<Vec<_>, _>>()?;
+        for path in &paths {
+            delete_contents(path)?;
+        }
+        drop(locks);
+        for path in paths {
+            std::fs::remove_dir_all(&path)?;
+            eprintln!("Deleted {}", path.display());
+        }
+        Ok(())
+    }
+
+    fn confirm(&self, action: &str) -> bool {
+        if self.yes {
+            return true;
+        }
+        println!("WARNING: This command will {action}.");
+        println!("WARNING: To bypass this confirmation use the --yes flag.");
+        println!("WARNING: Do you want to continue? [y/N]");
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim().to_lowercase();
+        input == "y" || input == "yes"
+    }
+}
+
+/// Takes the lock of a RocksDB database without opening it.
+///
+/// RocksDB locks the `LOCK` file in its directory with a POSIX record lock
+/// for as long as the database is open, so taking the same lock fails while
+/// a node is using the database.  The lock is held until the returned file is
+/// closed.
+fn lock_database(path: &Path) -> anyhow::Result<std::fs::File> {
+    use nix::fcntl::{fcntl, FcntlArg};
+    use nix::libc;
+    use std::os::unix::io::AsRawFd;
+
+    anyhow::ensure!(path.is_dir(), "database {} does not exist", path.display());
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .open(path.join("LOCK"))
+        .with_context(|| format!("failed to open the lock file of {}", path.display()))?;
+    let lock = libc::flock {
+        l_type: libc::F_WRLCK as libc::c_short,
+        l_whence: libc::SEEK_SET as libc::c_short,
+        l_start: 0,
+        l_len: 0,
+        l_pid: 0,
+    };
+    fcntl(file.as_raw_fd(), FcntlArg::F_SETLK(&lock))
+        .with_context(|| format!("database {} is in use by a running node", path.display()))?;
+    Ok(file)
+}
+
+/// Deletes everything in a database directory except for the lock file, so
+/// that a node started meanwhile can't take a new lock.
+fn delete_contents(path: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_name() == "LOCK" {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            std::fs::remove_dir_all(entry.path())?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset_data::ResetDataCommand;
+    use near_store::{DBCol, Mode, NodeStorage, StoreConfig};
+
+    #[test]
+    fn test() {
+        let home_dir = tempfile::tempdir().unwrap();
+        let store_config = StoreConfig::test_config();
+        let opener = NodeStorage::opener(home_dir.path(), false, &store_config, None);
+        {
+            let node_storage = opener.open().unwrap();
+            let mut store_update = node_storage.get_hot_store().store_update();
+            store_update.insert(DBCol::Block, vec![0], vec![42]);
+            store_update.insert(DBCol::CachedContractCode, vec![0], vec![42]);
+            store_update.commit().unwrap();
+        }
+
+        let cmd = ResetDataCommand { derived_only: true, yes: true };
+        cmd.run(home_dir.path(), false, &store_config, None).unwrap();
+        {
+            let node_storage = opener.open_in_mode(Mode::ReadOnly).unwrap();
+            let store = node_storage.get_hot_store();
+            assert!(store.exists(DBCol::Block, &[0]).unwrap());
+            assert!(!store.exists(DBCol::CachedContractCode, &[0]).unwrap());
+        }
+
+        let cmd = ResetDataCommand { derived_only: false, yes: true };
+        cmd.run(home_dir.path(), false, &store_config, None).unwrap();
+        assert!(!opener.path().exists());
+    }
+
+    #[test]
+    fn test_missing_cold_data() {
+        let home_dir = tempfile::tempdir().unwrap();
+        let store_config = StoreConfig::test_config();
+        let opener = NodeStorage::opener(home_dir.path(), true, &store_config, None);
+        opener.open().unwrap();
+
+        let cmd = ResetDataCommand { derived_only: false, yes: true };
+        cmd.run(home_dir.path(), true, &store_config, Some(&store_config)).unwrap();
+        assert!(!opener.path().exists());
+    }
+
+    #[test]
+    fn test_unopenable_data() {
+        let home_dir = tempfile::tempdir().unwrap();
+        let store_config = StoreConfig::test_config();
+        let opener = NodeStorage::opener(home_dir.path(), false, &store_config, None);
+        opener.open().unwrap();
+        std::fs::write(opener.path().join("CURRENT"), b"garbage\n").unwrap();
+        assert!(opener.open_in_mode(Mode::ReadWriteExisting).is_err());
+
+        let cmd = ResetDataCommand { derived_only: false, yes: true };
+        cmd.run(home_dir.path(), false, &store_config, None).unwrap();
+        assert!(!opener.path().exists());
+    }
+}
@@ -19,6 +19,16 @@ pub(crate) fn open_rocksdb(
     Ok(rocksdb)
 }
 
+/// Opens the hot store for reading only.  The database lock isn't taken, so
+/// the database of a running node can be inspected.
+pub(crate) fn open_store_read_only(home: &Path) -> anyhow::Result<Store> {
+    let config = nearcore::config::Config::from_file_skip_validation(
+        &home.join(nearcore::config::CONFIG_FILENAME),
+    )?;
+    let db_path = config.store.path.as_ref().cloned().unwrap_or_else(|| home.join("data"));
+    Ok(Store::open_read_only(&db_path)?)
+}
+
 pub(crate) fn open_state_snapshot(home: &Path, mode: near_store::Mode) -> anyhow::Result<Store> {
     let config = nearcore::config::Config::from_file_skip_validation(
         &home.join(nearcore::config::CONFIG_FILENAME),
@@ -245,6 +245,9 @@ export const CurrentPeersView = ({ addr }: NetworkInfoViewProps) => {
                         <th>Nonce</th>
                         <th>First connection</th>
                         <th>Traffic (last minute)</th>
+                        <th>Invalid messages</th>
+                        <th>Requests served</th>
+                        <th>Avg response latency</th>
                         <th>Route to validators</th>
                     </tr>
                 </thead>
@@ -285,6 +288,13 @@ export const CurrentPeersView = ({ addr }: NetworkInfoViewProps) => {
                                         peer.sent_bytes_per_sec
                                     )}
                                 </td>
+                                <td>{peer.invalid_messages}</td>
+                                <td>{peer.requests_served}</td>
+                                <td>
+                                    {peer.avg_response_latency_millis === null
+                                        ? 'N/A'
+                                        : `${peer.avg_response_latency_millis} ms`}
+                                </td>
                                 <td>
                                     <CollapsableValidatorList validators={routedValidator} />
                                 </td>
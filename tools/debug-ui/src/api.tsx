@@ -87,6 +87,9 @@ export interface PeerInfoView {
     connection_established_time_millis: number;
     is_outbound_peer: boolean;
     nonce: number;
+    invalid_messages: number;
+    requests_served: number;
+    avg_response_latency_millis: number | null;
 }
 
 export interface KnownProducerView {
@@ -15,6 +15,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::PartialMerkleTree;
 use near_primitives::types::EpochId;
 use near_primitives::utils::index_to_bytes;
+use near_store::keys::{BlockInfoKey, EpochInfoKey};
 use near_store::HEADER_HEAD_KEY;
 use near_store::{DBCol, Mode, NodeStorage, Store, StoreUpdate};
 use near_time::Clock;
@@ -86,7 +87,7 @@ fn read_block_checkpoint(store: &Store, block_hash: &CryptoHash) -> BlockCheckpo
         .unwrap_or_else(|| panic!("Key missing Block {}", block_hash));
 
     let info: BlockInfo = store
-        .get_ser(DBCol::BlockInfo, block_hash.as_ref())
+        .get_ser_by_key(&BlockInfoKey(block_hash))
         .unwrap_or_else(|_| panic!("DB error BlockInfo {:?}", block_hash))
         .unwrap_or_else(|| panic!("Key missing BlockInfo {}", block_hash));
 
@@ -105,7 +106,7 @@ fn write_block_checkpoint(store_update: &mut StoreUpdate, block_checkpoint: &Blo
         .expect("Failed writing a header");
 
     store_update
-        .insert_ser(DBCol::BlockInfo, hash.as_ref(), &block_checkpoint.info)
+        .insert_ser_by_key(&BlockInfoKey(hash), &block_checkpoint.info)
         .expect("Failed writing a block info");
 
     store_update
@@ -122,7 +123,7 @@ fn write_block_checkpoint(store_update: &mut StoreUpdate, block_checkpoint: &Blo
 
 fn write_epoch_checkpoint(store_update: &mut StoreUpdate, epoch_checkpoint: &EpochCheckpoint) {
     store_update
-        .set_ser(DBCol::EpochInfo, epoch_checkpoint.id.as_ref(), &epoch_checkpoint.info)
+        .set_ser_by_key(&EpochInfoKey::epoch(&epoch_checkpoint.id), &epoch_checkpoint.info)
         .expect("Failed to write epoch info");
 }
 
@@ -280,7 +281,7 @@ fn load_snapshot(load_cmd: LoadCmd) {
     // If not - we'll have to compute one and put it in the checkpoint.
     let aggregator =
         EpochInfoAggregator::new(snapshot.prev_epoch.id, *snapshot.final_block.header.hash());
-    store_update.set_ser(DBCol::EpochInfo, AGGREGATOR_KEY, &aggregator).unwrap();
+    store_update.set_ser_by_key(&EpochInfoKey::Aggregator, &aggregator).unwrap();
     store_update.commit().unwrap();
 }
 
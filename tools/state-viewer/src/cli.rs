@@ -4,6 +4,7 @@ use crate::contract_accounts::ContractAccountFilter;
 use crate::replay_headers::replay_headers;
 use crate::rocksdb_stats::get_rocksdb_stats;
 use crate::trie_iteration_benchmark::TrieIterationBenchmarkCmd;
+use crate::verify_state_root::VerifyStateRootCmd;
 
 use crate::latest_witnesses::StateWitnessCmd;
 use near_chain::types::RuntimeStorageConfig;
@@ -97,6 +98,10 @@ pub enum StateViewerSubCommand {
     StateStats(StateStatsCmd),
     /// Benchmark how long does it take to iterate the trie.
     TrieIterationBenchmark(TrieIterationBenchmarkCmd),
+    /// Rebuild the state root of a shard from the trie nodes or the flat
+    /// state in the DB, compare it with the state root recorded for the block
+    /// and print the first divergent subtree if they don't match.
+    VerifyStateRoot(VerifyStateRootCmd),
     /// View head of the storage.
     #[clap(alias = "view_chain")]
     ViewChain(ViewChainCmd),
@@ -183,6 +188,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::ViewGenesis(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::ViewTrie(cmd) => cmd.run(store),
             StateViewerSubCommand::TrieIterationBenchmark(cmd) => cmd.run(near_config, store),
+            StateViewerSubCommand::VerifyStateRoot(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::StateWitness(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::CongestionControl(cmd) => cmd.run(home_dir, near_config, store),
         }
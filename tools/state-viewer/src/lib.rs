@@ -18,5 +18,6 @@ mod state_parts;
 mod trie_iteration_benchmark;
 mod tx_dump;
 pub mod util;
+mod verify_state_root;
 
 pub use cli::StateViewerSubCommand;
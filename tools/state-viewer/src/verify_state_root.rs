@@ -0,0 +1,299 @@
+use itertools::{EitherOrBoth, Itertools};
+use near_chain::{ChainStore, ChainStoreAccess};
+use near_epoch_manager::EpochManager;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::state::ValueRef;
+use near_primitives::types::{BlockHeight, ShardId, StateRoot};
+use near_store::adapter::trie_store::TrieStoreAdapter;
+use near_store::adapter::StoreAdapter;
+use near_store::flat::{FlatStorageManager, FlatStorageStatus};
+use near_store::trie::mem::loading::compute_state_root_from_sorted_entries;
+use near_store::trie::Children;
+use near_store::{
+    NibbleSlice, RawTrieNode, RawTrieNodeWithSize, ShardUId, Store, Trie, TrieDBStorage,
+};
+use nearcore::NearConfig;
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+#[clap(rename_all = "kebab_case")]
+pub enum StateRootSource {
+    /// Recompute the hashes of all trie nodes and values stored in the
+    /// `State` column, starting from the expected state root.
+    Trie,
+    /// Build the trie from the `FlatState` column in memory. Only possible
+    /// for the block at the flat storage head.
+    FlatStorage,
+}
+
+#[derive(clap::Parser)]
+pub struct VerifyStateRootCmd {
+    /// Height of the block to check. Defaults to the chain head, or to the
+    /// flat storage head for `--source flat-storage`.
+    #[clap(long)]
+    height: Option<BlockHeight>,
+    #[clap(long)]
+    shard_id: ShardId,
+    /// What the state root should be rebuilt from.
+    #[clap(long, default_value = "trie")]
+    source: StateRootSource,
+}
+
+impl VerifyStateRootCmd {
+    pub fn run(self, near_config: NearConfig, store: Store) {
+        let genesis_config = &near_config.genesis.config;
+        let chain_store = ChainStore::new(
+            store.clone(),
+            genesis_config.genesis_height,
+            near_config.client_config.save_trie_changes,
+        );
+        let epoch_manager =
+            EpochManager::new_from_genesis_config(store.clone(), genesis_config).unwrap();
+
+        let block_hash = match self.height {
+            Some(height) => chain_store.get_block_hash_by_height(height).unwrap(),
+            None => chain_store.head().unwrap().last_block_hash,
+        };
+        let epoch_id = *chain_store.get_block_header(&block_hash).unwrap().epoch_id();
+        let shard_layout = epoch_manager.get_shard_layout(&epoch_id).unwrap();
+        let shard_uid = ShardUId::from_shard_id_and_layout(self.shard_id, &shard_layout);
+
+        let block_hash = match self.source {
+            StateRootSource::Trie => block_hash,
+            StateRootSource::FlatStorage => {
+                let flat_head = match store.flat_store().get_flat_storage_status(shard_uid) {
+                    Ok(FlatStorageStatus::Ready(status)) => status.flat_head,
+                    status => panic!("Flat storage for {shard_uid} is not ready: {status:?}"),
+                };
+                if self.height.is_some_and(|height| height != flat_head.height) {
+                    panic!(
+                        "Flat storage for {shard_uid} only holds the state at its head, height {}",
+                        flat_head.height
+                    );
+                }
+                flat_head.hash
+            }
+        };
+        let expected_root =
+            *chain_store.get_chunk_extra(&block_hash, &shard_uid).unwrap().state_root();
+        println!("Verifying state root {expected_root} of {shard_uid} at block {block_hash}");
+
+        let timer = Instant::now();
+        let result = match self.source {
+            StateRootSource::Trie => verify_trie(store.trie_store(), shard_uid, expected_root),
+            StateRootSource::FlatStorage => {
+                verify_flat_storage(&store, shard_uid, block_hash, expected_root)
+            }
+        };
+        match result {
+            Ok(()) => println!("{:.2?}: state root {expected_root} matches", timer.elapsed()),
+            Err(divergence) => {
+                println!("{:.2?}: state root {expected_root} does not match", timer.elapsed());
+                println!("{divergence}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// The first place found where the stored state stops agreeing with the
+/// expected state root. Paths are given as hex nibbles from the trie root.
+#[derive(Debug)]
+enum Divergence {
+    /// A trie node or value referenced by its parent can't be read.
+    Missing { path: String, hash: CryptoHash, error: String },
+    /// A trie node is present but can't be decoded.
+    Undecodable { path: String, hash: CryptoHash },
+    /// The stored bytes don't hash to the hash under which they are stored.
+    HashMismatch { path: String, expected: CryptoHash, actual: CryptoHash },
+    /// A value has a length different from the one in its parent node.
+    LengthMismatch { path: String, hash: CryptoHash, expected: u32, actual: usize },
+    /// The trie rebuilt from flat storage has a different root.
+    FlatStateRootMismatch { actual: StateRoot, first_differing_key: Option<String> },
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { path, hash, error } => {
+                write!(f, "missing node or value {hash} at path '{path}': {error}")
+            }
+            Self::Undecodable { path, hash } => {
+                write!(f, "node {hash} at path '{path}' cannot be decoded")
+            }
+            Self::HashMismatch { path, expected, actual } => {
+                write!(
+                    f,
+                    "subtree at path '{path}' should hash to {expected}, but hashes to {actual}"
+                )
+            }
+            Self::LengthMismatch { path, hash, expected, actual } => {
+                write!(f, "value {hash} at path '{path}' has length {actual}, expected {expected}")
+            }
+            Self::FlatStateRootMismatch { actual, first_differing_key } => {
+                write!(f, "trie built from flat storage has root {actual}")?;
+                match first_differing_key {
+                    Some(key) => write!(f, ", first key differing from the trie: {key}"),
+                    None => write!(f, ", but all keys and values match the trie"),
+                }
+            }
+        }
+    }
+}
+
+fn nibbles_to_string(nibbles: &[u8]) -> String {
+    nibbles.iter().map(|nibble| format!("{nibble:x}")).collect()
+}
+
+fn key_to_string(key: &[u8]) -> String {
+    key.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Walks the trie stored in the `State` column from `expected_root` and checks
+/// that every node and value hashes to the hash its parent refers to it by,
+/// which is equivalent to rebuilding the root from the stored nodes bottom-up.
+fn verify_trie(
+    store: TrieStoreAdapter,
+    shard_uid: ShardUId,
+    expected_root: StateRoot,
+) -> Result<(), Divergence> {
+    if expected_root == Trie::EMPTY_ROOT {
+        return Ok(());
+    }
+    let mut verifier = TrieVerifier { store, shard_uid, path: vec![], num_nodes: 0 };
+    let result = verifier.verify_node(&expected_root);
+    println!("Visited {} trie nodes", verifier.num_nodes);
+    result
+}
+
+struct TrieVerifier {
+    store: TrieStoreAdapter,
+    shard_uid: ShardUId,
+    /// Nibbles leading from the root to the node being verified.
+    path: Vec<u8>,
+    num_nodes: u64,
+}
+
+impl TrieVerifier {
+    fn read(&self, expected: &CryptoHash) -> Result<Arc<[u8]>, Divergence> {
+        let bytes =
+            self.store.get(self.shard_uid, expected).map_err(|err| Divergence::Missing {
+                path: nibbles_to_string(&self.path),
+                hash: *expected,
+                error: err.to_string(),
+            })?;
+        let actual = hash(&bytes);
+        if actual != *expected {
+            return Err(Divergence::HashMismatch {
+                path: nibbles_to_string(&self.path),
+                expected: *expected,
+                actual,
+            });
+        }
+        Ok(bytes)
+    }
+
+    fn verify_value(&self, value: &ValueRef) -> Result<(), Divergence> {
+        let bytes = self.read(&value.hash)?;
+        if bytes.len() != value.len() {
+            return Err(Divergence::LengthMismatch {
+                path: nibbles_to_string(&self.path),
+                hash: value.hash,
+                expected: value.length,
+                actual: bytes.len(),
+            });
+        }
+        Ok(())
+    }
+
+    fn verify_node(&mut self, node_hash: &CryptoHash) -> Result<(), Divergence> {
+        let bytes = self.read(node_hash)?;
+        let node = borsh::from_slice::<RawTrieNodeWithSize>(&bytes).map_err(|_| {
+            Divergence::Undecodable { path: nibbles_to_string(&self.path), hash: *node_hash }
+        })?;
+        self.num_nodes += 1;
+        if self.num_nodes % 1_000_000 == 0 {
+            let path = nibbles_to_string(&self.path);
+            tracing::info!(target: "state_viewer", num_nodes = self.num_nodes, %path, "Verifying trie");
+        }
+
+        let path_len = self.path.len();
+        let result = match &node.node {
+            RawTrieNode::Leaf(key, value) => {
+                self.path.extend(NibbleSlice::from_encoded(key).0.iter());
+                self.verify_value(value)
+            }
+            RawTrieNode::Extension(key, child) => {
+                self.path.extend(NibbleSlice::from_encoded(key).0.iter());
+                self.verify_node(child)
+            }
+            RawTrieNode::BranchNoValue(children) => self.verify_children(children),
+            RawTrieNode::BranchWithValue(value, children) => {
+                self.verify_value(value).and_then(|()| self.verify_children(children))
+            }
+        };
+        self.path.truncate(path_len);
+        result
+    }
+
+    fn verify_children(&mut self, children: &Children) -> Result<(), Divergence> {
+        for (nibble, child) in children.iter() {
+            self.path.push(nibble);
+            let result = self.verify_node(child);
+            self.path.pop();
+            result?;
+        }
+        Ok(())
+    }
+}
+
+/// Rebuilds the trie from the flat storage of the shard in memory and compares
+/// its root with `expected_root`. On mismatch, walks the flat storage and the
+/// stored trie side by side to find the first key where they disagree.
+fn verify_flat_storage(
+    store: &Store,
+    shard_uid: ShardUId,
+    block_hash: CryptoHash,
+    expected_root: StateRoot,
+) -> Result<(), Divergence> {
+    let flat_storage_manager = FlatStorageManager::new(store.flat_store());
+    flat_storage_manager.create_flat_storage_for_shard(shard_uid).unwrap();
+    let chunk_view = flat_storage_manager.chunk_view(shard_uid, block_hash).unwrap();
+    let actual =
+        compute_state_root_from_sorted_entries(shard_uid, chunk_view.iter_range(None, None))
+            .unwrap();
+    if actual == expected_root {
+        return Ok(());
+    }
+
+    let trie_storage = TrieDBStorage::new(store.trie_store(), shard_uid);
+    let trie = Trie::new(Arc::new(trie_storage), expected_root, None);
+    let trie_iter = trie.disk_iter().unwrap().map(|item| {
+        let (key, value) = item.unwrap();
+        (key, ValueRef::new(&value))
+    });
+    let flat_iter = chunk_view.iter_range(None, None).map(|item| {
+        let (key, value) = item.unwrap();
+        (key, value.to_value_ref())
+    });
+    let first_differing_key = trie_iter
+        .merge_join_by(flat_iter, |(trie_key, _), (flat_key, _)| trie_key.cmp(flat_key))
+        .find_map(|item| match item {
+            EitherOrBoth::Both((key, trie_value), (_, flat_value)) => {
+                (trie_value != flat_value).then(|| {
+                    format!(
+                        "{} has value {trie_value:?} in the trie and {flat_value:?} in flat storage",
+                        key_to_string(&key)
+                    )
+                })
+            }
+            EitherOrBoth::Left((key, _)) => {
+                Some(format!("{} is missing from flat storage", key_to_string(&key)))
+            }
+            EitherOrBoth::Right((key, _)) => {
+                Some(format!("{} is missing from the trie", key_to_string(&key)))
+            }
+        });
+    Err(Divergence::FlatStateRootMismatch { actual, first_differing_key })
+}